@@ -38,8 +38,57 @@ pub struct PlayerStats {
     pub biggest_pot: u64,
     pub chain_id: String,
     pub last_updated: u64,
+    /// Current run of consecutive wins (0 while on a losing streak)
+    pub win_streak: u32,
+    /// Current run of consecutive losses (0 while on a winning streak)
+    pub loss_streak: u32,
+    pub longest_win_streak: u32,
+    pub longest_loss_streak: u32,
 }
 
+impl PlayerStats {
+    /// Reject stats whose biggest pot exceeds what the game chain recorded
+    pub fn validate_biggest_pot(&self, recorded_biggest_pot: u64) -> Result<(), ArenaError> {
+        if self.biggest_pot > recorded_biggest_pot {
+            return Err(ArenaError::BiggestPotExceedsRecord {
+                name: self.name.clone(),
+                submitted: self.biggest_pot,
+                recorded: recorded_biggest_pot,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Why the arena rejected submitted stats
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ArenaError {
+    /// Biggest pot is larger than the game chain's own record
+    BiggestPotExceedsRecord {
+        name: String,
+        submitted: u64,
+        recorded: u64,
+    },
+}
+
+impl std::fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArenaError::BiggestPotExceedsRecord {
+                name,
+                submitted,
+                recorded,
+            } => write!(
+                f,
+                "Submitted biggest pot {} exceeds recorded {} for {}",
+                submitted, recorded, name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArenaError {}
+
 // ============================================================================
 // Operations
 // ============================================================================
@@ -47,14 +96,18 @@ pub struct PlayerStats {
 /// Arena operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Operation {
-    /// Submit player stats to leaderboard
+    /// Submit player stats to leaderboard; the biggest pot only arrives
+    /// from the game chain via `Message::ValidateStats`
     SubmitStats {
         chain_id: ChainId,
         name: String,
         chips: u64,
         hands_won: u64,
         hands_played: u64,
-        biggest_pot: u64,
+        win_streak: u32,
+        loss_streak: u32,
+        longest_win_streak: u32,
+        longest_loss_streak: u32,
     },
     /// Add authorized game chain (admin only)
     AddGameChain { chain_id: ChainId },
@@ -77,6 +130,16 @@ pub enum Message {
         hands_won: u64,
         hands_played: u64,
         biggest_pot: u64,
+        win_streak: u32,
+        loss_streak: u32,
+        longest_win_streak: u32,
+        longest_loss_streak: u32,
+    },
+    /// Game chain's recorded biggest pot, checked against submitted stats
+    ValidateStats {
+        source_chain: ChainId,
+        name: String,
+        biggest_pot_won: u64,
     },
 }
 
@@ -90,3 +153,36 @@ pub struct Parameters {
     /// Admin chain ID that can manage authorized chains
     pub admin_chain_id: ChainId,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(biggest_pot: u64) -> PlayerStats {
+        PlayerStats {
+            name: "alice".to_string(),
+            biggest_pot,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recorded_biggest_pot_is_accepted() {
+        assert!(stats(800).validate_biggest_pot(800).is_ok());
+        assert!(stats(0).validate_biggest_pot(800).is_ok());
+    }
+
+    #[test]
+    fn test_inflated_biggest_pot_is_rejected() {
+        let err = stats(5000).validate_biggest_pot(800).unwrap_err();
+        assert_eq!(
+            err,
+            ArenaError::BiggestPotExceedsRecord {
+                name: "alice".to_string(),
+                submitted: 5000,
+                recorded: 800,
+            }
+        );
+        assert!(err.to_string().contains("exceeds recorded 800"));
+    }
+}
@@ -10,4 +10,6 @@ pub mod service;
 pub mod state;
 
 // Re-export types from poker-arena-types
-pub use poker_arena_types::{Message, Operation, Parameters, PlayerStats, PokerArenaAbi};
+pub use poker_arena_types::{
+    ArenaError, Message, Operation, Parameters, PlayerStats, PokerArenaAbi,
+};
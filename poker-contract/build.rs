@@ -0,0 +1,468 @@
+//! Generates the pre-flop tables for the 169 starting hand classes: equity
+//! against a random hand, head-to-head equity between classes, and the
+//! push/fold equilibria derived from it, heads-up and with more players
+//! behind.
+//!
+//! Equities are estimated by Monte Carlo with a fixed seed, so the output is
+//! identical on every build. The evaluator below is a compact standalone
+//! 7-card scorer; the contract itself uses `HandEvaluator`.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const ITERATIONS: u32 = 4000;
+const SEED: u64 = 0x5EED_F00D;
+
+/// Run-outs per head-to-head class matchup
+const MATCHUP_ITERATIONS: u32 = 300;
+/// Fictitious play rounds per stack depth when solving push/fold
+const NASH_ROUNDS: u32 = 400;
+/// Push/fold is solved for 1 to 20 big blinds in half big blind steps
+const NASH_MAX_STACK_HALF_BB: u32 = 40;
+/// Fictitious play rounds per stack depth for jams with callers behind
+const MULTI_WAY_ROUNDS: u32 = 50;
+/// Multi-way jams are solved for 2 up to this many players behind
+const MULTI_WAY_MAX_BEHIND: u8 = 9;
+/// Blinds in the middle when a player opens all-in
+const DEAD_BLINDS_BB: f64 = 1.5;
+
+const RANK_NAMES: [&str; 15] = [
+    "", "", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack",
+    "Queen", "King", "Ace",
+];
+
+type Card = (u8, u8);
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut rng = SplitMix(SEED);
+    let mut table = String::new();
+    table.push_str("// @generated by build.rs - do not edit\n\n");
+    table.push_str("pub const EQUITY_TABLE: &[(StartingHandClass, f32)] = &[\n");
+
+    for high in (2..=14u8).rev() {
+        for low in (2..=high).rev() {
+            let suited_options: &[bool] = if high == low {
+                &[false]
+            } else {
+                &[true, false]
+            };
+            for &suited in suited_options {
+                let hero = [(high, 0), (low, if suited { 0 } else { 1 })];
+                let equity = simulate(&hero, &mut rng);
+                writeln!(
+                    table,
+                    "    (StartingHandClass {{ high: Rank::{}, low: Rank::{}, suited: {} }}, {:.4}),",
+                    RANK_NAMES[high as usize], RANK_NAMES[low as usize], suited, equity
+                )
+                .unwrap();
+            }
+        }
+    }
+    table.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("generated_equity_table.rs"), table).unwrap();
+
+    let classes = hand_classes();
+    let equity = head_to_head_equity(&classes, &mut rng);
+    let (push_max, call_max) = solve_push_fold(&classes, &equity);
+
+    let mut push_fold = String::new();
+    push_fold.push_str("// @generated by build.rs - do not edit\n\n");
+    push_fold.push_str(
+        "/// Row hand's equity against column hand, scaled to 0..=255, in EQUITY_TABLE order\n",
+    );
+    writeln!(
+        push_fold,
+        "pub static HEADS_UP_EQUITY: [u8; {}] = [",
+        equity.len() * equity.len()
+    )
+    .unwrap();
+    for row in &equity {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|e| ((e * 255.0).round() as u8).to_string())
+            .collect();
+        writeln!(push_fold, "    {},", cells.join(", ")).unwrap();
+    }
+    push_fold.push_str("];\n\n");
+    push_fold
+        .push_str("/// Deepest stack in big blinds at which the small blind jams, 0.0 for never\n");
+    write_stack_table(&mut push_fold, "NASH_PUSH_MAX_BB", &push_max);
+    push_fold.push_str(
+        "/// Deepest stack in big blinds at which the big blind calls a jam, 0.0 for never\n",
+    );
+    write_stack_table(&mut push_fold, "NASH_CALL_MAX_BB", &call_max);
+    writeln!(
+        push_fold,
+        "/// Most players behind a multi-way jam table covers\npub const MULTI_WAY_MAX_BEHIND: u8 = {};\n",
+        MULTI_WAY_MAX_BEHIND
+    )
+    .unwrap();
+    let multi_way: Vec<Vec<f64>> = (2..=MULTI_WAY_MAX_BEHIND)
+        .map(|behind| solve_multi_way_push(&classes, &equity, behind))
+        .collect();
+    push_fold.push_str(
+        "/// Deepest stack in big blinds at which an earlier seat jams, 0.0 for never;\n/// row `behind - 2` for the players left to act\n",
+    );
+    write_stack_tables(&mut push_fold, "NASH_PUSH_MULTI_WAY_MAX_BB", &multi_way);
+    fs::write(
+        Path::new(&out_dir).join("generated_push_fold.rs"),
+        push_fold,
+    )
+    .unwrap();
+}
+
+/// (high, low, suited) in the same order as EQUITY_TABLE
+fn hand_classes() -> Vec<(u8, u8, bool)> {
+    let mut classes = Vec::new();
+    for high in (2..=14u8).rev() {
+        for low in (2..=high).rev() {
+            if high == low {
+                classes.push((high, low, false));
+            } else {
+                classes.push((high, low, true));
+                classes.push((high, low, false));
+            }
+        }
+    }
+    classes
+}
+
+fn combos(class: (u8, u8, bool)) -> f64 {
+    match class {
+        (high, low, _) if high == low => 6.0,
+        (_, _, true) => 4.0,
+        _ => 12.0,
+    }
+}
+
+/// Concrete cards for a class that avoid the cards already in use
+fn representative(class: (u8, u8, bool), used: &[Card]) -> [Card; 2] {
+    let (high, low, suited) = class;
+    for first in 0..4u8 {
+        for second in 0..4u8 {
+            if suited != (first == second) || (high == low && first >= second) {
+                continue;
+            }
+            let cards = [(high, first), (low, second)];
+            if !cards.iter().any(|card| used.contains(card)) {
+                return cards;
+            }
+        }
+    }
+    unreachable!("two hands never exhaust a rank")
+}
+
+fn head_to_head_equity(classes: &[(u8, u8, bool)], rng: &mut SplitMix) -> Vec<Vec<f64>> {
+    let mut equity = vec![vec![0.5; classes.len()]; classes.len()];
+    for i in 0..classes.len() {
+        for j in (i + 1)..classes.len() {
+            let hero = representative(classes[i], &[]);
+            let villain = representative(classes[j], &hero);
+            let mut deck: Vec<Card> = (0..4u8)
+                .flat_map(|suit| (2..=14u8).map(move |rank| (rank, suit)))
+                .filter(|card| !hero.contains(card) && !villain.contains(card))
+                .collect();
+
+            let mut won = 0.0;
+            for _ in 0..MATCHUP_ITERATIONS {
+                for k in 0..5 {
+                    let swap = k + (rng.next() % (deck.len() - k) as u64) as usize;
+                    deck.swap(k, swap);
+                }
+                let mut ours = hero.to_vec();
+                ours.extend_from_slice(&deck[..5]);
+                let mut theirs = villain.to_vec();
+                theirs.extend_from_slice(&deck[..5]);
+
+                let (ours, theirs) = (score(&ours), score(&theirs));
+                if ours > theirs {
+                    won += 1.0;
+                } else if ours == theirs {
+                    won += 0.5;
+                }
+            }
+            equity[i][j] = won / MATCHUP_ITERATIONS as f64;
+            equity[j][i] = 1.0 - equity[i][j];
+        }
+    }
+    equity
+}
+
+/// Heads-up jam-or-fold by fictitious play at each stack depth. The small
+/// blind posts 0.5 and jams or folds; the big blind posts 1 and calls or
+/// folds. Returns the deepest stack at which each class jams and calls.
+fn solve_push_fold(classes: &[(u8, u8, bool)], equity: &[Vec<f64>]) -> (Vec<f64>, Vec<f64>) {
+    let weights: Vec<f64> = classes.iter().map(|class| combos(*class)).collect();
+    let total_weight: f64 = weights.iter().sum();
+    let mut push_max = vec![0.0; classes.len()];
+    let mut call_max = vec![0.0; classes.len()];
+
+    for half_bb in 2..=NASH_MAX_STACK_HALF_BB {
+        let stack = half_bb as f64 / 2.0;
+        let mut push = vec![1.0; classes.len()];
+        let mut call = vec![0.0; classes.len()];
+
+        for round in 1..=NASH_ROUNDS {
+            let step = 1.0 / round as f64;
+            let pushing: f64 = (0..classes.len()).map(|h| weights[h] * push[h]).sum();
+            for j in 0..classes.len() {
+                let called_ev: f64 = (0..classes.len())
+                    .map(|h| weights[h] * push[h] * (equity[j][h] * 2.0 * stack - stack))
+                    .sum::<f64>()
+                    / pushing.max(f64::MIN_POSITIVE);
+                let best = if called_ev > -1.0 { 1.0 } else { 0.0 };
+                call[j] += (best - call[j]) * step;
+            }
+            for h in 0..classes.len() {
+                let jam_ev: f64 = (0..classes.len())
+                    .map(|j| {
+                        weights[j]
+                            * (call[j] * (equity[h][j] * 2.0 * stack - stack) + (1.0 - call[j]))
+                    })
+                    .sum::<f64>()
+                    / total_weight;
+                let best = if jam_ev > -0.5 { 1.0 } else { 0.0 };
+                push[h] += (best - push[h]) * step;
+            }
+        }
+
+        for h in 0..classes.len() {
+            if push[h] > 0.5 {
+                push_max[h] = stack;
+            }
+            if call[h] > 0.5 {
+                call_max[h] = stack;
+            }
+        }
+    }
+    (push_max, call_max)
+}
+
+/// Jam-or-fold with `behind` players left to act, each calling on their
+/// own: an approximation of the multi-way equilibrium by fictitious play at
+/// each stack depth. Returns the deepest stack at which each class jams.
+fn solve_multi_way_push(classes: &[(u8, u8, bool)], equity: &[Vec<f64>], behind: u8) -> Vec<f64> {
+    let weights: Vec<f64> = classes.iter().map(|class| combos(*class)).collect();
+    let total_weight: f64 = weights.iter().sum();
+    let mut push_max = vec![0.0; classes.len()];
+
+    for half_bb in 2..=NASH_MAX_STACK_HALF_BB {
+        let stack = half_bb as f64 / 2.0;
+        let mut push = vec![1.0; classes.len()];
+        let mut call = vec![0.0; classes.len()];
+
+        for round in 1..=MULTI_WAY_ROUNDS {
+            let step = 1.0 / round as f64;
+            for j in 0..classes.len() {
+                let called = weighted_equity(&equity[j], &push, &weights);
+                let best = if called * 2.0 * stack - stack > -1.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+                call[j] += (best - call[j]) * step;
+            }
+
+            let call_frequency = (0..classes.len())
+                .map(|j| weights[j] * call[j])
+                .sum::<f64>()
+                / total_weight;
+            let nobody_calls = (1.0 - call_frequency).powi(behind as i32);
+            for h in 0..classes.len() {
+                let called = weighted_equity(&equity[h], &call, &weights)
+                    * (2.0 * stack + DEAD_BLINDS_BB)
+                    - stack;
+                let jam = nobody_calls * DEAD_BLINDS_BB + (1.0 - nobody_calls) * called;
+                let best = if jam > 0.0 { 1.0 } else { 0.0 };
+                push[h] += (best - push[h]) * step;
+            }
+        }
+
+        for h in 0..classes.len() {
+            if push[h] > 0.5 {
+                push_max[h] = stack;
+            }
+        }
+    }
+    push_max
+}
+
+/// Equity from one row of the head-to-head table against a mixed range,
+/// where `frequency` is how often each class is in it
+fn weighted_equity(row: &[f64], frequency: &[f64], weights: &[f64]) -> f64 {
+    let total: f64 = (0..row.len()).map(|v| weights[v] * frequency[v]).sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+    (0..row.len())
+        .map(|v| weights[v] * frequency[v] * row[v])
+        .sum::<f64>()
+        / total
+}
+
+fn write_stack_tables(out: &mut String, name: &str, rows: &[Vec<f64>]) {
+    let width = rows.first().map_or(0, Vec::len);
+    writeln!(
+        out,
+        "pub const {}: [[f32; {}]; {}] = [",
+        name,
+        width,
+        rows.len()
+    )
+    .unwrap();
+    for row in rows {
+        out.push_str("    [\n");
+        for chunk in row.chunks(13) {
+            let cells: Vec<String> = chunk.iter().map(|value| format!("{:.1}", value)).collect();
+            writeln!(out, "        {},", cells.join(", ")).unwrap();
+        }
+        out.push_str("    ],\n");
+    }
+    out.push_str("];\n\n");
+}
+
+fn write_stack_table(out: &mut String, name: &str, values: &[f64]) {
+    writeln!(out, "pub const {}: [f32; {}] = [", name, values.len()).unwrap();
+    for chunk in values.chunks(13) {
+        let cells: Vec<String> = chunk.iter().map(|value| format!("{:.1}", value)).collect();
+        writeln!(out, "    {},", cells.join(", ")).unwrap();
+    }
+    out.push_str("];\n\n");
+}
+
+fn simulate(hero: &[Card; 2], rng: &mut SplitMix) -> f64 {
+    let mut deck: Vec<Card> = (0..4u8)
+        .flat_map(|suit| (2..=14u8).map(move |rank| (rank, suit)))
+        .filter(|card| !hero.contains(card))
+        .collect();
+
+    let mut won = 0.0;
+    for _ in 0..ITERATIONS {
+        for i in 0..7 {
+            let j = i + (rng.next() % (deck.len() - i) as u64) as usize;
+            deck.swap(i, j);
+        }
+        let board = &deck[2..7];
+        let mut ours = hero.to_vec();
+        ours.extend_from_slice(board);
+        let mut theirs = deck[0..2].to_vec();
+        theirs.extend_from_slice(board);
+
+        let (ours, theirs) = (score(&ours), score(&theirs));
+        if ours > theirs {
+            won += 1.0;
+        } else if ours == theirs {
+            won += 0.5;
+        }
+    }
+    won / ITERATIONS as f64
+}
+
+/// Category in bits 20+, tie-breaking ranks packed four bits each below
+fn score(cards: &[Card]) -> u32 {
+    let mut counts = [0u8; 15];
+    let mut rank_mask = 0u16;
+    let mut suit_masks = [0u16; 4];
+    for &(rank, suit) in cards {
+        counts[rank as usize] += 1;
+        rank_mask |= 1 << rank;
+        suit_masks[suit as usize] |= 1 << rank;
+    }
+
+    let mut flush = None;
+    for mask in suit_masks {
+        if mask.count_ones() >= 5 {
+            if let Some(high) = straight_high(mask) {
+                return (8 << 20) | high;
+            }
+            flush = Some(pack(top_ranks(mask, 5)));
+        }
+    }
+
+    let by_count = |n: u8| -> Vec<u32> {
+        (2..=14u32)
+            .rev()
+            .filter(|&r| counts[r as usize] == n)
+            .collect()
+    };
+    let quads = by_count(4);
+    let trips = by_count(3);
+    let pairs = by_count(2);
+
+    if let Some(&quad) = quads.first() {
+        let kicker = top_ranks(rank_mask & !(1 << quad), 1);
+        return (7 << 20) | (quad << 4) | pack(kicker);
+    }
+    if let Some(&trip) = trips.first() {
+        let pair = trips
+            .get(1)
+            .copied()
+            .into_iter()
+            .chain(pairs.first().copied())
+            .max();
+        if let Some(pair) = pair {
+            return (6 << 20) | (trip << 4) | pair;
+        }
+    }
+    if let Some(flush) = flush {
+        return (5 << 20) | flush;
+    }
+    if let Some(high) = straight_high(rank_mask) {
+        return (4 << 20) | high;
+    }
+    if let Some(&trip) = trips.first() {
+        let kickers = top_ranks(rank_mask & !(1 << trip), 2);
+        return (3 << 20) | (trip << 8) | pack(kickers);
+    }
+    if pairs.len() >= 2 {
+        let kicker = top_ranks(rank_mask & !(1 << pairs[0]) & !(1 << pairs[1]), 1);
+        return (2 << 20) | (pairs[0] << 8) | (pairs[1] << 4) | pack(kicker);
+    }
+    if let Some(&pair) = pairs.first() {
+        let kickers = top_ranks(rank_mask & !(1 << pair), 3);
+        return (1 << 20) | (pair << 12) | pack(kickers);
+    }
+    pack(top_ranks(rank_mask, 5))
+}
+
+fn straight_high(mask: u16) -> Option<u32> {
+    // The ace also plays low in the wheel
+    let mask = if mask & (1 << 14) != 0 {
+        mask | (1 << 1)
+    } else {
+        mask
+    };
+    (5..=14u32)
+        .rev()
+        .find(|&high| (mask >> (high - 4)) & 0b11111 == 0b11111)
+}
+
+fn top_ranks(mask: u16, count: usize) -> Vec<u32> {
+    (2..=14u32)
+        .rev()
+        .filter(|&r| mask & (1 << r) != 0)
+        .take(count)
+        .collect()
+}
+
+fn pack(ranks: Vec<u32>) -> u32 {
+    ranks.iter().fold(0, |acc, rank| (acc << 4) | rank)
+}
+
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,751 @@
+//! Heuristic action advice for new players.
+//!
+//! Recommendations come from a quick Monte Carlo equity estimate against
+//! random hands compared with the pot odds on offer. This is a teaching aid,
+//! not a solver: it ignores ranges, position and future streets.
+//!
+//! Short-stack push/fold advice is the exception: it looks up the jam-or-fold
+//! equilibria solved by `build.rs`, heads-up for the small blind and one
+//! table per number of players behind for earlier seats.
+
+use crate::hand_evaluator::HandEvaluator;
+use crate::preflop::{StartingHandClass, EQUITY_TABLE};
+use crate::stats::aggression_frequency;
+use poker_types::{ActionFrequency, Card, PlayerAction, PokerError, PokerState, Rank};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+include!(concat!(env!("OUT_DIR"), "/generated_push_fold.rs"));
+
+/// Simulated runouts per recommendation
+pub const EQUITY_ITERATIONS: u32 = 1000;
+
+/// Equity above which the advisor suggests betting the pot
+const VALUE_BET_EQUITY: f64 = 0.65;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendedAction {
+    pub action: PlayerAction,
+    pub confidence: f32,
+    pub reasoning: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn best_action_by_equity(
+    player_name: &str,
+    hole_cards: &[Card; 2],
+    board: &[Card],
+    active_opponents: usize,
+    current_bet: u64,
+    pot: u64,
+    stack: u64,
+    seed: [u8; 32],
+) -> RecommendedAction {
+    let equity = monte_carlo_equity(
+        hole_cards,
+        board,
+        active_opponents.max(1),
+        seed,
+        EQUITY_ITERATIONS,
+    );
+    let to_call = current_bet.min(stack);
+    let required = if to_call == 0 {
+        0.0
+    } else {
+        to_call as f64 / (pot + to_call) as f64
+    };
+
+    let action = if equity > VALUE_BET_EQUITY && pot > 0 && pot <= stack {
+        if current_bet == 0 {
+            PlayerAction::Bet(pot)
+        } else {
+            PlayerAction::Raise(pot)
+        }
+    } else if to_call == 0 {
+        PlayerAction::Check
+    } else if equity >= required {
+        PlayerAction::Call
+    } else {
+        PlayerAction::Fold
+    };
+
+    let margin = match action {
+        PlayerAction::Bet(_) | PlayerAction::Raise(_) => equity - VALUE_BET_EQUITY,
+        _ => (equity - required).abs(),
+    };
+    let confidence = (0.5 + margin).clamp(0.0, 1.0) as f32;
+
+    RecommendedAction {
+        action,
+        confidence,
+        reasoning: format!(
+            "{}: {:.0}% equity against {} random hand(s), {:.0}% needed to call (heuristic)",
+            player_name,
+            equity * 100.0,
+            active_opponents.max(1),
+            required * 100.0
+        ),
+    }
+}
+
+/// Estimate the share of the pot `hole_cards` win against `opponents`
+/// random hands. Ties are credited fractionally.
+pub fn monte_carlo_equity(
+    hole_cards: &[Card],
+    board: &[Card],
+    opponents: usize,
+    seed: [u8; 32],
+    iterations: u32,
+) -> f64 {
+    let stub: Vec<Card> = PokerState::create_deck()
+        .into_iter()
+        .filter(|card| !hole_cards.contains(card) && !board.contains(card))
+        .collect();
+    let board_needed = 5usize.saturating_sub(board.len());
+    if iterations == 0 || stub.len() < board_needed + opponents * 2 {
+        return 0.0;
+    }
+
+    let mut rng = SeededRng::new(seed);
+    let mut won = 0.0;
+    for _ in 0..iterations {
+        let mut deck = stub.clone();
+        rng.partial_shuffle(&mut deck, board_needed + opponents * 2);
+
+        let mut full_board = board.to_vec();
+        full_board.extend_from_slice(&deck[..board_needed]);
+
+        let mut cards = hole_cards.to_vec();
+        cards.extend_from_slice(&full_board);
+        let hero = HandEvaluator::evaluate_best_hand(&cards);
+
+        let mut beaten = false;
+        let mut tied = 0;
+        for opponent in 0..opponents {
+            let start = board_needed + opponent * 2;
+            let mut cards = deck[start..start + 2].to_vec();
+            cards.extend_from_slice(&full_board);
+            let villain = HandEvaluator::evaluate_best_hand(&cards);
+            if villain > hero {
+                beaten = true;
+                break;
+            }
+            if villain == hero {
+                tied += 1;
+            }
+        }
+        if !beaten {
+            won += 1.0 / (tied + 1) as f64;
+        }
+    }
+    won / iterations as f64
+}
+
+/// Standard solver bet sizes, as a share of the pot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BetStrategy {
+    Polarized33,
+    Polarized50,
+    Polarized75,
+    Polarized100,
+    Merged25,
+    Merged40,
+    Overbet120,
+    Overbet150,
+}
+
+impl BetStrategy {
+    pub fn percentage(&self) -> u64 {
+        match self {
+            BetStrategy::Polarized33 => 33,
+            BetStrategy::Polarized50 => 50,
+            BetStrategy::Polarized75 => 75,
+            BetStrategy::Polarized100 => 100,
+            BetStrategy::Merged25 => 25,
+            BetStrategy::Merged40 => 40,
+            BetStrategy::Overbet120 => 120,
+            BetStrategy::Overbet150 => 150,
+        }
+    }
+}
+
+pub fn gto_bet_sizing(pot: u64, strategy: BetStrategy) -> u64 {
+    pot * strategy.percentage() / 100
+}
+
+/// Overbet the nuts, bet big with strong hands, bet small with thin value
+/// and check the rest. Never more than the remaining stack.
+pub fn optimal_bet_size_for_equity(equity: f64, pot: u64, stack: u64) -> u64 {
+    let strategy = if equity > 0.85 {
+        BetStrategy::Overbet150
+    } else if equity >= 0.65 {
+        BetStrategy::Polarized75
+    } else if equity >= 0.5 {
+        BetStrategy::Merged40
+    } else {
+        return 0;
+    };
+    gto_bet_sizing(pot, strategy).min(stack)
+}
+
+/// Fold-to-bet rate assumed for a defender with no history
+const DEFAULT_FOLD_TO_BET: f64 = 0.4;
+
+/// Chance the defender folds to a bet of `bet_size_fraction` times the pot.
+///
+/// Starts from how often the defender folds when facing a bet (checks and
+/// their own bets are not responses), scales it up for bigger bets, and down
+/// for aggressors who bet so often that their bets carry less weight.
+pub fn estimate_fold_equity(
+    aggressor_stats: &ActionFrequency,
+    defender_stats: &ActionFrequency,
+    bet_size_fraction: f64,
+) -> f64 {
+    let responses =
+        defender_stats.fold + defender_stats.call + defender_stats.raise + defender_stats.all_in;
+    let fold_to_bet = if responses == 0 {
+        DEFAULT_FOLD_TO_BET
+    } else {
+        defender_stats.fold as f64 / responses as f64
+    };
+
+    // 1.0 for a pot-sized bet, 2/3 for half pot, 4/3 for twice the pot
+    let bet_size_fraction = bet_size_fraction.max(0.0);
+    let size_factor = 2.0 * bet_size_fraction / (1.0 + bet_size_fraction);
+    let aggression = aggression_frequency(aggressor_stats) as f64 / 100.0;
+    let credibility = 1.0 - (aggression - 0.5).max(0.0);
+
+    (fold_to_bet * size_factor * credibility).clamp(0.0, 1.0)
+}
+
+/// Expected profit of betting `bet` into `pot`: the pot when the defender
+/// folds, otherwise our share of the called pot less the bet
+pub fn semi_bluff_ev(equity: f64, fold_equity: f64, pot: u64, bet: u64) -> f64 {
+    let (pot, bet) = (pot as f64, bet as f64);
+    fold_equity * pot + (1.0 - fold_equity) * (equity * (pot + 2.0 * bet) - bet)
+}
+
+pub fn is_profitable_semi_bluff(equity: f64, fold_equity: f64, pot: u64, bet: u64) -> bool {
+    semi_bluff_ev(equity, fold_equity, pot, bet) > 0.0
+}
+
+/// Deepest stack the push/fold tables cover; deeper stacks use these ranges
+pub const PUSH_FOLD_MAX_STACK_BB: f64 = 20.0;
+
+/// Seat relative to the button
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PositionLabel {
+    EarlyPosition,
+    MiddlePosition,
+    Hijack,
+    Cutoff,
+    Button,
+    SmallBlind,
+    BigBlind,
+}
+
+impl PositionLabel {
+    /// Players left to act when everyone has folded to this seat
+    pub fn players_behind(&self, players_remaining: u8) -> u8 {
+        let behind = match self {
+            PositionLabel::BigBlind => 0,
+            PositionLabel::SmallBlind => 1,
+            PositionLabel::Button => 2,
+            PositionLabel::Cutoff => 3,
+            PositionLabel::Hijack => 4,
+            PositionLabel::MiddlePosition => 5,
+            PositionLabel::EarlyPosition => players_remaining.saturating_sub(1),
+        };
+        behind.min(players_remaining.saturating_sub(1))
+    }
+}
+
+impl FromStr for PositionLabel {
+    type Err = PokerError;
+
+    fn from_str(label: &str) -> Result<Self, Self::Err> {
+        match label.to_ascii_uppercase().as_str() {
+            "EP" | "UTG" => Ok(PositionLabel::EarlyPosition),
+            "MP" => Ok(PositionLabel::MiddlePosition),
+            "HJ" => Ok(PositionLabel::Hijack),
+            "CO" => Ok(PositionLabel::Cutoff),
+            "BTN" | "BU" => Ok(PositionLabel::Button),
+            "SB" => Ok(PositionLabel::SmallBlind),
+            "BB" => Ok(PositionLabel::BigBlind),
+            _ => Err(PokerError::InvalidOperation(format!(
+                "Unknown position: {}",
+                label
+            ))),
+        }
+    }
+}
+
+/// A set of starting hand classes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandRange {
+    pub classes: Vec<StartingHandClass>,
+}
+
+impl HandRange {
+    pub fn contains(&self, class: &StartingHandClass) -> bool {
+        self.classes.contains(class)
+    }
+
+    pub fn combos(&self) -> u32 {
+        self.classes.iter().map(combos).sum()
+    }
+
+    /// Share of all 1326 starting hands, 0.0 to 100.0
+    pub fn percentage(&self) -> f64 {
+        self.combos() as f64 * 100.0 / 1326.0
+    }
+
+    /// Range shorthand such as "22+, A2s+, K9o+, T7s-T5s"
+    pub fn notation(&self) -> String {
+        let ranks = RANKS_DESCENDING;
+        let mut parts = Vec::new();
+
+        let pairs: Vec<Rank> = ranks
+            .iter()
+            .copied()
+            .filter(|&rank| self.contains(&class(rank, rank, false)))
+            .collect();
+        for run in runs(&pairs, &ranks) {
+            let (top, bottom) = (run[0], run[run.len() - 1]);
+            let (top_char, bottom_char) = (rank_char(top), rank_char(bottom));
+            parts.push(if run.len() == 1 {
+                format!("{}{}", top_char, top_char)
+            } else if top == Rank::Ace {
+                format!("{}{}+", bottom_char, bottom_char)
+            } else {
+                format!("{}{}-{}{}", top_char, top_char, bottom_char, bottom_char)
+            });
+        }
+
+        for (i, &high) in ranks.iter().enumerate() {
+            let kickers = &ranks[i + 1..];
+            for suited in [true, false] {
+                let suffix = if suited { 's' } else { 'o' };
+                let included: Vec<Rank> = kickers
+                    .iter()
+                    .copied()
+                    .filter(|&low| self.contains(&class(high, low, suited)))
+                    .collect();
+                for run in runs(&included, kickers) {
+                    let (top, bottom) = (run[0], run[run.len() - 1]);
+                    let h = rank_char(high);
+                    parts.push(if run.len() == 1 {
+                        format!("{}{}{}", h, rank_char(top), suffix)
+                    } else if Some(&top) == kickers.first() {
+                        format!("{}{}{}+", h, rank_char(bottom), suffix)
+                    } else {
+                        format!(
+                            "{}{}{}-{}{}{}",
+                            h,
+                            rank_char(top),
+                            suffix,
+                            h,
+                            rank_char(bottom),
+                            suffix
+                        )
+                    });
+                }
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+/// Nash jam-or-fold range when folded to `position`. The small blind uses
+/// the heads-up equilibrium table. Earlier seats use the table for their
+/// number of players behind, solved with every player behind calling
+/// independently, an approximation of the multi-way equilibrium; more than
+/// `MULTI_WAY_MAX_BEHIND` players behind play the last table.
+pub fn nash_push_fold_range(
+    stack_bb: f64,
+    position: PositionLabel,
+    players_remaining: u8,
+) -> HandRange {
+    let stack_bb = stack_bb.clamp(1.0, PUSH_FOLD_MAX_STACK_BB);
+    match position.players_behind(players_remaining) {
+        0 => HandRange::default(),
+        1 => range_from_table(&NASH_PUSH_MAX_BB, stack_bb),
+        behind => multi_way_push_range(stack_bb, behind),
+    }
+}
+
+/// Big blind's best response to a jam from `push_range`: call whenever the
+/// equity against that range beats the price of calling
+pub fn nash_call_range(stack_bb: f64, push_range: &HandRange) -> HandRange {
+    if push_range.classes.is_empty() {
+        return HandRange::default();
+    }
+    let stack_bb = stack_bb.max(1.0);
+    let required = (stack_bb - 1.0) / (2.0 * stack_bb);
+    let classes = EQUITY_TABLE
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| equity_against(*index, push_range) > required)
+        .map(|(_, (class, _))| *class)
+        .collect();
+    HandRange { classes }
+}
+
+/// `nash_push_fold_range` in range notation, for string-typed callers
+pub fn nash_push_range_notation(
+    stack_bb: f64,
+    position: &str,
+    players_remaining: u8,
+) -> Result<String, PokerError> {
+    let position = position.parse::<PositionLabel>()?;
+    Ok(nash_push_fold_range(stack_bb, position, players_remaining).notation())
+}
+
+const RANKS_DESCENDING: [Rank; 13] = [
+    Rank::Ace,
+    Rank::King,
+    Rank::Queen,
+    Rank::Jack,
+    Rank::Ten,
+    Rank::Nine,
+    Rank::Eight,
+    Rank::Seven,
+    Rank::Six,
+    Rank::Five,
+    Rank::Four,
+    Rank::Three,
+    Rank::Two,
+];
+
+fn class(high: Rank, low: Rank, suited: bool) -> StartingHandClass {
+    StartingHandClass { high, low, suited }
+}
+
+fn combos(class: &StartingHandClass) -> u32 {
+    if class.is_pair() {
+        6
+    } else if class.suited {
+        4
+    } else {
+        12
+    }
+}
+
+fn rank_char(rank: Rank) -> char {
+    crate::preflop::rank_char(rank)
+}
+
+fn multi_way_push_range(stack_bb: f64, behind: u8) -> HandRange {
+    let row = behind.clamp(2, MULTI_WAY_MAX_BEHIND) - 2;
+    range_from_table(&NASH_PUSH_MULTI_WAY_MAX_BB[row as usize], stack_bb)
+}
+
+/// Split `included` into runs that are consecutive within `order`
+fn runs(included: &[Rank], order: &[Rank]) -> Vec<Vec<Rank>> {
+    let mut runs: Vec<Vec<Rank>> = Vec::new();
+    let mut previous: Option<usize> = None;
+    for rank in included {
+        let position = order.iter().position(|r| r == rank).unwrap_or(0);
+        match (previous, runs.last_mut()) {
+            (Some(prev), Some(run)) if position == prev + 1 => run.push(*rank),
+            _ => runs.push(vec![*rank]),
+        }
+        previous = Some(position);
+    }
+    runs
+}
+
+fn range_from_table(table: &[f32], stack_bb: f64) -> HandRange {
+    let classes = EQUITY_TABLE
+        .iter()
+        .zip(table)
+        .filter(|(_, max_stack)| **max_stack > 0.0 && **max_stack as f64 >= stack_bb)
+        .map(|((class, _), _)| *class)
+        .collect();
+    HandRange { classes }
+}
+
+/// Combo-weighted equity of class `index` against every class in `range`
+fn equity_against(index: usize, range: &HandRange) -> f64 {
+    let (weights, included): (Vec<f64>, Vec<f64>) = EQUITY_TABLE
+        .iter()
+        .map(|(class, _)| {
+            let included = if range.contains(class) { 1.0 } else { 0.0 };
+            (combos(class) as f64, included)
+        })
+        .unzip();
+    weighted_equity(index, &included, &weights)
+}
+
+/// Equity of class `index` against a mixed range, where `frequency` is how
+/// often each class is in it
+fn weighted_equity(index: usize, frequency: &[f64], weights: &[f64]) -> f64 {
+    let classes = EQUITY_TABLE.len();
+    let mut weighted = 0.0;
+    let mut total = 0.0;
+    for villain in 0..classes {
+        let weight = weights[villain] * frequency[villain];
+        weighted += weight * HEADS_UP_EQUITY[index * classes + villain] as f64 / 255.0;
+        total += weight;
+    }
+    if total == 0.0 {
+        0.0
+    } else {
+        weighted / total
+    }
+}
+
+/// Small deterministic generator (SplitMix64) so simulations are
+/// reproducible from an on-chain seed
+pub(crate) struct SeededRng(u64);
+
+impl SeededRng {
+    pub(crate) fn new(seed: [u8; 32]) -> Self {
+        let mut state = 0u64;
+        for chunk in seed.chunks(8) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            state ^= u64::from_le_bytes(bytes);
+            state = state.rotate_left(17);
+        }
+        SeededRng(state)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Shuffle only the first `count` positions (Fisher-Yates prefix)
+    pub(crate) fn partial_shuffle<T>(&mut self, items: &mut [T], count: usize) {
+        for i in 0..count.min(items.len()) {
+            let j = i + (self.next_u64() % (items.len() - i) as u64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::{Rank, Suit};
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    #[test]
+    fn test_strong_hand_recommends_bet() {
+        let hole = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
+        let board = [
+            card(Rank::Ace, Suit::Diamonds),
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Seven, Suit::Spades),
+        ];
+
+        let advice = best_action_by_equity("alice", &hole, &board, 1, 0, 100, 1000, [1; 32]);
+
+        assert_eq!(advice.action, PlayerAction::Bet(100));
+        assert!(advice.confidence > 0.5);
+    }
+
+    #[test]
+    fn test_weak_hand_recommends_fold() {
+        let hole = [
+            card(Rank::Seven, Suit::Spades),
+            card(Rank::Two, Suit::Hearts),
+        ];
+        let board = [
+            card(Rank::Ace, Suit::Diamonds),
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Queen, Suit::Diamonds),
+        ];
+
+        let advice = best_action_by_equity("bob", &hole, &board, 2, 300, 100, 1000, [2; 32]);
+
+        assert_eq!(advice.action, PlayerAction::Fold);
+    }
+
+    #[test]
+    fn test_medium_hand_with_good_pot_odds_calls() {
+        // Two overcards and a gutshot: not strong enough to bet, but a small
+        // bet into a big pot is an easy call
+        let hole = [
+            card(Rank::Queen, Suit::Spades),
+            card(Rank::Jack, Suit::Hearts),
+        ];
+        let board = [
+            card(Rank::Nine, Suit::Diamonds),
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Two, Suit::Hearts),
+        ];
+
+        let advice = best_action_by_equity("carol", &hole, &board, 2, 20, 400, 1000, [3; 32]);
+
+        assert_eq!(advice.action, PlayerAction::Call);
+    }
+
+    #[test]
+    fn test_equity_is_deterministic_for_seed() {
+        let hole = [
+            card(Rank::King, Suit::Spades),
+            card(Rank::Queen, Suit::Spades),
+        ];
+
+        let first = monte_carlo_equity(&hole, &[], 1, [9; 32], 200);
+        let second = monte_carlo_equity(&hole, &[], 1, [9; 32], 200);
+
+        assert_eq!(first, second);
+        assert!(first > 0.5 && first < 0.8);
+    }
+
+    fn notation_class(notation: &str) -> StartingHandClass {
+        EQUITY_TABLE
+            .iter()
+            .map(|(class, _)| *class)
+            .find(|class| class.notation() == notation)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_heads_up_ten_big_blind_ranges() {
+        // Published heads-up equilibrium at 10 BB: the small blind jams about
+        // 58% of hands and the big blind calls about 37%
+        let push = nash_push_fold_range(10.0, PositionLabel::SmallBlind, 2);
+        assert!(
+            (55.0..61.0).contains(&push.percentage()),
+            "{}",
+            push.percentage()
+        );
+
+        let call = nash_call_range(10.0, &push);
+        assert!(
+            (33.0..40.0).contains(&call.percentage()),
+            "{}",
+            call.percentage()
+        );
+    }
+
+    #[test]
+    fn test_known_small_blind_jams() {
+        let deep = nash_push_fold_range(20.0, PositionLabel::SmallBlind, 2);
+        for hand in ["AA", "22", "A2o", "A2s", "KTo", "54s"] {
+            assert!(
+                deep.contains(&notation_class(hand)),
+                "{} should jam 20 BB",
+                hand
+            );
+        }
+        assert!(!deep.contains(&notation_class("72o")));
+        assert!(deep.notation().starts_with("22+, A2s+, A2o+"));
+
+        let desperate = nash_push_fold_range(1.0, PositionLabel::SmallBlind, 2);
+        assert_eq!(desperate.combos(), 1326);
+    }
+
+    #[test]
+    fn test_early_position_jams_tighter_than_button() {
+        let early = nash_push_fold_range(10.0, PositionLabel::EarlyPosition, 9);
+        let button = nash_push_fold_range(10.0, PositionLabel::Button, 9);
+        assert!(early.percentage() < button.percentage());
+        // Published 9-handed 10BB charts jam roughly 10% under the gun
+        assert!(early.percentage() > 5.0 && early.percentage() < 15.0);
+        assert!(early.contains(&notation_class("AA")));
+        assert!(!early.contains(&notation_class("K2o")));
+
+        assert!(nash_push_fold_range(10.0, PositionLabel::BigBlind, 9)
+            .classes
+            .is_empty());
+        assert_eq!(
+            nash_push_range_notation(10.0, "UTG", 9).unwrap(),
+            early.notation()
+        );
+        assert!(nash_push_range_notation(10.0, "dealer", 9).is_err());
+        // Tables stop at ten-handed; bigger tables use the last one
+        assert_eq!(
+            nash_push_fold_range(10.0, PositionLabel::EarlyPosition, 12),
+            nash_push_fold_range(10.0, PositionLabel::EarlyPosition, 10)
+        );
+    }
+
+    #[test]
+    fn test_gto_bet_sizing_strategies() {
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Polarized33), 99);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Polarized50), 150);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Polarized75), 225);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Polarized100), 300);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Merged25), 75);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Merged40), 120);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Overbet120), 360);
+        assert_eq!(gto_bet_sizing(300, BetStrategy::Overbet150), 450);
+    }
+
+    #[test]
+    fn test_bet_size_by_equity_boundaries() {
+        assert_eq!(optimal_bet_size_for_equity(0.90, 200, 5000), 300);
+        assert_eq!(optimal_bet_size_for_equity(0.85, 200, 5000), 150);
+        assert_eq!(optimal_bet_size_for_equity(0.65, 200, 5000), 150);
+        assert_eq!(optimal_bet_size_for_equity(0.64, 200, 5000), 80);
+        assert_eq!(optimal_bet_size_for_equity(0.50, 200, 5000), 80);
+        assert_eq!(optimal_bet_size_for_equity(0.49, 200, 5000), 0);
+    }
+
+    #[test]
+    fn test_overbet_capped_at_stack() {
+        assert_eq!(optimal_bet_size_for_equity(0.95, 1000, 600), 600);
+        assert_eq!(optimal_bet_size_for_equity(0.70, 1000, 600), 600);
+        assert_eq!(optimal_bet_size_for_equity(0.95, 1000, 0), 0);
+    }
+
+    fn frequency(fold: u32, call: u32, bet: u32, raise: u32) -> ActionFrequency {
+        ActionFrequency {
+            fold,
+            call,
+            bet,
+            raise,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fold_equity_grows_with_bet_size() {
+        let aggressor = frequency(10, 10, 5, 5);
+        let defender = frequency(60, 40, 0, 0);
+        let half_pot = estimate_fold_equity(&aggressor, &defender, 0.5);
+        let pot = estimate_fold_equity(&aggressor, &defender, 1.0);
+        let overbet = estimate_fold_equity(&aggressor, &defender, 2.0);
+        assert!((pot - 0.6).abs() < 1e-9);
+        assert!(half_pot < pot && pot < overbet);
+
+        // Maniacs get called more; unknown defenders use the default
+        let maniac = frequency(0, 0, 30, 10);
+        assert!((estimate_fold_equity(&maniac, &defender, 1.0) - 0.3).abs() < 1e-9);
+        let unknown = ActionFrequency::default();
+        assert!((estimate_fold_equity(&unknown, &unknown, 1.0) - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_semi_bluff_ev_examples() {
+        // Flush draw, 35% equity, pot-sized bet of 100, folds half the time:
+        // 0.5 * 100 + 0.5 * (0.35 * 300 - 100) = 52.5
+        assert!((semi_bluff_ev(0.35, 0.5, 100, 100) - 52.5).abs() < 1e-9);
+        assert!(is_profitable_semi_bluff(0.35, 0.5, 100, 100));
+        // Pure bluff that never gets a fold loses the bet
+        assert!((semi_bluff_ev(0.0, 0.0, 100, 100) + 100.0).abs() < 1e-9);
+        assert!(!is_profitable_semi_bluff(0.0, 0.0, 100, 100));
+    }
+
+    #[test]
+    fn test_bluff_break_even_fold_equity() {
+        // A pure bluff breaks even when it works bet / (bet + pot) of the time
+        for (pot, bet) in [(100, 100), (100, 50), (300, 450)] {
+            let break_even = crate::odds::compute_bluff_break_even_equity(bet, pot);
+            assert!(semi_bluff_ev(0.0, break_even, pot, bet).abs() < 1e-9);
+            assert!(is_profitable_semi_bluff(0.0, break_even + 0.01, pot, bet));
+            assert!(!is_profitable_semi_bluff(0.0, break_even - 0.01, pot, bet));
+        }
+    }
+}
@@ -1,10 +1,26 @@
-use poker_types::Card;
+use poker_types::{Card, CardCommitment, PokerError};
 
 pub struct CommitReveal;
 
+/// One player's showdown reveal against their bound commitment
+#[derive(Debug, Clone)]
+pub struct RevealData {
+    pub player: String,
+    pub commitment: [u8; 32],
+    pub cards: Vec<Card>,
+    pub salt: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchVerifyResult {
+    pub all_valid: bool,
+    /// Players whose reveal did not match their commitment
+    pub failed: Vec<String>,
+}
+
 impl CommitReveal {
     /// Create commitment hash for cards (simple XOR-based hash without SIMD)
-    pub fn commit_cards(cards: &[Card; 2], salt: &str) -> [u8; 32] {
+    pub fn commit_cards(cards: &[Card], salt: &str) -> [u8; 32] {
         let mut hash = [0u8; 32];
         
         // Simple hash without SIMD
@@ -26,13 +42,118 @@ impl CommitReveal {
     /// Verify revealed cards match commitment
     pub fn verify_reveal(
         commitment: &[u8; 32],
-        revealed_cards: &[Card; 2],
+        revealed_cards: &[Card],
         salt: &str,
     ) -> bool {
         let computed_hash = Self::commit_cards(revealed_cards, salt);
         commitment == &computed_hash
     }
 
+    /// Check a player's stored commitment against the cards they reveal in
+    /// `hand_number`. The commitment must have been made by that player for
+    /// that hand.
+    pub fn verify_commitment(
+        commitment: &CardCommitment,
+        revealed_cards: &[Card],
+        player_address: &str,
+        hand_number: u64,
+    ) -> Result<(), PokerError> {
+        if commitment.player_address != player_address
+            || commitment.hand_number != hand_number
+            || !Self::verify_reveal_bound(
+                &commitment.cards_hash,
+                revealed_cards,
+                &commitment.salt,
+                player_address,
+                hand_number,
+            )
+        {
+            return Err(PokerError::CommitmentVerificationFailed);
+        }
+        Ok(())
+    }
+
+    /// Commitment unique to one player in one hand, so it can neither be
+    /// borrowed from another player nor replayed from an earlier hand
+    pub fn commit_cards_bound(
+        cards: &[Card],
+        salt: &str,
+        player_address: &str,
+        hand_number: u64,
+    ) -> [u8; 32] {
+        let mut hash = Self::hash_cards_with_player_nonce(cards, salt, player_address);
+        for (i, byte) in hand_number.to_le_bytes().iter().enumerate() {
+            hash[24 + i] ^= byte;
+        }
+        hash
+    }
+
+    pub fn verify_reveal_bound(
+        commitment: &[u8; 32],
+        cards: &[Card],
+        salt: &str,
+        player_address: &str,
+        hand_number: u64,
+    ) -> bool {
+        *commitment == Self::commit_cards_bound(cards, salt, player_address, hand_number)
+    }
+
+    /// Commitment bound to the player's address, so a valid reveal cannot be
+    /// replayed by someone else
+    pub fn hash_cards_with_player_nonce(
+        cards: &[Card],
+        salt: &str,
+        player_address: &str,
+    ) -> [u8; 32] {
+        let mut hash = Self::commit_cards(cards, salt);
+        for (i, byte) in player_address.bytes().enumerate() {
+            let idx = (i * 7 + 3) % 32;
+            hash[idx] ^= byte.rotate_left((i % 8) as u32);
+        }
+        hash
+    }
+
+    /// Verify every showdown reveal at once against address-bound commitments
+    pub fn batch_verify(reveals: &[RevealData]) -> BatchVerifyResult {
+        let failed: Vec<String> = reveals
+            .iter()
+            .filter(|reveal| {
+                Self::hash_cards_with_player_nonce(&reveal.cards, &reveal.salt, &reveal.player)
+                    != reveal.commitment
+            })
+            .map(|reveal| reveal.player.clone())
+            .collect();
+        BatchVerifyResult {
+            all_valid: failed.is_empty(),
+            failed,
+        }
+    }
+
+    /// Check a revealed deck against the hand's commitment and confirm the
+    /// burns sit where the dealing order puts them: one card above each
+    /// street, counting from the top after `hole_cards_dealt`
+    pub fn verify_deck_reveal(
+        commitment: &[u8; 32],
+        deck: &[Card],
+        burn_cards: &[Card],
+        hole_cards_dealt: usize,
+    ) -> bool {
+        if Self::commit_deck(deck) != *commitment {
+            return false;
+        }
+        let mut position = hole_cards_dealt;
+        for (street, burned) in [3, 1, 1].iter().zip(burn_cards) {
+            let Some(index) = deck.len().checked_sub(position + 1) else {
+                return false;
+            };
+            if deck[index] != *burned {
+                return false;
+            }
+            position += 1 + street;
+        }
+        true
+    }
+
     /// Generate random salt
     pub fn generate_salt() -> String {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -77,4 +198,128 @@ mod tests {
         ];
         assert!(!CommitReveal::verify_reveal(&commitment, &wrong_cards, salt));
     }
+
+    #[test]
+    fn test_bound_commitment_cannot_be_reused() {
+        let cards = [
+            Card { rank: Rank::Ace, suit: Suit::Hearts },
+            Card { rank: Rank::King, suit: Suit::Spades },
+        ];
+        let salt = "test_salt_123";
+        let stored = CardCommitment {
+            cards_hash: CommitReveal::commit_cards_bound(&cards, salt, "alice", 7),
+            salt: salt.to_string(),
+            committed_at: 0,
+            player_address: "alice".to_string(),
+            hand_number: 7,
+        };
+        assert!(CommitReveal::verify_commitment(&stored, &cards, "alice", 7).is_ok());
+
+        // Another player presenting Alice's commitment
+        assert_eq!(
+            CommitReveal::verify_commitment(&stored, &cards, "bob", 7),
+            Err(PokerError::CommitmentVerificationFailed)
+        );
+        let relabelled = CardCommitment {
+            player_address: "bob".to_string(),
+            ..stored.clone()
+        };
+        assert!(CommitReveal::verify_commitment(&relabelled, &cards, "bob", 7).is_err());
+
+        // The same commitment replayed in a later hand
+        assert!(CommitReveal::verify_commitment(&stored, &cards, "alice", 8).is_err());
+        assert!(!CommitReveal::verify_reveal_bound(
+            &stored.cards_hash,
+            &cards,
+            salt,
+            "alice",
+            8
+        ));
+    }
+
+    #[test]
+    fn test_deck_reveal_includes_burn_cards() {
+        let deck = poker_types::PokerState::shuffle_deck([3; 32]);
+        let commitment = CommitReveal::commit_deck(&deck);
+        let top = |position: usize| deck[deck.len() - 1 - position];
+        let burns = vec![top(4), top(8), top(10)];
+        assert!(CommitReveal::verify_deck_reveal(&commitment, &deck, &burns, 4));
+
+        // A burn that was not the card above the street
+        let wrong_burns = vec![top(4), top(9), top(10)];
+        assert!(!CommitReveal::verify_deck_reveal(&commitment, &deck, &wrong_burns, 4));
+
+        // Reordering the deck to hide a burn breaks the commitment
+        let mut tampered = deck.clone();
+        let len = tampered.len();
+        tampered.swap(len - 5, len - 6);
+        assert!(!CommitReveal::verify_deck_reveal(&commitment, &tampered, &burns, 4));
+    }
+
+    fn reveal(player: &str, cards: [Card; 2], salt: &str) -> RevealData {
+        RevealData {
+            player: player.to_string(),
+            commitment: CommitReveal::hash_cards_with_player_nonce(&cards, salt, player),
+            cards: cards.to_vec(),
+            salt: salt.to_string(),
+        }
+    }
+
+    fn hole_cards(first: Rank, second: Rank) -> [Card; 2] {
+        [
+            Card {
+                rank: first,
+                suit: Suit::Clubs,
+            },
+            Card {
+                rank: second,
+                suit: Suit::Diamonds,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_batch_verify_all_valid() {
+        let reveals = vec![
+            reveal("alice", hole_cards(Rank::Ace, Rank::King), "salt_a"),
+            reveal("bob", hole_cards(Rank::Two, Rank::Seven), "salt_b"),
+        ];
+        let result = CommitReveal::batch_verify(&reveals);
+        assert!(result.all_valid);
+        assert!(result.failed.is_empty());
+    }
+
+    #[test]
+    fn test_batch_verify_reports_invalid_reveal() {
+        let mut cheater = reveal("bob", hole_cards(Rank::Two, Rank::Seven), "salt_b");
+        cheater.cards = hole_cards(Rank::Ace, Rank::Ace).to_vec();
+        let reveals = vec![
+            reveal("alice", hole_cards(Rank::Ace, Rank::King), "salt_a"),
+            cheater,
+        ];
+
+        let result = CommitReveal::batch_verify(&reveals);
+        assert!(!result.all_valid);
+        assert_eq!(result.failed, vec!["bob".to_string()]);
+    }
+
+    #[test]
+    fn test_commitment_is_bound_to_player_address() {
+        let cards = hole_cards(Rank::Queen, Rank::Queen);
+        let alice = CommitReveal::hash_cards_with_player_nonce(&cards, "salt", "alice");
+        let bob = CommitReveal::hash_cards_with_player_nonce(&cards, "salt", "bob");
+        assert_ne!(alice, bob);
+
+        // Bob cannot claim Alice's commitment and reveal as his own
+        let stolen = RevealData {
+            player: "bob".to_string(),
+            commitment: alice,
+            cards: cards.to_vec(),
+            salt: "salt".to_string(),
+        };
+        assert_eq!(
+            CommitReveal::batch_verify(&[stolen]).failed,
+            vec!["bob".to_string()]
+        );
+    }
 }
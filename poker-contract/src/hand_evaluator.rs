@@ -1,8 +1,114 @@
-use poker_types::{Card, Rank, HandRank};
+use poker_types::{Card, GameVariant, HandRank, Rank};
 use std::collections::HashMap;
 
 pub struct HandEvaluator;
 
+/// A qualifying ace-to-five low: five distinct ranks of eight or lower, ace
+/// counting as one, highest first. Lower compares as better.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LowHand(pub Vec<u8>);
+
+/// A hand ranked for deuce-to-seven lowball, where the worst high hand
+/// wins: aces are high, and straights and flushes count against you.
+/// Greater compares as better.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeuceToSevenRank(pub HandRank);
+
+impl Ord for DeuceToSevenRank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for DeuceToSevenRank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// An ace-to-five low for Razz: aces count as one, straights and flushes are
+/// ignored and pairs count against you. Greater compares as better.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RazzRank {
+    /// 0 for no pair up to 5 for four of a kind
+    pub pairing: u8,
+    /// Ranks grouped by count, then highest first
+    pub ranks: Vec<u8>,
+}
+
+impl Ord for RazzRank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (other.pairing, &other.ranks).cmp(&(self.pairing, &self.ranks))
+    }
+}
+
+impl PartialOrd for RazzRank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A Badugi hand: the largest set of cards with no two sharing a suit or
+/// rank, aces low. Any four-card badugi beats every three-card hand, and
+/// within a size the lowest cards win. Greater compares as better.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadugiRank {
+    /// Cards that play, from 1 to 4
+    pub cards: u8,
+    /// Their values, highest first
+    pub ranks: Vec<u8>,
+}
+
+impl Ord for BadugiRank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cards
+            .cmp(&other.cards)
+            .then_with(|| other.ranks.cmp(&self.ranks))
+    }
+}
+
+impl PartialOrd for BadugiRank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A hand ranked by Short Deck rules. With the deuces through fives gone a
+/// flush is rarer than a full house, so it ranks above one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortDeckRank(pub HandRank);
+
+impl ShortDeckRank {
+    fn category(&self) -> u8 {
+        match self.0 {
+            HandRank::HighCard(_) => 0,
+            HandRank::OnePair(..) => 1,
+            HandRank::TwoPair(..) => 2,
+            HandRank::ThreeOfAKind(..) => 3,
+            HandRank::Straight(_) => 4,
+            HandRank::FullHouse(..) => 5,
+            HandRank::Flush(_) => 6,
+            HandRank::FourOfAKind(..) => 7,
+            HandRank::StraightFlush(_) => 8,
+            HandRank::RoyalFlush => 9,
+        }
+    }
+}
+
+impl Ord for ShortDeckRank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.category()
+            .cmp(&other.category())
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for ShortDeckRank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl HandEvaluator {
     pub fn evaluate_best_hand(cards: &[Card]) -> HandRank {
         let combinations = Self::get_combinations(cards, 5);
@@ -18,6 +124,223 @@ impl HandEvaluator {
         best_hand
     }
 
+    /// Omaha hands use exactly two hole cards and exactly three board cards
+    pub fn evaluate_omaha_hand(hole_cards: &[Card], board: &[Card]) -> HandRank {
+        let mut best_hand = HandRank::HighCard(vec![Rank::Two]);
+
+        for hole in Self::get_combinations(hole_cards, 2) {
+            for mut combo in Self::get_combinations(board, 3) {
+                combo.extend_from_slice(&hole);
+                let hand_rank = Self::evaluate_five_cards(&combo);
+                if hand_rank > best_hand {
+                    best_hand = hand_rank;
+                }
+            }
+        }
+
+        best_hand
+    }
+
+    /// Best hand under Short Deck rules, where A-6-7-8-9 is the lowest straight
+    pub fn evaluate_short_deck_hand(cards: &[Card]) -> ShortDeckRank {
+        Self::get_combinations(cards, 5)
+            .iter()
+            .map(|combo| ShortDeckRank(Self::evaluate_short_deck_five(combo)))
+            .max()
+            .unwrap_or(ShortDeckRank(HandRank::HighCard(vec![Rank::Six])))
+    }
+
+    fn evaluate_short_deck_five(cards: &[Card]) -> HandRank {
+        let mut ranks: Vec<Rank> = cards.iter().map(|c| c.rank).collect();
+        ranks.sort_by(|a, b| b.cmp(a));
+        if ranks == [Rank::Ace, Rank::Nine, Rank::Eight, Rank::Seven, Rank::Six] {
+            if Self::is_flush(cards) {
+                return HandRank::StraightFlush(Rank::Nine);
+            }
+            return HandRank::Straight(Rank::Nine);
+        }
+        Self::evaluate_five_cards(cards)
+    }
+
+    /// Best deuce-to-seven low from any five of `cards`. 7-5-4-3-2 offsuit
+    /// is the nuts; A-2-3-4-5 is ace high rather than a straight.
+    pub fn evaluate_deuce_to_seven(cards: &[Card]) -> DeuceToSevenRank {
+        Self::get_combinations(cards, 5)
+            .iter()
+            .map(|combo| DeuceToSevenRank(Self::evaluate_aces_high_five(combo)))
+            .max()
+            .unwrap_or(DeuceToSevenRank(HandRank::RoyalFlush))
+    }
+
+    fn evaluate_aces_high_five(cards: &[Card]) -> HandRank {
+        let mut ranks: Vec<Rank> = cards.iter().map(|c| c.rank).collect();
+        ranks.sort_by(|a, b| b.cmp(a));
+        if ranks == [Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two] {
+            if Self::is_flush(cards) {
+                return HandRank::Flush(ranks);
+            }
+            return HandRank::HighCard(ranks);
+        }
+        Self::evaluate_five_cards(cards)
+    }
+
+    /// Best Razz low from any five of `cards`, usually all seven
+    pub fn evaluate_razz_hand(cards: &[Card]) -> RazzRank {
+        Self::get_combinations(cards, 5)
+            .iter()
+            .map(|combo| Self::razz_five_cards(combo))
+            .max()
+            .unwrap_or(RazzRank {
+                pairing: 5,
+                ranks: Vec::new(),
+            })
+    }
+
+    fn razz_five_cards(cards: &[Card]) -> RazzRank {
+        let mut groups: Vec<(usize, u8)> = Vec::new();
+        for card in cards {
+            let value = match card.rank {
+                Rank::Ace => 1,
+                rank => rank as u8,
+            };
+            match groups.iter_mut().find(|(_, held)| *held == value) {
+                Some((count, _)) => *count += 1,
+                None => groups.push((1, value)),
+            }
+        }
+        groups.sort_by(|a, b| b.cmp(a));
+        let counts: Vec<usize> = groups.iter().map(|(count, _)| *count).collect();
+        let pairing = match counts.as_slice() {
+            [4, ..] => 5,
+            [3, 2] => 4,
+            [3, ..] => 3,
+            [2, 2, ..] => 2,
+            [2, ..] => 1,
+            _ => 0,
+        };
+        RazzRank {
+            pairing,
+            ranks: groups.into_iter().map(|(_, value)| value).collect(),
+        }
+    }
+
+    /// Best 8-or-better low from any five of `cards`, if one qualifies
+    pub fn evaluate_low_hand(cards: &[Card]) -> Option<LowHand> {
+        Self::get_combinations(cards, 5)
+            .iter()
+            .filter_map(|combo| Self::low_five_cards(combo))
+            .min()
+    }
+
+    /// Omaha lows, like highs, use exactly two hole cards and three board cards
+    pub fn evaluate_omaha_low(hole_cards: &[Card], board: &[Card]) -> Option<LowHand> {
+        let mut best_low: Option<LowHand> = None;
+
+        for hole in Self::get_combinations(hole_cards, 2) {
+            for mut combo in Self::get_combinations(board, 3) {
+                combo.extend_from_slice(&hole);
+                if let Some(low) = Self::low_five_cards(&combo) {
+                    if best_low.as_ref().is_none_or(|best| low < *best) {
+                        best_low = Some(low);
+                    }
+                }
+            }
+        }
+
+        best_low
+    }
+
+    fn low_five_cards(cards: &[Card]) -> Option<LowHand> {
+        let mut ranks: Vec<u8> = cards
+            .iter()
+            .map(|c| match c.rank {
+                Rank::Ace => 1,
+                rank => rank as u8,
+            })
+            .collect();
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+        ranks.dedup();
+        if ranks.len() != 5 || ranks[0] > 8 {
+            return None;
+        }
+        Some(LowHand(ranks))
+    }
+
+    /// Best Badugi from the four hole cards
+    pub fn evaluate_badugi_hand(cards: &[Card]) -> BadugiRank {
+        for size in (1..=cards.len().min(4)).rev() {
+            let best = Self::get_combinations(cards, size)
+                .iter()
+                .filter(|combo| Self::is_rainbow_low(combo))
+                .map(|combo| {
+                    let mut ranks: Vec<u8> = combo
+                        .iter()
+                        .map(|c| match c.rank {
+                            Rank::Ace => 1,
+                            rank => rank as u8,
+                        })
+                        .collect();
+                    ranks.sort_unstable_by(|a, b| b.cmp(a));
+                    BadugiRank {
+                        cards: size as u8,
+                        ranks,
+                    }
+                })
+                .max();
+            if let Some(best) = best {
+                return best;
+            }
+        }
+        BadugiRank {
+            cards: 0,
+            ranks: Vec::new(),
+        }
+    }
+
+    fn is_rainbow_low(cards: &[Card]) -> bool {
+        cards.iter().enumerate().all(|(i, card)| {
+            cards[i + 1..]
+                .iter()
+                .all(|other| other.suit != card.suit && other.rank != card.rank)
+        })
+    }
+
+    /// Best qualifying low for the variant, or `None` in high-only games. Stud
+    /// has no board, so pass the up cards as `board`.
+    pub fn evaluate_low_for_variant(
+        variant: GameVariant,
+        hole_cards: &[Card],
+        board: &[Card],
+    ) -> Option<LowHand> {
+        match variant {
+            GameVariant::OmahaHiLo => Self::evaluate_omaha_low(hole_cards, board),
+            GameVariant::SevenCardStudHiLo => {
+                let mut cards = hole_cards.to_vec();
+                cards.extend_from_slice(board);
+                Self::evaluate_low_hand(&cards)
+            }
+            _ => None,
+        }
+    }
+
+    /// Best hand under the variant's rules for combining hole and board cards
+    pub fn evaluate_for_variant(
+        variant: GameVariant,
+        hole_cards: &[Card],
+        board: &[Card],
+    ) -> HandRank {
+        match variant {
+            GameVariant::Omaha | GameVariant::OmahaHiLo => {
+                Self::evaluate_omaha_hand(hole_cards, board)
+            }
+            _ => {
+                let mut cards = hole_cards.to_vec();
+                cards.extend_from_slice(board);
+                Self::evaluate_best_hand(&cards)
+            }
+        }
+    }
+
     fn evaluate_five_cards(cards: &[Card]) -> HandRank {
         let mut sorted = cards.to_vec();
         sorted.sort_by(|a, b| b.rank.cmp(&a.rank));
@@ -162,3 +485,335 @@ impl HandEvaluator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::Suit;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    #[test]
+    fn test_omaha_needs_two_suited_hole_cards_for_a_flush() {
+        let board = [
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Seven, Suit::Hearts),
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::Jack, Suit::Hearts),
+            card(Rank::Four, Suit::Clubs),
+        ];
+        // One heart plays for a flush in Hold'em but not in Omaha
+        let hole = [
+            card(Rank::Ace, Suit::Hearts),
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Eight, Suit::Diamonds),
+            card(Rank::Three, Suit::Spades),
+        ];
+        assert!(matches!(
+            HandEvaluator::evaluate_for_variant(GameVariant::TexasHoldem, &hole[..2], &board),
+            HandRank::Flush(_)
+        ));
+        assert!(!matches!(
+            HandEvaluator::evaluate_omaha_hand(&hole, &board),
+            HandRank::Flush(_)
+        ));
+
+        let two_hearts = [
+            card(Rank::Ace, Suit::Hearts),
+            card(Rank::King, Suit::Hearts),
+            card(Rank::Eight, Suit::Diamonds),
+            card(Rank::Three, Suit::Spades),
+        ];
+        assert!(matches!(
+            HandEvaluator::evaluate_omaha_hand(&two_hearts, &board),
+            HandRank::Flush(_)
+        ));
+    }
+
+    #[test]
+    fn test_omaha_cannot_play_the_board() {
+        // Broadway on board, but only two hole cards may join three of it
+        let board = [
+            card(Rank::Ace, Suit::Hearts),
+            card(Rank::King, Suit::Clubs),
+            card(Rank::Queen, Suit::Diamonds),
+            card(Rank::Jack, Suit::Spades),
+            card(Rank::Ten, Suit::Hearts),
+        ];
+        let hole = [
+            card(Rank::Two, Suit::Clubs),
+            card(Rank::Two, Suit::Diamonds),
+            card(Rank::Three, Suit::Spades),
+            card(Rank::Four, Suit::Hearts),
+        ];
+        assert_eq!(
+            HandEvaluator::evaluate_omaha_hand(&hole, &board),
+            HandRank::OnePair(Rank::Two, vec![Rank::Ace, Rank::King, Rank::Queen])
+        );
+    }
+
+    #[test]
+    fn test_low_hand_qualifies_at_eight_or_better() {
+        let board = [
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Five, Suit::Clubs),
+            card(Rank::Eight, Suit::Diamonds),
+            card(Rank::King, Suit::Spades),
+            card(Rank::Queen, Suit::Hearts),
+        ];
+        let wheel_draw = [
+            card(Rank::Ace, Suit::Spades),
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::King, Suit::Hearts),
+            card(Rank::King, Suit::Diamonds),
+        ];
+        let no_low = [
+            card(Rank::Nine, Suit::Spades),
+            card(Rank::Ten, Suit::Clubs),
+            card(Rank::Two, Suit::Clubs),
+            card(Rank::Jack, Suit::Diamonds),
+        ];
+        assert_eq!(
+            HandEvaluator::evaluate_omaha_low(&wheel_draw, &board),
+            Some(LowHand(vec![8, 5, 3, 2, 1]))
+        );
+        // A paired deuce cannot make five distinct low ranks
+        assert_eq!(HandEvaluator::evaluate_omaha_low(&no_low, &board), None);
+        // Lower is better
+        assert!(LowHand(vec![5, 4, 3, 2, 1]) < LowHand(vec![6, 4, 3, 2, 1]));
+        assert!(LowHand(vec![8, 5, 3, 2, 1]) < LowHand(vec![8, 6, 3, 2, 1]));
+    }
+
+    #[test]
+    fn test_short_deck_flush_beats_full_house() {
+        let board = [
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::Nine, Suit::Clubs),
+            card(Rank::Seven, Suit::Hearts),
+            card(Rank::Six, Suit::Hearts),
+            card(Rank::Queen, Suit::Spades),
+        ];
+        let flush = [card(Rank::Ace, Suit::Hearts), card(Rank::Ten, Suit::Hearts)];
+        let boat = [
+            card(Rank::Queen, Suit::Clubs),
+            card(Rank::Queen, Suit::Diamonds),
+        ];
+        let rank = |hole: &[Card]| {
+            let mut cards = hole.to_vec();
+            cards.extend_from_slice(&board);
+            (
+                HandEvaluator::evaluate_best_hand(&cards),
+                HandEvaluator::evaluate_short_deck_hand(&cards),
+            )
+        };
+        let (flush_holdem, flush_short) = rank(&flush);
+        let (boat_holdem, boat_short) = rank(&boat);
+        assert!(boat_holdem > flush_holdem);
+        assert!(flush_short > boat_short);
+    }
+
+    #[test]
+    fn test_short_deck_ace_six_straight() {
+        let cards = [
+            card(Rank::Ace, Suit::Hearts),
+            card(Rank::Six, Suit::Clubs),
+            card(Rank::Seven, Suit::Diamonds),
+            card(Rank::Eight, Suit::Spades),
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::King, Suit::Hearts),
+            card(Rank::King, Suit::Clubs),
+        ];
+        let best = HandEvaluator::evaluate_short_deck_hand(&cards);
+        assert_eq!(best, ShortDeckRank(HandRank::Straight(Rank::Nine)));
+        // Still the lowest straight
+        assert!(best < ShortDeckRank(HandRank::Straight(Rank::Ten)));
+        assert!(best > ShortDeckRank(HandRank::ThreeOfAKind(Rank::Ace, vec![])));
+    }
+
+    fn hand(cards: &[(Rank, Suit)]) -> Vec<Card> {
+        cards.iter().map(|&(rank, suit)| card(rank, suit)).collect()
+    }
+
+    #[test]
+    fn test_deuce_to_seven_rankings() {
+        use Rank::*;
+        use Suit::*;
+        let nuts = hand(&[
+            (Seven, Hearts),
+            (Five, Clubs),
+            (Four, Spades),
+            (Three, Hearts),
+            (Two, Diamonds),
+        ]);
+        let eight_low = hand(&[
+            (Eight, Hearts),
+            (Five, Clubs),
+            (Four, Spades),
+            (Three, Hearts),
+            (Two, Diamonds),
+        ]);
+        let wheel = hand(&[
+            (Ace, Hearts),
+            (Five, Clubs),
+            (Four, Spades),
+            (Three, Hearts),
+            (Two, Diamonds),
+        ]);
+        let straight = hand(&[
+            (Six, Hearts),
+            (Five, Clubs),
+            (Four, Spades),
+            (Three, Hearts),
+            (Two, Diamonds),
+        ]);
+        let flush = hand(&[
+            (Seven, Hearts),
+            (Five, Hearts),
+            (Four, Hearts),
+            (Three, Hearts),
+            (Two, Hearts),
+        ]);
+        let pair = hand(&[
+            (Two, Hearts),
+            (Two, Clubs),
+            (Four, Spades),
+            (Three, Hearts),
+            (Five, Diamonds),
+        ]);
+
+        let rank = |cards: &[Card]| HandEvaluator::evaluate_deuce_to_seven(cards);
+        assert!(rank(&nuts) > rank(&eight_low));
+        assert!(rank(&eight_low) > rank(&wheel));
+        assert_eq!(
+            rank(&wheel).0,
+            HandRank::HighCard(vec![Ace, Five, Four, Three, Two])
+        );
+        assert!(rank(&wheel) > rank(&pair));
+        assert!(rank(&pair) > rank(&straight));
+        assert!(rank(&straight) > rank(&flush));
+    }
+
+    #[test]
+    fn test_razz_rankings() {
+        use Rank::*;
+        use Suit::*;
+        // Seven cards; the best five make a 6-4-3-2-A with the straight ignored
+        let wheel_six = hand(&[
+            (Ace, Hearts),
+            (Two, Hearts),
+            (Three, Hearts),
+            (Four, Hearts),
+            (Six, Hearts),
+            (King, Clubs),
+            (King, Spades),
+        ]);
+        let wheel = hand(&[
+            (Ace, Clubs),
+            (Two, Clubs),
+            (Three, Clubs),
+            (Four, Clubs),
+            (Five, Clubs),
+        ]);
+        let paired = hand(&[
+            (Ace, Clubs),
+            (Ace, Spades),
+            (Two, Clubs),
+            (Three, Clubs),
+            (Four, Clubs),
+        ]);
+        let king_low = hand(&[
+            (King, Clubs),
+            (Two, Spades),
+            (Three, Clubs),
+            (Four, Clubs),
+            (Five, Hearts),
+        ]);
+
+        let rank = |cards: &[Card]| HandEvaluator::evaluate_razz_hand(cards);
+        assert_eq!(rank(&wheel_six).ranks, vec![6, 4, 3, 2, 1]);
+        assert!(rank(&wheel) > rank(&wheel_six));
+        assert!(rank(&wheel_six) > rank(&king_low));
+        assert!(rank(&king_low) > rank(&paired));
+    }
+
+    #[test]
+    fn test_stud_hi_lo_low_and_high_from_seven_cards() {
+        use Rank::*;
+        use Suit::*;
+        let variant = GameVariant::SevenCardStudHiLo;
+        let down = hand(&[(Ace, Clubs), (Two, Diamonds), (King, Hearts)]);
+        let up = hand(&[
+            (Three, Hearts),
+            (Seven, Spades),
+            (Eight, Clubs),
+            (King, Diamonds),
+        ]);
+        assert_eq!(
+            HandEvaluator::evaluate_low_for_variant(variant, &down, &up),
+            Some(LowHand(vec![8, 7, 3, 2, 1]))
+        );
+        assert_eq!(
+            HandEvaluator::evaluate_for_variant(variant, &down, &up),
+            HandRank::OnePair(King, vec![Ace, Eight, Seven])
+        );
+
+        // Only four cards eight or lower: no qualifying low, high takes it all
+        let no_low = hand(&[(Nine, Clubs), (Two, Hearts), (Queen, Spades)]);
+        assert_eq!(
+            HandEvaluator::evaluate_low_for_variant(variant, &no_low, &up),
+            None
+        );
+        // High-only Stud never has a low
+        assert_eq!(
+            HandEvaluator::evaluate_low_for_variant(GameVariant::SevenCardStud, &down, &up),
+            None
+        );
+    }
+
+    #[test]
+    fn test_badugi_rankings() {
+        use Rank::*;
+        use Suit::*;
+        let rank = |cards: &[(Rank, Suit)]| HandEvaluator::evaluate_badugi_hand(&hand(cards));
+        let best = rank(&[
+            (Ace, Clubs),
+            (Two, Diamonds),
+            (Three, Hearts),
+            (Four, Spades),
+        ]);
+        let king_badugi = rank(&[
+            (King, Clubs),
+            (Queen, Diamonds),
+            (Jack, Hearts),
+            (Ten, Spades),
+        ]);
+        // Two clubs: only three cards play
+        let three_card = rank(&[(Ace, Clubs), (Two, Clubs), (Three, Hearts), (Four, Spades)]);
+        let paired = rank(&[
+            (Ace, Clubs),
+            (Ace, Diamonds),
+            (Two, Hearts),
+            (Three, Spades),
+        ]);
+
+        assert_eq!(best.cards, 4);
+        assert_eq!(best.ranks, vec![4, 3, 2, 1]);
+        assert!(best > king_badugi);
+        assert!(king_badugi > three_card);
+        assert_eq!(three_card.ranks, vec![4, 3, 1]);
+        assert_eq!(paired.ranks, vec![3, 2, 1]);
+        assert!(three_card < paired);
+
+        // All one suit leaves a single card, the lowest
+        let one_card = rank(&[(Nine, Clubs), (Five, Clubs), (King, Clubs), (Two, Clubs)]);
+        assert_eq!(
+            one_card,
+            BadugiRank {
+                cards: 1,
+                ranks: vec![2]
+            }
+        );
+    }
+}
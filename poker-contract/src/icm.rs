@@ -0,0 +1,379 @@
+//! Independent Chip Model (ICM) tournament equity
+
+use crate::advisor::SeededRng;
+use crate::hand_evaluator::HandEvaluator;
+use poker_types::{Card, PokerError, PokerState, SidePot};
+
+/// Most players `calculate_icm_equity` will take: a full final table. The
+/// model is exact, and its cost doubles with every extra player.
+pub const MAX_ICM_PLAYERS: usize = 10;
+
+/// Convert chip stacks into prize equity using the Malmuth-Harville model.
+///
+/// The chance of a player taking the next place is their share of the chips
+/// still in play, which depends only on who has already finished and not in
+/// what order. Places are filled in by dynamic programming over those sets
+/// of finished players: at most 2^players × players steps, fewer when only a
+/// few places are paid. Larger fields are rejected rather than computed.
+pub fn calculate_icm_equity(
+    stacks: &[(String, u64)],
+    payouts: &[u64],
+) -> Result<Vec<(String, f64)>, PokerError> {
+    let players = stacks.len();
+    if players > MAX_ICM_PLAYERS {
+        return Err(PokerError::LimitReached(format!(
+            "ICM equity covers at most {} players, not {}",
+            MAX_ICM_PLAYERS, players
+        )));
+    }
+    let chips: Vec<f64> = stacks.iter().map(|(_, chips)| *chips as f64).collect();
+    // There are never more places to pay than players to finish in them
+    let places = payouts.len().min(players);
+
+    // Chance that the players in each set took the top places, in any order
+    let mut finished = vec![0.0; 1 << players];
+    finished[0] = 1.0;
+    let mut equities = vec![0.0; players];
+    // Every set is reached only from its subsets, which are smaller numbers
+    for set in 0..finished.len() {
+        let probability = finished[set];
+        let place = set.count_ones() as usize;
+        if probability == 0.0 || place >= places {
+            continue;
+        }
+        let in_play = |player: &usize| set & (1 << player) == 0 && chips[*player] > 0.0;
+        let total: f64 = (0..players)
+            .filter(in_play)
+            .map(|player| chips[player])
+            .sum();
+        for player in (0..players).filter(in_play) {
+            let place_probability = probability * chips[player] / total;
+            equities[player] += place_probability * payouts[place] as f64;
+            finished[set | 1 << player] += place_probability;
+        }
+    }
+
+    Ok(stacks
+        .iter()
+        .zip(equities)
+        .map(|((name, _), equity)| (name.clone(), equity))
+        .collect())
+}
+
+/// Expected chips for each all-in player once the board runs out.
+///
+/// Every simulated run-out is shared by all pots, but each pot is only
+/// contested by its own eligible players, so side pot equity is computed
+/// independently of the main pot. Fractional expectations are rounded to
+/// whole chips with the largest remainders rounded up, so every pot is
+/// fully allocated. Every player eligible for a pot must be among
+/// `all_in_players`; a pot nobody listed could win is refused rather than
+/// dropped.
+pub fn calculate_equity_adjusted_chips(
+    all_in_players: &[(&str, Vec<Card>)],
+    board: &[Card],
+    pots: &[SidePot],
+    seed: [u8; 32],
+    iterations: u32,
+) -> Result<Vec<(String, u64)>, PokerError> {
+    for pot in pots {
+        if pot.eligible_players.is_empty() {
+            return Err(PokerError::InvalidOperation(format!(
+                "A pot of {} has no eligible players",
+                pot.amount
+            )));
+        }
+        let missing = pot
+            .eligible_players
+            .iter()
+            .find(|name| !all_in_players.iter().any(|(player, _)| player == name));
+        if let Some(name) = missing {
+            return Err(PokerError::InvalidOperation(format!(
+                "{} is eligible for a pot but has no cards in the run-out",
+                name
+            )));
+        }
+    }
+
+    let stub: Vec<Card> = PokerState::create_deck()
+        .into_iter()
+        .filter(|card| {
+            !board.contains(card) && !all_in_players.iter().any(|(_, hole)| hole.contains(card))
+        })
+        .collect();
+    let board_needed = 5usize.saturating_sub(board.len());
+    // A complete board needs only one "run-out"
+    let iterations = if board_needed == 0 {
+        1
+    } else {
+        iterations.max(1)
+    };
+
+    let mut shares = vec![vec![0.0; all_in_players.len()]; pots.len()];
+    let mut rng = SeededRng::new(seed);
+    for _ in 0..iterations {
+        let mut deck = stub.clone();
+        rng.partial_shuffle(&mut deck, board_needed);
+        let mut full_board = board.to_vec();
+        full_board.extend_from_slice(&deck[..board_needed.min(deck.len())]);
+
+        let ranks: Vec<_> = all_in_players
+            .iter()
+            .map(|(_, hole)| {
+                let mut cards = hole.clone();
+                cards.extend_from_slice(&full_board);
+                HandEvaluator::evaluate_best_hand(&cards)
+            })
+            .collect();
+
+        for (pot, pot_shares) in pots.iter().zip(shares.iter_mut()) {
+            let contenders: Vec<usize> = (0..all_in_players.len())
+                .filter(|&i| {
+                    pot.eligible_players
+                        .iter()
+                        .any(|name| name == all_in_players[i].0)
+                })
+                .collect();
+            let Some(best) = contenders.iter().map(|&i| &ranks[i]).max() else {
+                continue;
+            };
+            let winners: Vec<usize> = contenders
+                .into_iter()
+                .filter(|&i| &ranks[i] == best)
+                .collect();
+            for &winner in &winners {
+                pot_shares[winner] += 1.0 / winners.len() as f64;
+            }
+        }
+    }
+
+    let mut chips = vec![0u64; all_in_players.len()];
+    for (pot, pot_shares) in pots.iter().zip(&shares) {
+        let expected: Vec<f64> = pot_shares
+            .iter()
+            .map(|wins| pot.amount as f64 * wins / iterations as f64)
+            .collect();
+        let mut floors: Vec<u64> = expected.iter().map(|value| value.floor() as u64).collect();
+        let allocated: u64 = floors.iter().sum();
+        // Someone wins every run-out, so the rounding is all that is left
+        let mut leftover = pot.amount.saturating_sub(allocated);
+
+        let mut by_remainder: Vec<usize> = (0..expected.len()).collect();
+        by_remainder.sort_by(|&a, &b| {
+            let remainder = |i: usize| expected[i] - expected[i].floor();
+            remainder(b).total_cmp(&remainder(a))
+        });
+        for i in by_remainder {
+            if leftover == 0 {
+                break;
+            }
+            if pot_shares[i] > 0.0 {
+                floors[i] += 1;
+                leftover -= 1;
+            }
+        }
+        for (total, share) in chips.iter_mut().zip(floors) {
+            *total += share;
+        }
+    }
+
+    Ok(all_in_players
+        .iter()
+        .zip(chips)
+        .map(|((name, _), chips)| (name.to_string(), chips))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::{Rank, Suit};
+
+    fn stacks(chips: &[u64]) -> Vec<(String, u64)> {
+        chips
+            .iter()
+            .enumerate()
+            .map(|(i, chips)| (format!("player{}", i), *chips))
+            .collect()
+    }
+
+    #[test]
+    fn test_heads_up_equity_is_linear() {
+        let equities = calculate_icm_equity(&stacks(&[7500, 2500]), &[70, 30]).unwrap();
+
+        assert!((equities[0].1 - 60.0).abs() < 1e-9);
+        assert!((equities[1].1 - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_three_handed_known_example() {
+        // Classic 50/30/20 stacks paying 50/30/20
+        let equities = calculate_icm_equity(&stacks(&[5000, 3000, 2000]), &[50, 30, 20]).unwrap();
+
+        assert!((equities[0].1 - 38.393).abs() < 0.01);
+        assert!((equities[1].1 - 32.750).abs() < 0.01);
+        assert!((equities[2].1 - 28.857).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_equities_sum_to_prize_pool() {
+        let payouts = [50000, 30000, 20000];
+        let equities =
+            calculate_icm_equity(&stacks(&[4000, 2500, 1800, 1200, 500]), &payouts).unwrap();
+
+        let total: f64 = equities.iter().map(|(_, equity)| equity).sum();
+        assert!((total - 100000.0).abs() < 1e-6);
+        // A bigger stack is always worth more, but less than proportionally
+        assert!(equities[0].1 > equities[1].1);
+        assert!(equities[0].1 < 100000.0 * 4000.0 / 10000.0);
+    }
+
+    #[test]
+    fn test_busted_player_has_no_equity() {
+        let equities = calculate_icm_equity(&stacks(&[6000, 4000, 0]), &[60, 40]).unwrap();
+
+        assert_eq!(equities[2].1, 0.0);
+        assert!((equities[0].1 + equities[1].1 - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_final_table_is_cheap_and_larger_fields_rejected() {
+        let final_table: Vec<u64> = (1..=MAX_ICM_PLAYERS as u64).map(|i| i * 1000).collect();
+        let payouts = [300, 200, 150, 100, 80, 60, 50, 40, 20];
+        let equities = calculate_icm_equity(&stacks(&final_table), &payouts).unwrap();
+
+        let total: f64 = equities.iter().map(|(_, equity)| equity).sum();
+        assert!((total - 1000.0).abs() < 1e-6);
+        assert!(equities.windows(2).all(|pair| pair[0].1 < pair[1].1));
+
+        let field = vec![1000; MAX_ICM_PLAYERS + 1];
+        assert!(matches!(
+            calculate_icm_equity(&stacks(&field), &payouts),
+            Err(PokerError::LimitReached(_))
+        ));
+    }
+
+    #[test]
+    fn test_more_payouts_than_players_pays_each_place_once() {
+        let equities = calculate_icm_equity(&stacks(&[7500, 2500]), &[70, 30, 20, 10]).unwrap();
+
+        assert!((equities[0].1 - 60.0).abs() < 1e-9);
+        assert!((equities[1].1 - 40.0).abs() < 1e-9);
+    }
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    fn pot(amount: u64, eligible: &[&str]) -> SidePot {
+        SidePot {
+            amount,
+            eligible_players: eligible.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_coin_flip_splits_chips_equally() {
+        // Same hand in different suits with no flush possible: always a chop
+        let players = vec![
+            (
+                "alice",
+                vec![
+                    card(Rank::Ace, Suit::Hearts),
+                    card(Rank::King, Suit::Diamonds),
+                ],
+            ),
+            (
+                "bob",
+                vec![card(Rank::Ace, Suit::Clubs), card(Rank::King, Suit::Spades)],
+            ),
+        ];
+        let board = [
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Seven, Suit::Diamonds),
+            card(Rank::Nine, Suit::Clubs),
+        ];
+        let chips = calculate_equity_adjusted_chips(
+            &players,
+            &board,
+            &[pot(1000, &["alice", "bob"])],
+            [3u8; 32],
+            500,
+        )
+        .unwrap();
+
+        assert_eq!(
+            chips,
+            vec![("alice".to_string(), 500), ("bob".to_string(), 500)]
+        );
+    }
+
+    #[test]
+    fn test_side_pot_equity_is_independent() {
+        let players = vec![
+            (
+                "alice",
+                vec![
+                    card(Rank::Ace, Suit::Hearts),
+                    card(Rank::Ace, Suit::Diamonds),
+                ],
+            ),
+            (
+                "bob",
+                vec![
+                    card(Rank::King, Suit::Hearts),
+                    card(Rank::King, Suit::Diamonds),
+                ],
+            ),
+            (
+                "carol",
+                vec![
+                    card(Rank::Queen, Suit::Hearts),
+                    card(Rank::Queen, Suit::Diamonds),
+                ],
+            ),
+        ];
+        let board = [
+            card(Rank::Two, Suit::Clubs),
+            card(Rank::Seven, Suit::Diamonds),
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::Jack, Suit::Spades),
+            card(Rank::Four, Suit::Clubs),
+        ];
+        let pots = [
+            pot(300, &["alice", "bob", "carol"]),
+            pot(200, &["bob", "carol"]),
+        ];
+        let chips =
+            calculate_equity_adjusted_chips(&players, &board, &pots, [0u8; 32], 100).unwrap();
+
+        // Aces scoop the main pot but cannot win the side pot kings take
+        assert_eq!(
+            chips,
+            vec![
+                ("alice".to_string(), 300),
+                ("bob".to_string(), 200),
+                ("carol".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pot_without_a_listed_contender_is_refused() {
+        let players = vec![(
+            "alice",
+            vec![
+                card(Rank::Ace, Suit::Hearts),
+                card(Rank::Ace, Suit::Diamonds),
+            ],
+        )];
+        let pots = [pot(300, &["alice", "bob"]), pot(200, &["bob"])];
+        assert!(matches!(
+            calculate_equity_adjusted_chips(&players, &[], &pots, [0u8; 32], 10),
+            Err(PokerError::InvalidOperation(_))
+        ));
+        assert!(
+            calculate_equity_adjusted_chips(&players, &[], &[pot(50, &[])], [0u8; 32], 10).is_err()
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! All-in insurance pricing.
+//!
+//! Once every remaining player is all-in the result depends only on the
+//! cards still to come, so equity is exact: every possible runout is dealt
+//! and scored. Offers are only made from the flop on, when at most two
+//! cards remain and the enumeration is small.
+
+use crate::hand_evaluator::HandEvaluator;
+use poker_types::{Card, InsuranceOffer, PokerState};
+
+/// Share of the pot each hand wins over every runout of `board`, with ties
+/// split. Returns one entry per hand in the order given.
+pub fn runout_equity(hands: &[Vec<Card>], board: &[Card]) -> Vec<f64> {
+    let stub: Vec<Card> = PokerState::create_deck()
+        .into_iter()
+        .filter(|card| !board.contains(card) && !hands.iter().any(|hand| hand.contains(card)))
+        .collect();
+    let mut won = vec![0.0; hands.len()];
+    let mut runouts = 0u32;
+    match 5usize.saturating_sub(board.len()) {
+        0 => {
+            score_runout(hands, board, &mut won);
+            runouts = 1;
+        }
+        1 => {
+            for card in &stub {
+                let mut full_board = board.to_vec();
+                full_board.push(*card);
+                score_runout(hands, &full_board, &mut won);
+                runouts += 1;
+            }
+        }
+        2 => {
+            for (i, first) in stub.iter().enumerate() {
+                for second in &stub[i + 1..] {
+                    let mut full_board = board.to_vec();
+                    full_board.extend_from_slice(&[*first, *second]);
+                    score_runout(hands, &full_board, &mut won);
+                    runouts += 1;
+                }
+            }
+        }
+        _ => return vec![0.0; hands.len()],
+    }
+    won.iter().map(|w| w / runouts.max(1) as f64).collect()
+}
+
+fn score_runout(hands: &[Vec<Card>], board: &[Card], won: &mut [f64]) {
+    let ranks: Vec<_> = hands
+        .iter()
+        .map(|hand| {
+            let mut cards = hand.clone();
+            cards.extend_from_slice(board);
+            HandEvaluator::evaluate_best_hand(&cards)
+        })
+        .collect();
+    let Some(best) = ranks.iter().max() else {
+        return;
+    };
+    let winners = ranks.iter().filter(|rank| *rank == best).count();
+    for (share, rank) in won.iter_mut().zip(&ranks) {
+        if rank == best {
+            *share += 1.0 / winners as f64;
+        }
+    }
+}
+
+/// Price insurance for a favourite: `coverage` is paid if the hand is lost,
+/// for a premium of the fair price `coverage * (1 - equity)` plus the
+/// house margin, rounded up. Underdogs and locks get no offer.
+pub fn price_offer(
+    player: &str,
+    hand_number: u64,
+    equity: f64,
+    coverage: u64,
+    margin_percent: u8,
+) -> Option<InsuranceOffer> {
+    if equity <= 0.5 || equity >= 1.0 || coverage == 0 {
+        return None;
+    }
+    let fair = coverage as f64 * (1.0 - equity);
+    let premium = (fair * (100 + margin_percent as u64) as f64 / 100.0).ceil() as u64;
+    if premium >= coverage {
+        return None;
+    }
+    Some(InsuranceOffer {
+        player: player.to_string(),
+        hand_number,
+        equity,
+        coverage,
+        premium,
+        accepted: false,
+    })
+}
+
+/// Offers for every all-in favourite. `players` holds each contender's
+/// name, hole cards and the pot they stand to win.
+pub fn insurance_offers(
+    players: &[(String, Vec<Card>, u64)],
+    board: &[Card],
+    hand_number: u64,
+    margin_percent: u8,
+) -> Vec<InsuranceOffer> {
+    if board.len() < 3 || players.len() < 2 {
+        return Vec::new();
+    }
+    let hands: Vec<Vec<Card>> = players.iter().map(|(_, cards, _)| cards.clone()).collect();
+    runout_equity(&hands, board)
+        .into_iter()
+        .zip(players)
+        .filter_map(|(equity, (name, _, at_stake))| {
+            price_offer(name, hand_number, equity, *at_stake, margin_percent)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::{Rank, Suit};
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_runout_equity_counts_outs_on_the_turn() {
+        // Aces against a nut flush draw with one card to come
+        let aces = vec![
+            card(Rank::Ace, Suit::Clubs),
+            card(Rank::Ace, Suit::Diamonds),
+        ];
+        let draw = vec![
+            card(Rank::King, Suit::Hearts),
+            card(Rank::Queen, Suit::Hearts),
+        ];
+        let board = [
+            card(Rank::Two, Suit::Hearts),
+            card(Rank::Seven, Suit::Hearts),
+            card(Rank::Nine, Suit::Spades),
+            card(Rank::Four, Suit::Clubs),
+        ];
+        let equity = runout_equity(&[aces, draw], &board);
+        // Nine hearts out of 44 unseen cards
+        assert_close(equity[1], 9.0 / 44.0);
+        assert_close(equity[0] + equity[1], 1.0);
+    }
+
+    #[test]
+    fn test_offer_is_priced_from_equity() {
+        let offer = price_offer("alice", 3, 0.8, 1_000, 10).unwrap();
+        // Fair price 200, plus a 10% margin
+        assert_eq!(offer.premium, 220);
+        assert!(!offer.accepted);
+
+        assert_eq!(price_offer("bob", 3, 0.5, 1_000, 10), None);
+        assert_eq!(price_offer("bob", 3, 1.0, 1_000, 10), None);
+        assert_eq!(price_offer("bob", 3, 0.9, 0, 10), None);
+    }
+
+    #[test]
+    fn test_only_favourites_are_offered_insurance_after_the_flop() {
+        let players = vec![
+            (
+                "alice".to_string(),
+                vec![
+                    card(Rank::Ace, Suit::Clubs),
+                    card(Rank::Ace, Suit::Diamonds),
+                ],
+                400,
+            ),
+            (
+                "bob".to_string(),
+                vec![
+                    card(Rank::Seven, Suit::Clubs),
+                    card(Rank::Two, Suit::Diamonds),
+                ],
+                400,
+            ),
+        ];
+        let flop = [
+            card(Rank::King, Suit::Spades),
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::Four, Suit::Diamonds),
+        ];
+        let offers = insurance_offers(&players, &flop, 7, 5);
+        assert_eq!(offers.len(), 1);
+        assert_eq!(offers[0].player, "alice");
+        assert_eq!(offers[0].hand_number, 7);
+
+        // No offers before the flop
+        assert!(insurance_offers(&players, &[], 7, 5).is_empty());
+    }
+}
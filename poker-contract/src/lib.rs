@@ -7,21 +7,41 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+pub mod advisor;
 pub mod commit_reveal;
 pub mod contract;
 pub mod hand_evaluator;
+pub mod icm;
+pub mod insurance;
 pub mod messages;
+pub mod narrative;
+pub mod odds;
 pub mod operations;
+pub mod pot_manager;
+pub mod preflop;
+pub mod replay;
 pub mod service;
 pub mod state;
+pub mod stats;
 
 pub use commit_reveal::CommitReveal;
 pub use hand_evaluator::HandEvaluator;
 pub use messages::*;
 pub use operations::PokerParameters;
+pub use pot_manager::{PotAward, PotManager};
 
 // Re-export types from poker-types
+pub use poker_types::{betting, invariants};
 pub use poker_types::{
-    Card, CardCommitment, GamePhase, HandRank, LeaderboardData, Operation,
-    PlayerAction, PlayerStatus, PokerAbi, PokerPlayer, Rank, Suit, TableConfig,
+    ActionFrequency, BettingRound, BettingSituation, BettingStructure,
+    BlindLevel, BlindPositions, BlindSchedule, CancelReason, Card,
+    CardCommitment, ChatEntry, ChipTransfer, DeckError, DeckIntegrityResult,
+    Elimination, FlaggedHand, GamePhase, GameRules, GameVariant, HandEvent,
+    HandParticipation, HandRank, InsuranceOffer, JoinOutcome,
+    LeaderboardData, LegalActions, LevelDuration, MixedGameSchedule,
+    Operation, PairStats, PlayerAction, PlayerDataExport, PlayerStatus,
+    PokerAbi, PokerError, PokerPlayer, ProposalOutcome, Rank, RoundStatus,
+    RuleProposal, SidePot, StackDepthCategory, StackMigration, StreakType,
+    Suit, TableConfig, TableConfigPatch, TournamentConfig, TournamentMove,
+    TournamentPayout, WaitingPlayer,
 };
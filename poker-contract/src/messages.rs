@@ -1,5 +1,9 @@
+use crate::pot_manager::PotAward;
 use linera_sdk::linera_base_types::ChainId;
-use poker_types::{Card, CardCommitment, GamePhase, HandRank, PlayerAction};
+use poker_types::{
+    CancelReason, Card, CardCommitment, GamePhase, GameVariant, HandRank, InsuranceOffer,
+    PlayerAction, PlayerDataExport,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,6 +16,10 @@ pub enum Message {
         hands_won: u64,
         hands_played: u64,
         biggest_pot: u64,
+        win_streak: u32,
+        loss_streak: u32,
+        longest_win_streak: u32,
+        longest_loss_streak: u32,
     },
     PlayerJoined {
         player: String,
@@ -32,7 +40,7 @@ pub enum Message {
     },
     CardsRevealed {
         player: String,
-        cards: [Card; 2],
+        cards: Vec<Card>,
     },
     HandStarted {
         dealer: String,
@@ -62,12 +70,161 @@ pub enum Message {
     HandComplete {
         winners: Vec<Winner>,
         pot_distribution: Vec<(String, u64)>,
+        /// Each pot's winners, showing ties and the odd chip
+        pot_awards: Vec<PotAward>,
+        /// Hole cards shown at showdown; auto-mucked losing hands are left out
+        revealed_hands: Vec<(String, Vec<Card>)>,
     },
     TransferChips {
         from: String,
         to: String,
         amount: u64,
     },
+    BombPotInitiated {
+        ante_per_player: u64,
+        total_pot: u64,
+    },
+    PlayerAutoSittingOut {
+        player: String,
+        reason: String,
+    },
+    IcmEquityUpdate {
+        equities: Vec<(String, f64)>,
+    },
+    BountyWon {
+        eliminator: String,
+        eliminated: String,
+        bounty_amount: u64,
+    },
+    TournamentComplete {
+        final_rankings: Vec<(String, u8)>,
+        prizes: Vec<(String, u64)>,
+    },
+    GameVariantChanged {
+        new_variant: GameVariant,
+        next_change_in_hands: u8,
+    },
+    OrphanedChipsRecovered {
+        amount: u64,
+        distribution: Vec<(String, u64)>,
+    },
+    PlayerDisconnected {
+        player: String,
+        grace_period_ms: u64,
+    },
+    PlayerReconnected {
+        player: String,
+    },
+    HandVoided {
+        hand_number: u64,
+        refunds: Vec<(String, u64)>,
+    },
+    GameResetDueToStaleness {
+        hand_number: u64,
+        refunds: Vec<(String, u64)>,
+    },
+    PlayerDataExport {
+        export: Box<PlayerDataExport>,
+    },
+    SessionComplete {
+        final_standings: Vec<(String, u64)>,
+    },
+    /// Full shuffled deck, top card last, plus the burns, so players can
+    /// check it against `HandStarted::deck_commitment`
+    DeckRevealed {
+        deck: Vec<Card>,
+        burn_cards: Vec<Card>,
+    },
+    UncalledBetReturned {
+        player: String,
+        amount: u64,
+    },
+    RabbitHuntCards {
+        cards: Vec<Card>,
+    },
+    AutoRebuyTriggered {
+        player: String,
+        amount: u64,
+    },
+    BlindsIncreased {
+        level: u32,
+        small_blind: u64,
+        big_blind: u64,
+        ante: u64,
+    },
+    InsuranceOffered {
+        offers: Vec<InsuranceOffer>,
+    },
+    /// `claimed` when the insured player lost and was paid out
+    InsuranceSettled {
+        player: String,
+        amount: u64,
+        claimed: bool,
+    },
+    /// A player chose to show their hole cards
+    HandShown {
+        player: String,
+        cards: Vec<Card>,
+    },
+    /// A hand that could not be completed was called off and refunded
+    HandCancelled {
+        hand_number: u64,
+        reason: CancelReason,
+        refunds: Vec<(String, u64)>,
+    },
+    /// A player bought more chips between hands
+    ChipsAdded {
+        player: String,
+        amount: u64,
+        stack: u64,
+    },
+    /// A seat opened and the next player on the waiting list took it
+    SeatAvailable {
+        player: String,
+        name: String,
+        buy_in: u64,
+        position: u8,
+    },
+    /// A Sit & Go filled up and its prize pool is locked
+    TournamentStarted {
+        players: Vec<String>,
+        prize_pool: u64,
+    },
+    /// From the tournament coordinator: seat a player here
+    TournamentSeatPlayer {
+        player_name: String,
+        owner: String,
+        chips: u64,
+        seat: u8,
+    },
+    /// From the tournament coordinator: the player leaves this table and
+    /// their stack goes with them to `to_table`
+    TournamentMovePlayer {
+        player_name: String,
+        to_table: ChainId,
+        seat: u8,
+    },
+    /// From another table of the same tournament: a moved player arriving
+    /// with their stack
+    TournamentStackMigration {
+        player_name: String,
+        owner: String,
+        chips: u64,
+        seat: u8,
+    },
+    /// From the tournament coordinator: blinds for the next hand on
+    TournamentBlindLevel {
+        level: u32,
+        small_blind: u64,
+        big_blind: u64,
+        ante: u64,
+    },
+    /// From the tournament coordinator: chips the player bought with a
+    /// rebuy or add-on
+    TournamentAddChips {
+        player_name: String,
+        chips: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,451 @@
+//! One-line, human-readable summaries of completed hands
+
+use crate::preflop::rank_char;
+use poker_types::{Card, GamePhase, HandEvent, HandRank, PlayerAction, Rank, Suit, TableConfig};
+
+/// A bet is "big" once it reaches the pot or this many big blinds
+const BIG_RAISE_BIG_BLINDS: u64 = 4;
+
+/// What made a hand worth talking about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandHighlight {
+    /// Everyone folded to an ordinary bet
+    Uncontested,
+    /// A big bet or raise that nobody called
+    BigRaiseFold,
+    Showdown,
+    SplitPot,
+    /// A full house or better lost at showdown
+    BadBeat,
+}
+
+/// Summarise a finished hand, e.g. `Hand #42 — Alice raised to 200 on the
+/// button, Bob called. Flop: Ah-Kd-Qc. Alice bet 300, Bob folded. Alice wins 500.`
+pub fn generate_hand_summary(events: &[HandEvent], config: &TableConfig) -> String {
+    let mut header = String::from("Hand");
+    let mut dealer: Option<&str> = None;
+    let mut dealer_has_acted = false;
+    let mut street_actions: Vec<String> = Vec::new();
+    let mut sentences: Vec<String> = Vec::new();
+
+    for event in events {
+        match event {
+            HandEvent::HandStarted {
+                hand_number,
+                dealer: button,
+            } => {
+                header = format!("Hand #{}", hand_number);
+                dealer = Some(button);
+            }
+            HandEvent::PlayerActed { player, action } => {
+                let mut text = format!("{} {}", player, describe_action(action));
+                if dealer == Some(player.as_str()) && !dealer_has_acted {
+                    text.push_str(" on the button");
+                    dealer_has_acted = true;
+                }
+                street_actions.push(text);
+            }
+            HandEvent::CommunityCardsDealt { phase, cards } => {
+                if !street_actions.is_empty() {
+                    sentences.push(format!("{}.", street_actions.join(", ")));
+                    street_actions.clear();
+                }
+                // Only pre-flop play is narrated relative to the button
+                dealer_has_acted = true;
+                let board: Vec<String> = cards.iter().map(card_label).collect();
+                sentences.push(format!("{}: {}.", phase_name(phase), board.join("-")));
+            }
+            _ => {}
+        }
+    }
+    if !street_actions.is_empty() {
+        sentences.push(format!("{}.", street_actions.join(", ")));
+    }
+    sentences.push(outcome_sentence(events, hand_highlight(events, config)));
+
+    format!("{} — {}", header, sentences.join(" "))
+}
+
+/// Classify the hand by its most dramatic moment
+pub fn hand_highlight(events: &[HandEvent], config: &TableConfig) -> HandHighlight {
+    let winners = pot_winners(events);
+    let revealed = revealed_hands(events);
+
+    if revealed.len() >= 2 {
+        if winners.len() > 1 {
+            return HandHighlight::SplitPot;
+        }
+        let beaten = revealed
+            .iter()
+            .filter(|(player, _)| !winners.iter().any(|(winner, _)| winner == player))
+            .any(|(_, rank)| is_bad_beat_hand(rank));
+        return if beaten {
+            HandHighlight::BadBeat
+        } else {
+            HandHighlight::Showdown
+        };
+    }
+
+    let mut pot = 0u64;
+    let mut street_bet = 0u64;
+    let mut committed: Vec<(&str, u64)> = Vec::new();
+    let mut last_bet_was_big = false;
+    for event in events {
+        match event {
+            HandEvent::BlindPosted { player, amount } => {
+                pot += amount;
+                street_bet = street_bet.max(*amount);
+                add_committed(&mut committed, player, *amount);
+            }
+            HandEvent::PlayerActed { player, action } => match action {
+                PlayerAction::Bet(to) | PlayerAction::Raise(to) => {
+                    let already = committed_by(&committed, player);
+                    last_bet_was_big = *to >= pot.max(BIG_RAISE_BIG_BLINDS * config.big_blind);
+                    pot += to.saturating_sub(already);
+                    street_bet = street_bet.max(*to);
+                    add_committed(&mut committed, player, to.saturating_sub(already));
+                }
+                PlayerAction::Call => {
+                    let owed = street_bet.saturating_sub(committed_by(&committed, player));
+                    pot += owed;
+                    add_committed(&mut committed, player, owed);
+                    last_bet_was_big = false;
+                }
+                _ => {}
+            },
+            HandEvent::CommunityCardsDealt { .. } => {
+                street_bet = 0;
+                committed.clear();
+                last_bet_was_big = false;
+            }
+            _ => {}
+        }
+    }
+
+    if last_bet_was_big {
+        HandHighlight::BigRaiseFold
+    } else {
+        HandHighlight::Uncontested
+    }
+}
+
+/// Plain-English hand name, e.g. "a straight, Jack high"
+pub fn describe_hand_rank(rank: &HandRank) -> String {
+    match rank {
+        HandRank::HighCard(kickers) => match kickers.first() {
+            Some(high) => format!("{} high", rank_name(*high)),
+            None => "high card".to_string(),
+        },
+        HandRank::OnePair(pair, _) => format!("a pair of {}", rank_plural(*pair)),
+        HandRank::TwoPair(high, low, _) => {
+            format!("two pair, {} and {}", rank_plural(*high), rank_plural(*low))
+        }
+        HandRank::ThreeOfAKind(trips, _) => format!("three of a kind, {}", rank_plural(*trips)),
+        HandRank::Straight(high) => format!("a straight, {} high", rank_name(*high)),
+        HandRank::Flush(ranks) => match ranks.first() {
+            Some(high) => format!("a flush, {} high", rank_name(*high)),
+            None => "a flush".to_string(),
+        },
+        HandRank::FullHouse(trips, pair) => {
+            format!(
+                "a full house, {} full of {}",
+                rank_plural(*trips),
+                rank_plural(*pair)
+            )
+        }
+        HandRank::FourOfAKind(quads, _) => format!("four of a kind, {}", rank_plural(*quads)),
+        HandRank::StraightFlush(high) => format!("a straight flush, {} high", rank_name(*high)),
+        HandRank::RoyalFlush => "a royal flush".to_string(),
+    }
+}
+
+fn outcome_sentence(events: &[HandEvent], highlight: HandHighlight) -> String {
+    let winners = pot_winners(events);
+    let revealed = revealed_hands(events);
+    let Some((first_winner, _)) = winners.first() else {
+        return "No pot was awarded.".to_string();
+    };
+
+    let total: u64 = winners.iter().map(|(_, amount)| amount).sum();
+    let names: Vec<&str> = winners.iter().map(|(name, _)| *name).collect();
+    let mut sentence = if names.len() == 1 {
+        format!("{} wins {}", first_winner, total)
+    } else {
+        format!("{} split {}", join_names(&names), total)
+    };
+
+    if revealed.len() >= 2 {
+        if let Some((_, rank)) = revealed.iter().find(|(player, _)| player == first_winner) {
+            sentence.push_str(&format!(" with {}", describe_hand_rank(rank)));
+        }
+    }
+    if highlight == HandHighlight::BadBeat {
+        if let Some((loser, rank)) = revealed
+            .iter()
+            .find(|(player, rank)| !names.contains(player) && is_bad_beat_hand(rank))
+        {
+            sentence.push_str(&format!(
+                ", a bad beat for {} holding {}",
+                loser,
+                describe_hand_rank(rank)
+            ));
+        }
+    }
+    sentence.push('.');
+    sentence
+}
+
+/// Winners in award order, with the chips from every pot they took
+fn pot_winners(events: &[HandEvent]) -> Vec<(&str, u64)> {
+    let mut winners: Vec<(&str, u64)> = Vec::new();
+    for event in events {
+        if let HandEvent::PotAwarded { player, amount } = event {
+            add_committed(&mut winners, player, *amount);
+        }
+    }
+    winners
+}
+
+fn revealed_hands(events: &[HandEvent]) -> Vec<(&str, &HandRank)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            HandEvent::ShowdownRevealed { player, hand_rank } => Some((player.as_str(), hand_rank)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn add_committed<'a>(totals: &mut Vec<(&'a str, u64)>, player: &'a str, amount: u64) {
+    match totals.iter_mut().find(|(name, _)| *name == player) {
+        Some((_, total)) => *total += amount,
+        None => totals.push((player, amount)),
+    }
+}
+
+fn committed_by(totals: &[(&str, u64)], player: &str) -> u64 {
+    totals
+        .iter()
+        .find(|(name, _)| *name == player)
+        .map(|(_, total)| *total)
+        .unwrap_or(0)
+}
+
+fn is_bad_beat_hand(rank: &HandRank) -> bool {
+    matches!(
+        rank,
+        HandRank::FullHouse(..)
+            | HandRank::FourOfAKind(..)
+            | HandRank::StraightFlush(_)
+            | HandRank::RoyalFlush
+    )
+}
+
+fn describe_action(action: &PlayerAction) -> String {
+    match action {
+        PlayerAction::Fold => "folded".to_string(),
+        PlayerAction::Check => "checked".to_string(),
+        PlayerAction::Call => "called".to_string(),
+        PlayerAction::Bet(amount) => format!("bet {}", amount),
+        PlayerAction::Raise(amount) => format!("raised to {}", amount),
+        PlayerAction::AllIn => "went all-in".to_string(),
+    }
+}
+
+fn join_names(names: &[&str]) -> String {
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => names.join(""),
+    }
+}
+
+fn phase_name(phase: &GamePhase) -> &'static str {
+    match phase {
+        GamePhase::Flop => "Flop",
+        GamePhase::Turn => "Turn",
+        GamePhase::River => "River",
+        _ => "Board",
+    }
+}
+
+fn card_label(card: &Card) -> String {
+    let suit = match card.suit {
+        Suit::Hearts => 'h',
+        Suit::Diamonds => 'd',
+        Suit::Clubs => 'c',
+        Suit::Spades => 's',
+    };
+    format!("{}{}", rank_char(card.rank), suit)
+}
+
+fn rank_name(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Two => "Two",
+        Rank::Three => "Three",
+        Rank::Four => "Four",
+        Rank::Five => "Five",
+        Rank::Six => "Six",
+        Rank::Seven => "Seven",
+        Rank::Eight => "Eight",
+        Rank::Nine => "Nine",
+        Rank::Ten => "Ten",
+        Rank::Jack => "Jack",
+        Rank::Queen => "Queen",
+        Rank::King => "King",
+        Rank::Ace => "Ace",
+    }
+}
+
+fn rank_plural(rank: Rank) -> String {
+    match rank {
+        Rank::Six => "Sixes".to_string(),
+        _ => format!("{}s", rank_name(rank)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    fn started(hand_number: u64, dealer: &str) -> HandEvent {
+        HandEvent::HandStarted {
+            hand_number,
+            dealer: dealer.to_string(),
+        }
+    }
+
+    fn blind(player: &str, amount: u64) -> HandEvent {
+        HandEvent::BlindPosted {
+            player: player.to_string(),
+            amount,
+        }
+    }
+
+    fn acted(player: &str, action: PlayerAction) -> HandEvent {
+        HandEvent::PlayerActed {
+            player: player.to_string(),
+            action,
+        }
+    }
+
+    fn dealt(phase: GamePhase, cards: Vec<Card>) -> HandEvent {
+        HandEvent::CommunityCardsDealt { phase, cards }
+    }
+
+    fn revealed(player: &str, hand_rank: HandRank) -> HandEvent {
+        HandEvent::ShowdownRevealed {
+            player: player.to_string(),
+            hand_rank,
+        }
+    }
+
+    fn awarded(player: &str, amount: u64) -> HandEvent {
+        HandEvent::PotAwarded {
+            player: player.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_summary_of_pre_flop_fold() {
+        let events = vec![
+            started(42, "Alice"),
+            blind("Bob", 10),
+            blind("Carol", 20),
+            acted("Alice", PlayerAction::Raise(100)),
+            acted("Bob", PlayerAction::Fold),
+            acted("Carol", PlayerAction::Fold),
+            awarded("Alice", 130),
+        ];
+        let config = TableConfig::default();
+
+        assert_eq!(
+            generate_hand_summary(&events, &config),
+            "Hand #42 — Alice raised to 100 on the button, Bob folded, Carol folded. Alice wins 130."
+        );
+        assert_eq!(
+            hand_highlight(&events, &config),
+            HandHighlight::BigRaiseFold
+        );
+    }
+
+    #[test]
+    fn test_summary_of_showdown_with_straight() {
+        let events = vec![
+            started(7, "Alice"),
+            blind("Alice", 10),
+            blind("Bob", 20),
+            acted("Alice", PlayerAction::Call),
+            acted("Bob", PlayerAction::Check),
+            dealt(
+                GamePhase::Flop,
+                vec![
+                    card(Rank::Nine, Suit::Hearts),
+                    card(Rank::Eight, Suit::Diamonds),
+                    card(Rank::Two, Suit::Clubs),
+                ],
+            ),
+            acted("Bob", PlayerAction::Check),
+            acted("Alice", PlayerAction::Bet(40)),
+            acted("Bob", PlayerAction::Call),
+            dealt(GamePhase::Turn, vec![card(Rank::Seven, Suit::Spades)]),
+            acted("Bob", PlayerAction::Check),
+            acted("Alice", PlayerAction::Check),
+            dealt(GamePhase::River, vec![card(Rank::King, Suit::Diamonds)]),
+            acted("Bob", PlayerAction::Check),
+            acted("Alice", PlayerAction::Check),
+            revealed("Alice", HandRank::Straight(Rank::Jack)),
+            revealed(
+                "Bob",
+                HandRank::OnePair(Rank::King, vec![Rank::Nine, Rank::Eight, Rank::Seven]),
+            ),
+            awarded("Alice", 120),
+        ];
+        let config = TableConfig::default();
+
+        assert_eq!(
+            generate_hand_summary(&events, &config),
+            "Hand #7 — Alice called on the button, Bob checked. Flop: 9h-8d-2c. \
+             Bob checked, Alice bet 40, Bob called. Turn: 7s. Bob checked, Alice checked. \
+             River: Kd. Bob checked, Alice checked. Alice wins 120 with a straight, Jack high."
+        );
+        assert_eq!(hand_highlight(&events, &config), HandHighlight::Showdown);
+    }
+
+    #[test]
+    fn test_summary_of_split_pot() {
+        let events = vec![
+            started(3, "Bob"),
+            blind("Bob", 10),
+            blind("Alice", 20),
+            acted("Bob", PlayerAction::Raise(100)),
+            acted("Alice", PlayerAction::Call),
+            dealt(
+                GamePhase::Flop,
+                vec![
+                    card(Rank::Ace, Suit::Spades),
+                    card(Rank::King, Suit::Diamonds),
+                    card(Rank::Queen, Suit::Hearts),
+                ],
+            ),
+            dealt(GamePhase::Turn, vec![card(Rank::Jack, Suit::Clubs)]),
+            dealt(GamePhase::River, vec![card(Rank::Ten, Suit::Spades)]),
+            revealed("Alice", HandRank::Straight(Rank::Ace)),
+            revealed("Bob", HandRank::Straight(Rank::Ace)),
+            awarded("Alice", 100),
+            awarded("Bob", 100),
+        ];
+        let config = TableConfig::default();
+
+        assert_eq!(
+            generate_hand_summary(&events, &config),
+            "Hand #3 — Bob raised to 100 on the button, Alice called. Flop: As-Kd-Qh. \
+             Turn: Jc. River: Ts. Alice and Bob split 200 with a straight, Ace high."
+        );
+        assert_eq!(hand_highlight(&events, &config), HandHighlight::SplitPot);
+    }
+}
@@ -0,0 +1,57 @@
+//! Pot odds arithmetic for decision support
+
+/// Minimum share of the final pot a call needs to break even.
+/// `current_pot` is everything already in the middle, including the bet
+/// being faced.
+pub fn compute_break_even_equity(call_amount: u64, current_pot: u64) -> f64 {
+    let pot_after_call = current_pot + call_amount;
+    if pot_after_call == 0 {
+        return 0.0;
+    }
+    call_amount as f64 / pot_after_call as f64
+}
+
+/// True when `equity` (0.0 to 1.0) beats the price of the call
+pub fn is_profitable_call(equity: f64, call_amount: u64, current_pot: u64) -> bool {
+    equity > compute_break_even_equity(call_amount, current_pot)
+}
+
+/// How often a bet of `bet_size` into `pot` must make everyone fold to
+/// break even as a pure bluff
+pub fn compute_bluff_break_even_equity(bet_size: u64, pot: u64) -> f64 {
+    compute_break_even_equity(bet_size, pot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_break_even_equity() {
+        // Calling an amount equal to the pot needs half of the final pot
+        assert_close(compute_break_even_equity(100, 100), 0.5);
+        // Calling half the pot needs a third
+        assert_close(compute_break_even_equity(50, 100), 1.0 / 3.0);
+        // Facing a pot-sized bet: 100 into 100 makes the pot 200 to call 100
+        assert_close(compute_break_even_equity(100, 200), 1.0 / 3.0);
+        assert_close(compute_break_even_equity(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_profitable_call() {
+        assert!(is_profitable_call(0.40, 100, 200));
+        assert!(!is_profitable_call(0.30, 100, 200));
+        assert!(!is_profitable_call(0.5, 100, 100));
+    }
+
+    #[test]
+    fn test_bluff_break_even_equity() {
+        assert_close(compute_bluff_break_even_equity(100, 100), 0.5);
+        assert_close(compute_bluff_break_even_equity(50, 100), 1.0 / 3.0);
+        assert_close(compute_bluff_break_even_equity(200, 100), 2.0 / 3.0);
+    }
+}
@@ -0,0 +1,590 @@
+//! Main pot and side pot bookkeeping for multi-way all-ins
+
+use crate::hand_evaluator::LowHand;
+use poker_types::{HandRank, PokerPlayer, SidePot};
+use serde::{Deserialize, Serialize};
+
+/// How one pot was won, reported with `HandComplete`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PotAward {
+    /// 0 for the main pot, then each side pot in turn
+    pub pot_index: usize,
+    pub amount: u64,
+    /// More than one when hands tie, in seat order from the button
+    pub winners: Vec<String>,
+    /// First winner left of the button, set when the split leaves odd chips
+    pub odd_chip_to: Option<String>,
+}
+
+/// Tracks every player's contribution for the hand and layers it into a
+/// main pot plus one side pot per distinct all-in level.
+#[derive(Debug, Clone, Default)]
+pub struct PotManager {
+    pots: Vec<SidePot>,
+    /// Total put in this hand, in order of first contribution (seat order)
+    contributions: Vec<(String, u64)>,
+    all_in: Vec<String>,
+    folded: Vec<String>,
+    /// Seats starting left of the button; decides who gets odd chips
+    seat_order: Vec<String>,
+    /// Chips put in beyond the stack a player started the hand with
+    over_stake: Vec<(String, u64)>,
+}
+
+impl PotManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Layer the pots from what each player has put in this hand, in seat
+    /// order. Used to rebuild the pots from saved state after every action.
+    ///
+    /// Under table stakes only the stack a player started the hand with is
+    /// at risk. `stakes` are those stacks, as `hand_start_stakes` returns
+    /// them: anything put in beyond a stake stays out of the pots and is
+    /// listed by `over_stake`, and a player who has put in their whole stake
+    /// is capped there like an all-in.
+    pub fn from_players(players: &[PokerPlayer], stakes: &[(String, u64)]) -> Self {
+        let stake = |player: &PokerPlayer| {
+            stakes
+                .iter()
+                .find(|(name, _)| *name == player.name)
+                .map(|(_, stake)| *stake)
+        };
+        let at_risk = |player: &PokerPlayer| {
+            stake(player).map_or(player.total_bet_this_hand, |stake| {
+                player.total_bet_this_hand.min(stake)
+            })
+        };
+        let contributions: Vec<(String, u64)> = players
+            .iter()
+            .filter(|p| at_risk(p) > 0)
+            .map(|p| (p.name.clone(), at_risk(p)))
+            .collect();
+        let all_in = players
+            .iter()
+            .filter(|p| p.is_all_in || stake(p).is_some_and(|stake| p.total_bet_this_hand >= stake))
+            .map(|p| p.name.clone())
+            .collect();
+        let folded = players
+            .iter()
+            .filter(|p| p.has_folded)
+            .map(|p| p.name.clone())
+            .collect();
+        let mut manager = Self::from_hand(contributions, all_in, folded);
+        manager.over_stake = players
+            .iter()
+            .filter(|p| p.total_bet_this_hand > at_risk(p))
+            .map(|p| (p.name.clone(), p.total_bet_this_hand - at_risk(p)))
+            .collect();
+        manager
+    }
+
+    pub fn from_hand(
+        contributions: Vec<(String, u64)>,
+        all_in: Vec<String>,
+        folded: Vec<String>,
+    ) -> Self {
+        let mut manager = Self {
+            pots: Vec::new(),
+            contributions,
+            all_in,
+            folded,
+            seat_order: Vec::new(),
+            over_stake: Vec::new(),
+        };
+        manager.rebuild();
+        manager
+    }
+
+    /// Add chips for `player` and re-layer the pots
+    pub fn contribute(&mut self, player: &str, amount: u64) {
+        match self.contributions.iter_mut().find(|(name, _)| name == player) {
+            Some((_, total)) => *total += amount,
+            None => self.contributions.push((player.to_string(), amount)),
+        }
+        self.rebuild();
+    }
+
+    /// Final contribution of a player who is now all-in; caps what they can win
+    pub fn contribute_all_in(&mut self, player: &str, amount: u64) {
+        if !self.all_in.iter().any(|name| name == player) {
+            self.all_in.push(player.to_string());
+        }
+        self.contribute(player, amount);
+    }
+
+    /// A folded player's chips stay in the pots but they can no longer win
+    pub fn fold(&mut self, player: &str) {
+        if !self.folded.iter().any(|name| name == player) {
+            self.folded.push(player.to_string());
+        }
+        self.rebuild();
+    }
+
+    pub fn pots(&self) -> &[SidePot] {
+        &self.pots
+    }
+
+    pub fn total(&self) -> u64 {
+        self.pots.iter().map(|pot| pot.amount).sum()
+    }
+
+    /// Chips each player put in beyond their starting stack; they were
+    /// never at risk and go back to the player
+    pub fn over_stake(&self) -> &[(String, u64)] {
+        &self.over_stake
+    }
+
+    pub fn eligible_players(&self, pot_index: usize) -> &[String] {
+        self.pots
+            .get(pot_index)
+            .map(|pot| pot.eligible_players.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Split pots give odd chips to the first winner left of the button.
+    /// Without a button they go to the earliest contributor.
+    pub fn set_button(&mut self, seats: &[String], dealer_position: usize) {
+        self.seat_order = (1..=seats.len())
+            .map(|offset| seats[(dealer_position + offset) % seats.len()].clone())
+            .collect();
+    }
+
+    fn in_seat_order<'a>(&self, mut winners: Vec<&'a String>) -> Vec<&'a String> {
+        winners.sort_by_key(|name| {
+            self.seat_order
+                .iter()
+                .position(|seat| seat == *name)
+                .unwrap_or(usize::MAX)
+        });
+        winners
+    }
+
+    /// Award every pot to the best eligible hand. Ties split evenly with odd
+    /// chips going to the first winner left of the button. Players missing
+    /// from `player_hand_ranks` (mucked) cannot win. Any ordered ranking
+    /// works, so Short Deck passes `ShortDeckRank`s.
+    pub fn finalize<R: Ord>(&self, player_hand_ranks: &[(&str, R)]) -> Vec<(String, u64)> {
+        let mut payouts: Vec<(String, u64)> = Vec::new();
+        for award in self.awards(player_hand_ranks) {
+            let winners: Vec<&String> = award.winners.iter().collect();
+            Self::split(&mut payouts, award.amount, &winners);
+        }
+        payouts
+    }
+
+    /// Who wins each pot, with ties spelled out
+    pub fn awards<R: Ord>(&self, player_hand_ranks: &[(&str, R)]) -> Vec<PotAward> {
+        let mut awards = Vec::new();
+
+        for (pot_index, pot) in self.pots.iter().enumerate() {
+            let contenders: Vec<(&String, &R)> = pot
+                .eligible_players
+                .iter()
+                .filter_map(|name| {
+                    player_hand_ranks
+                        .iter()
+                        .find(|(ranked, _)| ranked == name)
+                        .map(|(_, rank)| (name, rank))
+                })
+                .collect();
+            let Some(best) = contenders.iter().map(|(_, rank)| *rank).max() else {
+                continue;
+            };
+            let winners = self.in_seat_order(
+                contenders
+                    .iter()
+                    .filter(|(_, rank)| *rank == best)
+                    .map(|(name, _)| *name)
+                    .collect(),
+            );
+            let odd_chips = pot.amount % winners.len() as u64 > 0;
+            awards.push(PotAward {
+                pot_index,
+                amount: pot.amount,
+                winners: winners.iter().map(|name| (*name).clone()).collect(),
+                odd_chip_to: odd_chips.then(|| winners[0].clone()),
+            });
+        }
+
+        awards
+    }
+
+    /// Hi-lo showdown: each pot is halved between the best high hand and the
+    /// best qualifying low, with the odd chip going high. A pot with no
+    /// qualifying low goes entirely to the high hand.
+    pub fn finalize_hi_lo(
+        &self,
+        player_hand_ranks: &[(&str, HandRank)],
+        player_low_hands: &[(&str, Option<LowHand>)],
+    ) -> Vec<(String, u64)> {
+        let mut payouts: Vec<(String, u64)> = Vec::new();
+
+        for pot in &self.pots {
+            let high: Vec<(&String, &HandRank)> = pot
+                .eligible_players
+                .iter()
+                .filter_map(|name| {
+                    player_hand_ranks
+                        .iter()
+                        .find(|(ranked, _)| ranked == name)
+                        .map(|(_, rank)| (name, rank))
+                })
+                .collect();
+            let Some(best_high) = high.iter().map(|(_, rank)| *rank).max() else {
+                continue;
+            };
+            let high_winners = self.in_seat_order(
+                high.iter()
+                    .filter(|(_, rank)| *rank == best_high)
+                    .map(|(name, _)| *name)
+                    .collect(),
+            );
+
+            let low: Vec<(&String, &LowHand)> = pot
+                .eligible_players
+                .iter()
+                .filter_map(|name| {
+                    player_low_hands
+                        .iter()
+                        .find(|(ranked, _)| ranked == name)
+                        .and_then(|(_, low)| low.as_ref())
+                        .map(|low| (name, low))
+                })
+                .collect();
+            let Some(best_low) = low.iter().map(|(_, low)| *low).min() else {
+                Self::split(&mut payouts, pot.amount, &high_winners);
+                continue;
+            };
+            let low_winners = self.in_seat_order(
+                low.iter()
+                    .filter(|(_, low)| *low == best_low)
+                    .map(|(name, _)| *name)
+                    .collect(),
+            );
+
+            let low_half = pot.amount / 2;
+            Self::split(&mut payouts, pot.amount - low_half, &high_winners);
+            Self::split(&mut payouts, low_half, &low_winners);
+        }
+
+        payouts
+    }
+
+    /// Share `amount` evenly, odd chips to the first winners listed
+    fn split(payouts: &mut Vec<(String, u64)>, amount: u64, winners: &[&String]) {
+        let share = amount / winners.len() as u64;
+        let mut odd_chips = amount % winners.len() as u64;
+        for winner in winners {
+            let mut prize = share;
+            if odd_chips > 0 {
+                prize += 1;
+                odd_chips -= 1;
+            }
+            match payouts.iter_mut().find(|(name, _)| name == *winner) {
+                Some((_, total)) => *total += prize,
+                None => payouts.push(((*winner).clone(), prize)),
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let is_live = |name: &String| !self.folded.contains(name);
+
+        let mut levels: Vec<u64> = self
+            .contributions
+            .iter()
+            .filter(|(name, _)| self.all_in.contains(name) && is_live(name))
+            .map(|(_, total)| *total)
+            .collect();
+        let top = self.contributions.iter().map(|(_, total)| *total).max().unwrap_or(0);
+        levels.push(top);
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<SidePot> = Vec::new();
+        let mut previous = 0;
+        for level in levels {
+            let amount: u64 = self
+                .contributions
+                .iter()
+                .map(|(_, total)| (*total).min(level) - (*total).min(previous))
+                .sum();
+            let eligible: Vec<String> = self
+                .contributions
+                .iter()
+                .filter(|(name, total)| *total >= level && is_live(name))
+                .map(|(name, _)| name.clone())
+                .collect();
+            previous = level;
+            if amount == 0 {
+                continue;
+            }
+            match pots.last_mut() {
+                // Nobody live reached this level: the chips stay with the pot below
+                Some(last) if eligible.is_empty() => last.amount += amount,
+                _ => pots.push(SidePot {
+                    amount,
+                    eligible_players: eligible,
+                }),
+            }
+        }
+
+        self.pots = pots;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::Rank;
+
+    fn pair(rank: Rank) -> HandRank {
+        HandRank::OnePair(rank, vec![Rank::Two, Rank::Three, Rank::Four])
+    }
+
+    #[test]
+    fn test_two_way_all_in_returns_excess_to_big_stack() {
+        let mut pots = PotManager::new();
+        pots.contribute_all_in("alice", 100);
+        pots.contribute("bob", 300);
+
+        assert_eq!(pots.pots().len(), 2);
+        assert_eq!(pots.pots()[0].amount, 200);
+        assert_eq!(pots.eligible_players(1), ["bob".to_string()]);
+
+        // Short stack wins the main pot only
+        let payouts = pots.finalize(&[("alice", pair(Rank::Ace)), ("bob", pair(Rank::King))]);
+        assert_eq!(
+            payouts,
+            vec![("alice".to_string(), 200), ("bob".to_string(), 200)]
+        );
+    }
+
+    #[test]
+    fn test_three_way_all_in_layers_pots() {
+        let mut pots = PotManager::new();
+        pots.contribute_all_in("alice", 100);
+        pots.contribute_all_in("bob", 200);
+        pots.contribute_all_in("carol", 300);
+
+        let amounts: Vec<u64> = pots.pots().iter().map(|pot| pot.amount).collect();
+        assert_eq!(amounts, vec![300, 200, 100]);
+        assert_eq!(pots.eligible_players(0).len(), 3);
+        assert_eq!(pots.eligible_players(1), ["bob".to_string(), "carol".to_string()]);
+
+        let payouts = pots.finalize(&[
+            ("alice", pair(Rank::Ace)),
+            ("bob", pair(Rank::King)),
+            ("carol", pair(Rank::Queen)),
+        ]);
+        assert_eq!(
+            payouts,
+            vec![
+                ("alice".to_string(), 300),
+                ("bob".to_string(), 200),
+                ("carol".to_string(), 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_four_way_all_in_with_uneven_stacks_and_tie() {
+        let mut pots = PotManager::new();
+        pots.contribute_all_in("alice", 50);
+        pots.contribute_all_in("bob", 100);
+        pots.contribute_all_in("carol", 250);
+        pots.contribute("dave", 400);
+
+        let amounts: Vec<u64> = pots.pots().iter().map(|pot| pot.amount).collect();
+        assert_eq!(amounts, vec![200, 150, 300, 150]);
+        assert_eq!(pots.total(), 800);
+
+        // Bob and Carol tie for best; Alice's main pot share goes to them too
+        let payouts = pots.finalize(&[
+            ("alice", pair(Rank::Two)),
+            ("bob", pair(Rank::Ace)),
+            ("carol", pair(Rank::Ace)),
+            ("dave", pair(Rank::Three)),
+        ]);
+        assert_eq!(
+            payouts,
+            vec![
+                ("bob".to_string(), 175),
+                ("carol".to_string(), 475),
+                ("dave".to_string(), 150),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_folded_chips_stay_in_pot() {
+        let mut pots = PotManager::new();
+        pots.contribute_all_in("alice", 100);
+        pots.contribute("bob", 150);
+        pots.contribute("carol", 150);
+        pots.fold("carol");
+
+        assert_eq!(pots.total(), 400);
+        assert_eq!(pots.pots()[0].eligible_players, vec!["alice", "bob"]);
+        assert_eq!(pots.pots()[1].eligible_players, vec!["bob"]);
+
+        let payouts = pots.finalize(&[("alice", pair(Rank::Ace)), ("bob", pair(Rank::King))]);
+        assert_eq!(
+            payouts,
+            vec![("alice".to_string(), 300), ("bob".to_string(), 100)]
+        );
+    }
+
+    #[test]
+    fn test_hi_lo_splits_pot_with_odd_chip_high() {
+        let mut pots = PotManager::new();
+        pots.contribute("alice", 101);
+        pots.contribute("bob", 101);
+        pots.contribute("carol", 101);
+
+        let payouts = pots.finalize_hi_lo(
+            &[
+                ("alice", pair(Rank::Ace)),
+                ("bob", pair(Rank::Two)),
+                ("carol", pair(Rank::Three)),
+            ],
+            &[
+                ("alice", None),
+                ("bob", Some(LowHand(vec![7, 5, 4, 2, 1]))),
+                ("carol", Some(LowHand(vec![8, 5, 4, 2, 1]))),
+            ],
+        );
+        assert_eq!(
+            payouts,
+            vec![("alice".to_string(), 152), ("bob".to_string(), 151)]
+        );
+    }
+
+    #[test]
+    fn test_hi_lo_without_qualifying_low_scoops() {
+        let mut pots = PotManager::new();
+        pots.contribute_all_in("alice", 100);
+        pots.contribute("bob", 200);
+
+        // Bob's low takes half the main pot; the side pot is his alone
+        let payouts = pots.finalize_hi_lo(
+            &[("alice", pair(Rank::Ace)), ("bob", pair(Rank::King))],
+            &[("alice", None), ("bob", Some(LowHand(vec![6, 5, 4, 3, 1])))],
+        );
+        assert_eq!(
+            payouts,
+            vec![("alice".to_string(), 100), ("bob".to_string(), 200)]
+        );
+    }
+
+    #[test]
+    fn test_pots_rebuilt_from_saved_contributions() {
+        let pots = PotManager::from_hand(
+            vec![
+                ("alice".to_string(), 100),
+                ("bob".to_string(), 300),
+                ("carol".to_string(), 300),
+            ],
+            vec!["alice".to_string()],
+            vec!["carol".to_string()],
+        );
+        assert_eq!(
+            pots.pots(),
+            [
+                SidePot {
+                    amount: 300,
+                    eligible_players: vec!["alice".to_string(), "bob".to_string()],
+                },
+                SidePot {
+                    amount: 400,
+                    eligible_players: vec!["bob".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chips_won_mid_hand_are_not_at_risk() {
+        // Alice started with 500 and won chips since; she has put in 700,
+        // against Bob's 200 all-in and Carol's 700
+        let player = |name: &str, chips: u64, total_bet: u64| {
+            let mut player = PokerPlayer::new(name, name, chips, 0);
+            player.total_bet_this_hand = total_bet;
+            player.is_all_in = chips == 0;
+            player
+        };
+        let players = [
+            player("alice", 200, 700),
+            player("bob", 0, 200),
+            player("carol", 300, 700),
+        ];
+        let stakes = [
+            ("alice".to_string(), 500),
+            ("bob".to_string(), 200),
+            ("carol".to_string(), 1000),
+        ];
+        let pots = PotManager::from_players(&players, &stakes);
+
+        let amounts: Vec<u64> = pots.pots().iter().map(|pot| pot.amount).collect();
+        assert_eq!(amounts, vec![600, 600, 200]);
+        assert_eq!(
+            pots.eligible_players(1),
+            ["alice".to_string(), "carol".to_string()]
+        );
+        assert_eq!(pots.eligible_players(2), ["carol".to_string()]);
+        assert_eq!(pots.over_stake(), [("alice".to_string(), 200)]);
+
+        // Alice can win no more from Carol than the 500 she started with
+        let payouts = pots.finalize(&[
+            ("alice", pair(Rank::Ace)),
+            ("bob", pair(Rank::King)),
+            ("carol", pair(Rank::Queen)),
+        ]);
+        assert_eq!(
+            payouts,
+            vec![("alice".to_string(), 1200), ("carol".to_string(), 200)]
+        );
+
+        // Without recorded stakes every chip put in is layered
+        let unchecked = PotManager::from_players(&players, &[]);
+        assert_eq!(unchecked.total(), 1600);
+        assert!(unchecked.over_stake().is_empty());
+    }
+
+    #[test]
+    fn test_odd_chip_goes_left_of_the_button() {
+        let mut pots = PotManager::new();
+        pots.contribute("alice", 100);
+        pots.contribute("bob", 1);
+        pots.contribute("carol", 100);
+        pots.fold("bob");
+        let seats = ["alice".to_string(), "bob".to_string(), "carol".to_string()];
+        let ranks = [("alice", pair(Rank::Ace)), ("carol", pair(Rank::Ace))];
+
+        // Bob has the button, so Carol is first to his left
+        pots.set_button(&seats, 1);
+        let awards = pots.awards(&ranks);
+        assert_eq!(
+            awards,
+            vec![PotAward {
+                pot_index: 0,
+                amount: 201,
+                winners: vec!["carol".to_string(), "alice".to_string()],
+                odd_chip_to: Some("carol".to_string()),
+            }]
+        );
+        assert_eq!(
+            pots.finalize(&ranks),
+            vec![("carol".to_string(), 101), ("alice".to_string(), 100)]
+        );
+
+        // With Carol on the button the chip goes round to Alice
+        pots.set_button(&seats, 2);
+        assert_eq!(
+            pots.finalize(&ranks),
+            vec![("alice".to_string(), 101), ("carol".to_string(), 100)]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Pre-flop starting hand classes and their equity against a random hand.
+//!
+//! The equity table is produced by `build.rs` so nothing is simulated on-chain.
+
+use poker_types::{Card, Rank};
+use serde::{Deserialize, Serialize};
+
+include!(concat!(env!("OUT_DIR"), "/generated_equity_table.rs"));
+
+/// One of the 169 strategically distinct hold'em starting hands
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct StartingHandClass {
+    pub high: Rank,
+    pub low: Rank,
+    /// Always false for pocket pairs
+    pub suited: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StartingHandEquity {
+    pub class: StartingHandClass,
+    pub preflop_equity_vs_random: f32,
+}
+
+impl StartingHandClass {
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Standard shorthand such as "AA", "AKs" or "72o"
+    pub fn notation(&self) -> String {
+        let suffix = if self.is_pair() {
+            ""
+        } else if self.suited {
+            "s"
+        } else {
+            "o"
+        };
+        format!("{}{}{}", rank_char(self.high), rank_char(self.low), suffix)
+    }
+}
+
+pub fn preflop_equity_class(cards: &[Card; 2]) -> StartingHandClass {
+    let (high, low) = if cards[0].rank >= cards[1].rank {
+        (cards[0], cards[1])
+    } else {
+        (cards[1], cards[0])
+    };
+    StartingHandClass {
+        high: high.rank,
+        low: low.rank,
+        suited: high.rank != low.rank && high.suit == low.suit,
+    }
+}
+
+pub fn preflop_equity(cards: &[Card; 2]) -> StartingHandEquity {
+    let class = preflop_equity_class(cards);
+    let preflop_equity_vs_random = EQUITY_TABLE
+        .iter()
+        .find(|(entry, _)| *entry == class)
+        .map(|(_, equity)| *equity)
+        .unwrap_or_default();
+    StartingHandEquity {
+        class,
+        preflop_equity_vs_random,
+    }
+}
+
+pub fn rank_char(rank: Rank) -> char {
+    match rank {
+        Rank::Two => '2',
+        Rank::Three => '3',
+        Rank::Four => '4',
+        Rank::Five => '5',
+        Rank::Six => '6',
+        Rank::Seven => '7',
+        Rank::Eight => '8',
+        Rank::Nine => '9',
+        Rank::Ten => 'T',
+        Rank::Jack => 'J',
+        Rank::Queen => 'Q',
+        Rank::King => 'K',
+        Rank::Ace => 'A',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::Suit;
+    use std::collections::HashSet;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    #[test]
+    fn test_pocket_aces_equity() {
+        let aces = [card(Rank::Ace, Suit::Spades), card(Rank::Ace, Suit::Hearts)];
+
+        let equity = preflop_equity(&aces);
+
+        assert_eq!(equity.class.notation(), "AA");
+        assert!((equity.preflop_equity_vs_random - 0.85).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_seven_deuce_offsuit_equity() {
+        let hand = [card(Rank::Two, Suit::Clubs), card(Rank::Seven, Suit::Hearts)];
+
+        let equity = preflop_equity(&hand);
+
+        assert_eq!(equity.class.notation(), "72o");
+        assert!((equity.preflop_equity_vs_random - 0.35).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_all_169_classes_present() {
+        let classes: HashSet<String> = EQUITY_TABLE
+            .iter()
+            .map(|(class, _)| class.notation())
+            .collect();
+
+        assert_eq!(EQUITY_TABLE.len(), 169);
+        assert_eq!(classes.len(), 169);
+        assert!(classes.contains("AKs") && classes.contains("AKo"));
+    }
+}
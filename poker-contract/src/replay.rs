@@ -0,0 +1,441 @@
+//! Deterministic re-execution of a hand from its `HandEvent` log
+
+use crate::pot_manager::PotManager;
+use poker_types::{Card, HandEvent, HandRank, PlayerAction, PokerError, TableConfig};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayResult {
+    pub final_stacks: Vec<(String, u64)>,
+    pub community_cards: Vec<Card>,
+    /// Player awarded the largest share of the pot
+    pub winner: String,
+    pub pot: u64,
+    /// Events that could not be applied, or awards that disagree with the replay
+    pub discrepancies: Vec<String>,
+}
+
+struct Seat {
+    name: String,
+    stack: u64,
+    committed_this_street: u64,
+    folded: bool,
+    acted_this_street: bool,
+}
+
+impl Seat {
+    fn can_act(&self) -> bool {
+        !self.folded && self.stack > 0
+    }
+}
+
+struct Replay {
+    seats: Vec<Seat>,
+    dealer: usize,
+    to_act: Option<usize>,
+    street_bet: u64,
+    min_raise: u64,
+    big_blind: u64,
+    pots: PotManager,
+    community_cards: Vec<Card>,
+    revealed: Vec<(String, HandRank)>,
+    awarded: Vec<(String, u64)>,
+    discrepancies: Vec<String>,
+}
+
+/// Replay `events` from `initial_stacks` (in seat order) and report the
+/// resulting stacks. Invalid events are skipped and listed as discrepancies.
+pub fn replay_hand(
+    events: &[HandEvent],
+    config: &TableConfig,
+    initial_stacks: &[(String, u64)],
+) -> ReplayResult {
+    let mut replay = Replay {
+        seats: initial_stacks
+            .iter()
+            .map(|(name, stack)| Seat {
+                name: name.clone(),
+                stack: *stack,
+                committed_this_street: 0,
+                folded: false,
+                acted_this_street: false,
+            })
+            .collect(),
+        dealer: 0,
+        to_act: None,
+        street_bet: 0,
+        min_raise: config.big_blind,
+        big_blind: config.big_blind,
+        pots: PotManager::new(),
+        community_cards: Vec::new(),
+        revealed: Vec::new(),
+        awarded: Vec::new(),
+        discrepancies: Vec::new(),
+    };
+
+    for (index, event) in events.iter().enumerate() {
+        if let Err(reason) = replay.apply(event) {
+            replay
+                .discrepancies
+                .push(format!("event {}: {}", index, reason));
+        }
+    }
+    replay.finish()
+}
+
+impl Replay {
+    fn apply(&mut self, event: &HandEvent) -> Result<(), PokerError> {
+        match event {
+            HandEvent::HandStarted { dealer, .. } => {
+                self.dealer = self.seat_of(dealer)?;
+                Ok(())
+            }
+            HandEvent::BlindPosted { player, amount } => {
+                let seat = self.seat_of(player)?;
+                self.put_in(seat, *amount)?;
+                self.street_bet = self.street_bet.max(self.seats[seat].committed_this_street);
+                self.to_act = self.next_to_act(seat);
+                Ok(())
+            }
+            HandEvent::PlayerActed { player, action } => self.act(player, action),
+            HandEvent::CommunityCardsDealt { cards, .. } => {
+                if !self.betting_complete() {
+                    return Err(PokerError::InvalidOperation(
+                        "community cards dealt before betting finished".to_string(),
+                    ));
+                }
+                for seat in &mut self.seats {
+                    seat.committed_this_street = 0;
+                    seat.acted_this_street = false;
+                }
+                self.street_bet = 0;
+                self.min_raise = self.big_blind;
+                self.to_act = self.next_to_act(self.dealer);
+                self.community_cards.extend_from_slice(cards);
+                Ok(())
+            }
+            HandEvent::ShowdownRevealed { player, hand_rank } => {
+                let seat = self.seat_of(player)?;
+                if self.seats[seat].folded {
+                    return Err(PokerError::InvalidOperation(format!(
+                        "{} revealed a folded hand",
+                        player
+                    )));
+                }
+                self.revealed.push((player.clone(), hand_rank.clone()));
+                Ok(())
+            }
+            HandEvent::PotAwarded { player, amount } => {
+                self.seat_of(player)?;
+                self.awarded.push((player.clone(), *amount));
+                Ok(())
+            }
+            HandEvent::InitialButtonAssigned { .. } => Ok(()),
+        }
+    }
+
+    fn act(&mut self, player: &str, action: &PlayerAction) -> Result<(), PokerError> {
+        let seat = self.seat_of(player)?;
+        if self.to_act != Some(seat) {
+            return Err(PokerError::NotPlayersTurn(player.to_string()));
+        }
+        let invalid = |reason: String| PokerError::InvalidAction {
+            action: action.clone(),
+            reason,
+        };
+
+        let owed = self.street_bet - self.seats[seat].committed_this_street;
+        match action {
+            PlayerAction::Fold => {
+                self.seats[seat].folded = true;
+                self.pots.fold(player);
+            }
+            PlayerAction::Check if owed > 0 => {
+                return Err(invalid(format!("{} faces a bet of {}", player, owed)));
+            }
+            PlayerAction::Check => {}
+            PlayerAction::Call if owed == 0 => {
+                return Err(invalid(format!("{} has nothing to call", player)));
+            }
+            PlayerAction::Call => self.put_in(seat, owed.min(self.seats[seat].stack))?,
+            PlayerAction::Bet(to) | PlayerAction::Raise(to) => {
+                let raise_by = to.saturating_sub(self.street_bet);
+                if raise_by < self.min_raise {
+                    return Err(invalid(format!(
+                        "{} raised below the minimum of {}",
+                        player,
+                        self.street_bet + self.min_raise
+                    )));
+                }
+                self.put_in(seat, to - self.seats[seat].committed_this_street)?;
+                self.reopen_betting(seat, raise_by);
+            }
+            PlayerAction::AllIn => {
+                let stack = self.seats[seat].stack;
+                self.put_in(seat, stack)?;
+                let total = self.seats[seat].committed_this_street;
+                if total > self.street_bet {
+                    self.reopen_betting(seat, total - self.street_bet);
+                }
+            }
+        }
+
+        self.seats[seat].acted_this_street = true;
+        self.to_act = if self.betting_complete() {
+            None
+        } else {
+            self.next_to_act(seat)
+        };
+        Ok(())
+    }
+
+    fn put_in(&mut self, seat: usize, amount: u64) -> Result<(), PokerError> {
+        let player = &mut self.seats[seat];
+        if amount > player.stack {
+            return Err(PokerError::InsufficientChips {
+                needed: amount,
+                available: player.stack,
+            });
+        }
+        player.stack -= amount;
+        player.committed_this_street += amount;
+        if player.stack == 0 {
+            self.pots.contribute_all_in(&player.name, amount);
+        } else {
+            self.pots.contribute(&player.name, amount);
+        }
+        Ok(())
+    }
+
+    /// A full raise makes everyone else act again
+    fn reopen_betting(&mut self, raiser: usize, raise_by: u64) {
+        self.street_bet = self.seats[raiser].committed_this_street;
+        self.min_raise = self.min_raise.max(raise_by);
+        for (index, seat) in self.seats.iter_mut().enumerate() {
+            if index != raiser {
+                seat.acted_this_street = false;
+            }
+        }
+    }
+
+    fn betting_complete(&self) -> bool {
+        let live = self.seats.iter().filter(|seat| !seat.folded).count();
+        let waiting_on = self.seats.iter().filter(|seat| {
+            seat.can_act()
+                && (!seat.acted_this_street || seat.committed_this_street < self.street_bet)
+        });
+        live <= 1 || waiting_on.count() == 0
+    }
+
+    fn next_to_act(&self, after: usize) -> Option<usize> {
+        let count = self.seats.len();
+        (1..=count)
+            .map(|offset| (after + offset) % count)
+            .find(|&index| self.seats[index].can_act())
+    }
+
+    fn seat_of(&self, player: &str) -> Result<usize, PokerError> {
+        self.seats
+            .iter()
+            .position(|seat| seat.name == player)
+            .ok_or_else(|| PokerError::PlayerNotFound(player.to_string()))
+    }
+
+    fn finish(mut self) -> ReplayResult {
+        let live: Vec<&Seat> = self.seats.iter().filter(|seat| !seat.folded).collect();
+        let ranks: Vec<(&str, HandRank)> = if live.len() == 1 {
+            // Uncontested: the last player standing takes every pot
+            vec![(live[0].name.as_str(), HandRank::HighCard(Vec::new()))]
+        } else {
+            self.revealed
+                .iter()
+                .map(|(name, rank)| (name.as_str(), rank.clone()))
+                .collect()
+        };
+        let payouts = self.pots.finalize(&ranks);
+
+        for (name, amount) in &self.awarded {
+            let expected = payouts
+                .iter()
+                .find(|(winner, _)| winner == name)
+                .map(|(_, prize)| *prize)
+                .unwrap_or(0);
+            if *amount != expected {
+                self.discrepancies.push(format!(
+                    "{} was awarded {} but the replay pays {}",
+                    name, amount, expected
+                ));
+            }
+        }
+
+        let mut winner = String::new();
+        let mut best = 0;
+        for (name, prize) in &payouts {
+            if *prize > best {
+                best = *prize;
+                winner = name.clone();
+            }
+            if let Some(seat) = self.seats.iter_mut().find(|seat| &seat.name == name) {
+                seat.stack += prize;
+            }
+        }
+
+        ReplayResult {
+            final_stacks: self
+                .seats
+                .iter()
+                .map(|seat| (seat.name.clone(), seat.stack))
+                .collect(),
+            community_cards: self.community_cards,
+            winner,
+            pot: self.pots.total(),
+            discrepancies: self.discrepancies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poker_types::{GamePhase, Rank, Suit};
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    fn acted(player: &str, action: PlayerAction) -> HandEvent {
+        HandEvent::PlayerActed {
+            player: player.to_string(),
+            action,
+        }
+    }
+
+    fn blind(player: &str, amount: u64) -> HandEvent {
+        HandEvent::BlindPosted {
+            player: player.to_string(),
+            amount,
+        }
+    }
+
+    fn stacks(entries: &[(&str, u64)]) -> Vec<(String, u64)> {
+        entries
+            .iter()
+            .map(|(name, chips)| (name.to_string(), *chips))
+            .collect()
+    }
+
+    fn three_handed_showdown() -> Vec<HandEvent> {
+        vec![
+            HandEvent::HandStarted {
+                hand_number: 12,
+                dealer: "alice".to_string(),
+            },
+            blind("bob", 10),
+            blind("carol", 20),
+            acted("alice", PlayerAction::Raise(60)),
+            acted("bob", PlayerAction::Fold),
+            acted("carol", PlayerAction::Call),
+            HandEvent::CommunityCardsDealt {
+                phase: GamePhase::Flop,
+                cards: vec![
+                    card(Rank::Ace, Suit::Hearts),
+                    card(Rank::Seven, Suit::Clubs),
+                    card(Rank::Two, Suit::Diamonds),
+                ],
+            },
+            acted("carol", PlayerAction::Check),
+            acted("alice", PlayerAction::Bet(80)),
+            acted("carol", PlayerAction::Call),
+            HandEvent::CommunityCardsDealt {
+                phase: GamePhase::Turn,
+                cards: vec![card(Rank::King, Suit::Spades)],
+            },
+            acted("carol", PlayerAction::Check),
+            acted("alice", PlayerAction::Check),
+            HandEvent::CommunityCardsDealt {
+                phase: GamePhase::River,
+                cards: vec![card(Rank::Four, Suit::Hearts)],
+            },
+            acted("carol", PlayerAction::Check),
+            acted("alice", PlayerAction::Check),
+            HandEvent::ShowdownRevealed {
+                player: "alice".to_string(),
+                hand_rank: HandRank::OnePair(Rank::Ace, vec![Rank::King, Rank::Seven, Rank::Four]),
+            },
+            HandEvent::ShowdownRevealed {
+                player: "carol".to_string(),
+                hand_rank: HandRank::OnePair(Rank::King, vec![Rank::Ace, Rank::Seven, Rank::Four]),
+            },
+            HandEvent::PotAwarded {
+                player: "alice".to_string(),
+                amount: 290,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_replay_known_hand_matches_final_stacks() {
+        let initial = stacks(&[("alice", 1000), ("bob", 1000), ("carol", 1000)]);
+        let result = replay_hand(&three_handed_showdown(), &TableConfig::default(), &initial);
+
+        assert!(
+            result.discrepancies.is_empty(),
+            "{:?}",
+            result.discrepancies
+        );
+        assert_eq!(
+            result.final_stacks,
+            stacks(&[("alice", 1150), ("bob", 990), ("carol", 860)])
+        );
+        assert_eq!(result.winner, "alice");
+        assert_eq!(result.pot, 290);
+        assert_eq!(result.community_cards.len(), 5);
+    }
+
+    #[test]
+    fn test_out_of_turn_action_is_reported() {
+        let mut events = three_handed_showdown();
+        // Carol acts before Bob has responded to the raise
+        events.swap(4, 5);
+        let initial = stacks(&[("alice", 1000), ("bob", 1000), ("carol", 1000)]);
+        let result = replay_hand(&events, &TableConfig::default(), &initial);
+
+        assert_eq!(result.discrepancies[0], "event 4: It is not carol's turn");
+    }
+
+    #[test]
+    fn test_short_all_in_only_wins_main_pot() {
+        let events = vec![
+            HandEvent::HandStarted {
+                hand_number: 1,
+                dealer: "alice".to_string(),
+            },
+            blind("alice", 10),
+            blind("bob", 20),
+            acted("alice", PlayerAction::Raise(300)),
+            acted("bob", PlayerAction::AllIn),
+            HandEvent::ShowdownRevealed {
+                player: "alice".to_string(),
+                hand_rank: HandRank::OnePair(Rank::Two, vec![Rank::Ace, Rank::King, Rank::Queen]),
+            },
+            HandEvent::ShowdownRevealed {
+                player: "bob".to_string(),
+                hand_rank: HandRank::Straight(Rank::Nine),
+            },
+            HandEvent::PotAwarded {
+                player: "bob".to_string(),
+                amount: 400,
+            },
+        ];
+        let initial = stacks(&[("alice", 1000), ("bob", 200)]);
+        let result = replay_hand(&events, &TableConfig::default(), &initial);
+
+        assert_eq!(result.final_stacks, stacks(&[("alice", 800), ("bob", 400)]));
+        assert_eq!(result.winner, "bob");
+        assert_eq!(result.pot, 500);
+        assert!(
+            result.discrepancies.is_empty(),
+            "{:?}",
+            result.discrepancies
+        );
+    }
+}
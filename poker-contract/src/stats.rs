@@ -0,0 +1,49 @@
+//! Player tendency statistics built from action counts
+
+use poker_types::ActionFrequency;
+
+/// Share of actions that put money in voluntarily, 0.0 to 100.0
+pub fn vpip(freq: &ActionFrequency) -> f32 {
+    let voluntary = freq.call + freq.bet + freq.raise + freq.all_in;
+    percentage(voluntary, freq.total())
+}
+
+/// Share of non-check actions that were aggressive, 0.0 to 100.0
+pub fn aggression_frequency(freq: &ActionFrequency) -> f32 {
+    let aggressive = freq.bet + freq.raise + freq.all_in;
+    percentage(aggressive, aggressive + freq.call + freq.fold)
+}
+
+fn percentage(part: u32, whole: u32) -> f32 {
+    if whole == 0 {
+        return 0.0;
+    }
+    part as f32 * 100.0 / whole as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vpip_and_aggression_percentages() {
+        let freq = ActionFrequency {
+            fold: 4,
+            check: 2,
+            call: 2,
+            bet: 1,
+            raise: 1,
+            all_in: 0,
+        };
+        assert_eq!(vpip(&freq), 40.0);
+        assert_eq!(aggression_frequency(&freq), 25.0);
+    }
+
+    #[test]
+    fn test_no_actions_is_all_zero() {
+        let freq = ActionFrequency::default();
+        assert_eq!(freq.total(), 0);
+        assert_eq!(vpip(&freq), 0.0);
+        assert_eq!(aggression_frequency(&freq), 0.0);
+    }
+}
@@ -0,0 +1,1551 @@
+//! Poker Tournament types - shared between poker-tournament contract and service
+//!
+//! The coordinator keeps the field, the prize pool and which table chain
+//! every player sits at. Table chains run the hands and report eliminations
+//! back with `Message::PlayerEliminated`.
+
+use std::collections::BTreeMap;
+
+use async_graphql::{Request, Response};
+use linera_sdk::{
+    linera_base_types::{ChainId, ContractAbi, ServiceAbi},
+    views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
+};
+use poker_types::{BlindLevel, BlindSchedule, PokerState};
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// ABI
+// ============================================================================
+
+pub struct PokerTournamentAbi;
+
+impl ContractAbi for PokerTournamentAbi {
+    type Operation = Operation;
+    type Response = Result<(), TournamentError>;
+}
+
+impl ServiceAbi for PokerTournamentAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum TournamentStatus {
+    #[default]
+    Registering,
+    Running,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Entrant {
+    pub name: String,
+    /// Account that paid the buy-in
+    pub owner: String,
+    /// Table chain the player sits at while still in
+    pub table: Option<ChainId>,
+    /// Finishing place once out; 1 for the winner
+    pub finish: Option<u32>,
+    /// Stack as last reported by the table, plus chips bought since
+    pub chips: u64,
+    pub rebuys: u32,
+    pub took_add_on: bool,
+    /// Bounty on the player's head; grows with every knockout they make
+    pub bounty: u64,
+    /// Entered with a satellite ticket rather than the buy-in
+    pub ticket: bool,
+}
+
+/// Players at or below `stack_threshold` may buy another starting stack for
+/// the buy-in until the end of blind level `last_level`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RebuyPeriod {
+    pub last_level: u32,
+    pub stack_threshold: u64,
+    /// `None` allows unlimited rebuys
+    pub max_rebuys: Option<u32>,
+}
+
+/// One add-on per player, sold during blind level `level`: the first break
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AddOn {
+    pub level: u32,
+    pub price: u64,
+    pub chips: u64,
+}
+
+/// Chips bought from the coordinator, for the player's table to add
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChipGrant {
+    pub table: ChainId,
+    pub player_name: String,
+    pub chips: u64,
+}
+
+/// A table chain and who sits where at it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TournamentTable {
+    pub chain_id: ChainId,
+    pub seats: BTreeMap<u8, String>,
+    /// As last reported by the table: players in the order the big blind
+    /// reaches them, next hand's first
+    pub big_blind_order: Vec<String>,
+}
+
+/// A player to seat at `table` when the tournament starts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TableSeating {
+    pub table: ChainId,
+    pub seat: u8,
+    pub player_name: String,
+    pub owner: String,
+    pub chips: u64,
+}
+
+/// A player moving tables; their stack travels with them
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeatMove {
+    pub player_name: String,
+    pub from: ChainId,
+    pub to: ChainId,
+    pub seat: u8,
+}
+
+/// Prize owed to a finisher
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Payout {
+    pub player_name: String,
+    pub owner: String,
+    pub place: u32,
+    pub amount: u64,
+    /// Paid as an entry ticket to the tournament on this chain rather than
+    /// in chips; any `amount` over the ticket's value is paid in chips
+    pub ticket: Option<ChainId>,
+}
+
+/// A satellite pays its top finishers entry tickets to another tournament
+/// instead of chips
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Satellite {
+    /// Coordinator chain of the tournament the tickets are for
+    pub target: ChainId,
+    /// The target's buy-in; the pool buys as many tickets as it covers
+    pub ticket_value: u64,
+}
+
+/// Bounty owed to a player for a knockout, or to the winner for their own
+/// head when `knocked_out` is `None`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BountyPayout {
+    pub player_name: String,
+    pub owner: String,
+    pub knocked_out: Option<String>,
+    pub amount: u64,
+}
+
+/// Everything an elimination makes payable
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EliminationPayouts {
+    pub prizes: Vec<Payout>,
+    pub bounties: Vec<BountyPayout>,
+}
+
+/// Weight of first place under `PayoutStructure::Standard`; place n gets
+/// this over n
+const STANDARD_FIRST_PLACE_WEIGHT: u64 = 1_000_000_000;
+
+/// How the prize pool is split between finishing places
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PayoutStructure {
+    /// Pays the top `percent_paid` of the field, each place's share falling
+    /// off with its finishing position
+    Standard { percent_paid: u8 },
+    /// Percent of the pool for each place, first place first
+    Fixed(Vec<u8>),
+}
+
+impl PayoutStructure {
+    pub fn validate(&self) -> Result<(), TournamentError> {
+        match self {
+            PayoutStructure::Standard { percent_paid } if !(1..=100).contains(percent_paid) => {
+                Err(TournamentError::InvalidPayoutStructure(
+                    "Between 1 and 100 percent of the field must be paid".to_string(),
+                ))
+            }
+            PayoutStructure::Fixed(percentages)
+                if percentages.iter().map(|&p| p as u32).sum::<u32>() != 100 =>
+            {
+                Err(TournamentError::InvalidPayoutStructure(
+                    "Payout percentages must add up to 100".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn places_paid(&self, entrants: usize) -> usize {
+        match self {
+            PayoutStructure::Standard { percent_paid } => (entrants * *percent_paid as usize)
+                .div_ceil(100)
+                .clamp(1, entrants.max(1)),
+            PayoutStructure::Fixed(percentages) => percentages.len().min(entrants),
+        }
+    }
+
+    /// Prize for each paid place, first place first. The whole pool is paid
+    /// out: with fewer entrants than fixed places the paid places share it
+    /// in proportion, and rounding leftovers go a chip each to the best
+    /// finishers.
+    pub fn payouts(&self, pool: u64, entrants: usize) -> Vec<u64> {
+        let places = self.places_paid(entrants);
+        let weights: Vec<u64> = match self {
+            PayoutStructure::Standard { .. } => (1..=places as u64)
+                .map(|place| STANDARD_FIRST_PLACE_WEIGHT / place)
+                .collect(),
+            PayoutStructure::Fixed(percentages) => {
+                percentages[..places].iter().map(|&p| p as u64).collect()
+            }
+        };
+        let total: u64 = weights.iter().sum();
+        if total == 0 {
+            return Vec::new();
+        }
+        let mut prizes: Vec<u64> = weights
+            .iter()
+            .map(|&weight| (pool as u128 * weight as u128 / total as u128) as u64)
+            .collect();
+        // Each place loses less than a chip to rounding
+        let leftover = pool - prizes.iter().sum::<u64>();
+        for prize in prizes.iter_mut().take(leftover as usize) {
+            *prize += 1;
+        }
+        prizes
+    }
+}
+
+/// Why an operation was rejected
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TournamentError {
+    RegistrationClosed,
+    TournamentFull,
+    DuplicateEntrant(String),
+    EntrantNotFound(String),
+    DuplicateTable,
+    NotEnoughEntrants {
+        have: usize,
+        need: usize,
+    },
+    NotEnoughTables {
+        have: usize,
+        need: usize,
+    },
+    NotRunning,
+    InvalidParameters(String),
+    InvalidPayoutStructure(String),
+    /// A rebuy or add-on the rules do not allow right now
+    PurchaseNotAllowed(String),
+    NoTicket,
+}
+
+impl std::fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TournamentError::RegistrationClosed => write!(f, "Registration is closed"),
+            TournamentError::TournamentFull => write!(f, "Tournament is full"),
+            TournamentError::DuplicateEntrant(name) => write!(f, "{} is already registered", name),
+            TournamentError::EntrantNotFound(name) => {
+                write!(f, "{} is not in the tournament", name)
+            }
+            TournamentError::DuplicateTable => write!(f, "Table is already in use"),
+            TournamentError::NotEnoughEntrants { have, need } => {
+                write!(
+                    f,
+                    "Need {} entrants to start, only {} registered",
+                    need, have
+                )
+            }
+            TournamentError::NotEnoughTables { have, need } => {
+                write!(
+                    f,
+                    "Need {} tables to seat everyone, only {} added",
+                    need, have
+                )
+            }
+            TournamentError::NotRunning => write!(f, "Tournament is not running"),
+            TournamentError::InvalidParameters(reason) => write!(f, "{}", reason),
+            TournamentError::InvalidPayoutStructure(reason) => write!(f, "{}", reason),
+            TournamentError::PurchaseNotAllowed(reason) => write!(f, "{}", reason),
+            TournamentError::NoTicket => write!(f, "No entry ticket for this account"),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+// ============================================================================
+// Operations
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Operation {
+    /// Pay the buy-in and enter
+    Register { player_name: String },
+    /// Leave before the start and get the buy-in back
+    Unregister { player_name: String },
+    /// Add a table chain for the tournament to use (admin only)
+    AddTable { chain_id: ChainId },
+    /// Chain the players left move to once they fit at one table (admin
+    /// only)
+    SetFinalTable { chain_id: ChainId },
+    /// Seat the field and start play (admin only)
+    Start,
+    /// Buy another starting stack during the rebuy period
+    Rebuy { player_name: String },
+    /// Buy the add-on during the break
+    AddOn { player_name: String },
+    /// Enter with a ticket won in a satellite instead of the buy-in
+    RegisterWithTicket { player_name: String },
+}
+
+// ============================================================================
+// Messages
+// ============================================================================
+
+/// Cross-chain messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+    /// Sent by a table chain when a player there loses their last chip,
+    /// with the players who won it
+    PlayerEliminated {
+        table: ChainId,
+        player_name: String,
+        eliminated_by: Vec<String>,
+    },
+    /// Sent to the finisher once their place is decided and in the money
+    PrizeAwarded {
+        player_name: String,
+        place: u32,
+        amount: u64,
+    },
+    /// Sent by a satellite to the coordinator of its target tournament for
+    /// each ticket it awards
+    TicketAwarded { owner: String, player_name: String },
+    /// Sent to a player for each bounty they collect
+    BountyAwarded {
+        player_name: String,
+        knocked_out: Option<String>,
+        amount: u64,
+    },
+    /// Sent by a table chain between hands with every stack there, and
+    /// who posts the big blind from the next hand on
+    StackReport {
+        table: ChainId,
+        stacks: Vec<(String, u64)>,
+        big_blind_order: Vec<String>,
+    },
+}
+
+// ============================================================================
+// Parameters
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Parameters {
+    /// Chain that may add tables and start the tournament
+    pub admin_chain_id: ChainId,
+    pub buy_in: u64,
+    /// Tournament chips each entrant starts with
+    pub starting_stack: u64,
+    pub players_per_table: u8,
+    pub min_entrants: u32,
+    pub max_entrants: u32,
+    /// Levels are timed; table chains do not report hands to the coordinator
+    pub blind_schedule: BlindSchedule,
+    pub payout_structure: PayoutStructure,
+    pub rebuy_period: Option<RebuyPeriod>,
+    pub add_on: Option<AddOn>,
+    /// Registration stays open until the end of this blind level
+    pub late_registration_level: Option<u32>,
+    /// Part of each buy-in and rebuy that goes on the player's head rather
+    /// than into the prize pool
+    pub bounty: Option<u64>,
+    /// Pay entry tickets instead of chips; `payout_structure` is not used
+    pub satellite: Option<Satellite>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+#[derive(RootView)]
+#[view(context = ViewStorageContext)]
+pub struct TournamentState {
+    pub status: RegisterView<TournamentStatus>,
+    pub buy_in: RegisterView<u64>,
+    pub starting_stack: RegisterView<u64>,
+    pub players_per_table: RegisterView<u8>,
+    pub min_entrants: RegisterView<u32>,
+    pub max_entrants: RegisterView<u32>,
+    pub entrants: MapView<String, Entrant>,
+    /// Entrant names in registration order
+    pub registration_order: RegisterView<Vec<String>>,
+    pub tables: RegisterView<Vec<TournamentTable>>,
+    /// Table chain kept back for the final table
+    pub final_table_chain: RegisterView<Option<ChainId>>,
+    /// Buy-ins paid in so far
+    pub prize_pool: RegisterView<u64>,
+    pub payout_structure: RegisterView<Option<PayoutStructure>>,
+    /// Prize for each paid place, first place first. Set at the start and
+    /// raised with every rebuy and add-on.
+    pub payouts: RegisterView<Vec<u64>>,
+    pub rebuy_period: RegisterView<Option<RebuyPeriod>>,
+    pub add_on: RegisterView<Option<AddOn>>,
+    pub late_registration_level: RegisterView<Option<u32>>,
+    /// Bounty bought with each buy-in; 0 when knockouts pay nothing
+    pub entry_bounty: RegisterView<u64>,
+    pub satellite: RegisterView<Option<Satellite>>,
+    /// Unused tickets to this tournament won in satellites, by owner
+    pub tickets: MapView<String, u32>,
+    /// Eliminated players, first out first
+    pub elimination_order: RegisterView<Vec<String>>,
+    pub blind_schedule: RegisterView<BlindSchedule>,
+    pub blind_level: RegisterView<u32>,
+    pub blind_level_started_ms: RegisterView<u64>,
+}
+
+impl TournamentState {
+    pub fn initialize(&mut self, parameters: &Parameters) {
+        self.status.set(TournamentStatus::Registering);
+        self.buy_in.set(parameters.buy_in);
+        self.starting_stack.set(parameters.starting_stack);
+        self.players_per_table.set(parameters.players_per_table);
+        self.min_entrants.set(parameters.min_entrants);
+        self.max_entrants.set(parameters.max_entrants);
+        self.prize_pool.set(0);
+        self.blind_schedule.set(parameters.blind_schedule.clone());
+        self.blind_level.set(0);
+        self.payout_structure
+            .set(Some(parameters.payout_structure.clone()));
+        self.rebuy_period.set(parameters.rebuy_period.clone());
+        self.add_on.set(parameters.add_on.clone());
+        self.late_registration_level
+            .set(parameters.late_registration_level);
+        self.entry_bounty.set(parameters.bounty.unwrap_or(0));
+        self.satellite.set(parameters.satellite.clone());
+    }
+
+    pub async fn load_entrant(&self, player_name: &str) -> Result<Entrant, TournamentError> {
+        self.entrants
+            .get(&player_name.to_string())
+            .await
+            .expect("Failed to load entrant")
+            .ok_or_else(|| TournamentError::EntrantNotFound(player_name.to_string()))
+    }
+
+    fn save_entrant(&mut self, entrant: Entrant) {
+        self.entrants
+            .insert(&entrant.name.clone(), entrant)
+            .expect("Failed to save entrant");
+    }
+
+    /// `Register`: the buy-in goes into the prize pool, less the player's
+    /// bounty. During late
+    /// registration the player is seated straight away at the smallest
+    /// table; returns that seating.
+    pub async fn register(
+        &mut self,
+        owner: &str,
+        player_name: &str,
+    ) -> Result<Option<TableSeating>, TournamentError> {
+        let late = match self.status.get() {
+            TournamentStatus::Registering => false,
+            TournamentStatus::Running
+                if Self::late_registration_open(
+                    *self.late_registration_level.get(),
+                    *self.blind_level.get(),
+                ) =>
+            {
+                true
+            }
+            _ => return Err(TournamentError::RegistrationClosed),
+        };
+        if self
+            .registration_order
+            .get()
+            .iter()
+            .any(|name| name == player_name)
+        {
+            return Err(TournamentError::DuplicateEntrant(player_name.to_string()));
+        }
+        if self.registration_order.get().len() >= *self.max_entrants.get() as usize {
+            return Err(TournamentError::TournamentFull);
+        }
+        let (to_pool, bounty) = self.split_buy_in();
+        self.save_entrant(Entrant {
+            name: player_name.to_string(),
+            owner: owner.to_string(),
+            table: None,
+            finish: None,
+            chips: 0,
+            rebuys: 0,
+            took_add_on: false,
+            bounty,
+            ticket: false,
+        });
+        self.registration_order
+            .get_mut()
+            .push(player_name.to_string());
+        *self.prize_pool.get_mut() += to_pool;
+        if !late {
+            return Ok(None);
+        }
+        let seating = self.seat_late_entrant(player_name).await?;
+        self.update_payouts();
+        Ok(Some(seating))
+    }
+
+    /// `Message::TicketAwarded`
+    pub async fn issue_ticket(&mut self, owner: &str) {
+        let held = self.tickets_held(owner).await;
+        self.tickets
+            .insert(&owner.to_string(), held + 1)
+            .expect("Failed to save tickets");
+    }
+
+    pub async fn tickets_held(&self, owner: &str) -> u32 {
+        self.tickets
+            .get(&owner.to_string())
+            .await
+            .expect("Failed to load tickets")
+            .unwrap_or(0)
+    }
+
+    /// `RegisterWithTicket`: registers as usual with a ticket standing in
+    /// for the buy-in
+    pub async fn register_with_ticket(
+        &mut self,
+        owner: &str,
+        player_name: &str,
+    ) -> Result<Option<TableSeating>, TournamentError> {
+        let held = self.tickets_held(owner).await;
+        if held == 0 {
+            return Err(TournamentError::NoTicket);
+        }
+        let seating = self.register(owner, player_name).await?;
+        let mut entrant = self.load_entrant(player_name).await?;
+        entrant.ticket = true;
+        self.save_entrant(entrant);
+        self.tickets
+            .insert(&owner.to_string(), held - 1)
+            .expect("Failed to save tickets");
+        Ok(seating)
+    }
+
+    pub fn late_registration_open(last_level: Option<u32>, level: u32) -> bool {
+        last_level.is_some_and(|last| level <= last)
+    }
+
+    async fn seat_late_entrant(
+        &mut self,
+        player_name: &str,
+    ) -> Result<TableSeating, TournamentError> {
+        let per_table = *self.players_per_table.get();
+        let index = Self::table_for_late_entrant(self.tables.get(), per_table).ok_or(
+            TournamentError::NotEnoughTables {
+                have: self.tables.get().len(),
+                need: self.tables.get().len() + 1,
+            },
+        )?;
+        let table = &self.tables.get()[index];
+        let chain_id = table.chain_id;
+        let seat = Self::free_seat(&table.seats, per_table).expect("Table has a free seat");
+        self.tables.get_mut()[index]
+            .seats
+            .insert(seat, player_name.to_string());
+        let mut entrant = self.load_entrant(player_name).await?;
+        entrant.table = Some(chain_id);
+        entrant.chips = *self.starting_stack.get();
+        let seating = TableSeating {
+            table: chain_id,
+            seat,
+            player_name: entrant.name.clone(),
+            owner: entrant.owner.clone(),
+            chips: entrant.chips,
+        };
+        self.save_entrant(entrant);
+        Ok(seating)
+    }
+
+    /// The table with the fewest players that still has a free seat
+    pub fn table_for_late_entrant(
+        tables: &[TournamentTable],
+        players_per_table: u8,
+    ) -> Option<usize> {
+        (0..tables.len())
+            .filter(|&index| tables[index].seats.len() < players_per_table as usize)
+            .min_by_key(|&index| tables[index].seats.len())
+    }
+
+    /// `Unregister`: returns the buy-in to refund. A ticket entry gets its
+    /// ticket back and nothing else.
+    pub async fn unregister(&mut self, player_name: &str) -> Result<u64, TournamentError> {
+        if *self.status.get() != TournamentStatus::Registering {
+            return Err(TournamentError::RegistrationClosed);
+        }
+        let entrant = self.load_entrant(player_name).await?;
+        self.entrants
+            .remove(&player_name.to_string())
+            .expect("Failed to remove entrant");
+        self.registration_order
+            .get_mut()
+            .retain(|name| name != player_name);
+        let buy_in = *self.buy_in.get();
+        *self.prize_pool.get_mut() -= buy_in - entrant.bounty;
+        if entrant.ticket {
+            self.issue_ticket(&entrant.owner).await;
+            return Ok(0);
+        }
+        Ok(buy_in)
+    }
+
+    /// A buy-in as (into the prize pool, onto the player's bounty)
+    fn split_buy_in(&self) -> (u64, u64) {
+        let buy_in = *self.buy_in.get();
+        let bounty = (*self.entry_bounty.get()).min(buy_in);
+        (buy_in - bounty, bounty)
+    }
+
+    /// `AddTable`: tables can be added until the tournament is over
+    pub fn add_table(&mut self, chain_id: ChainId) -> Result<(), TournamentError> {
+        if *self.status.get() == TournamentStatus::Finished {
+            return Err(TournamentError::NotRunning);
+        }
+        self.check_table_unused(chain_id)?;
+        self.tables.get_mut().push(TournamentTable {
+            chain_id,
+            seats: BTreeMap::new(),
+            big_blind_order: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// `SetFinalTable`: replaces any final table chain set before
+    pub fn set_final_table(&mut self, chain_id: ChainId) -> Result<(), TournamentError> {
+        if *self.status.get() == TournamentStatus::Finished {
+            return Err(TournamentError::NotRunning);
+        }
+        self.check_table_unused(chain_id)?;
+        self.final_table_chain.set(Some(chain_id));
+        Ok(())
+    }
+
+    fn check_table_unused(&self, chain_id: ChainId) -> Result<(), TournamentError> {
+        if *self.final_table_chain.get() == Some(chain_id)
+            || self
+                .tables
+                .get()
+                .iter()
+                .any(|table| table.chain_id == chain_id)
+        {
+            return Err(TournamentError::DuplicateTable);
+        }
+        Ok(())
+    }
+
+    /// `Start`: deal the field out over as few tables as will hold it and
+    /// start the blind clock. Returns the seatings to send to the table chains.
+    pub async fn start(&mut self, now_ms: u64) -> Result<Vec<TableSeating>, TournamentError> {
+        if *self.status.get() != TournamentStatus::Registering {
+            return Err(TournamentError::RegistrationClosed);
+        }
+        let payout_structure = self
+            .payout_structure
+            .get()
+            .clone()
+            .expect("Tournament not initialized");
+        payout_structure.validate()?;
+        if self
+            .satellite
+            .get()
+            .as_ref()
+            .is_some_and(|satellite| satellite.ticket_value == 0)
+        {
+            return Err(TournamentError::InvalidParameters(
+                "Satellite tickets must have a value".to_string(),
+            ));
+        }
+        Self::validate_purchase_levels(
+            self.blind_schedule.get(),
+            self.rebuy_period.get().as_ref(),
+            self.add_on.get().as_ref(),
+        )?;
+        if self
+            .late_registration_level
+            .get()
+            .is_some_and(|level| level as usize >= self.blind_schedule.get().levels.len())
+        {
+            return Err(TournamentError::InvalidParameters(
+                "Late registration must end on a level of the blind schedule".to_string(),
+            ));
+        }
+        let names = self.registration_order.get().clone();
+        let need = *self.min_entrants.get() as usize;
+        if names.len() < need.max(2) {
+            return Err(TournamentError::NotEnoughEntrants {
+                have: names.len(),
+                need: need.max(2),
+            });
+        }
+        let per_table = *self.players_per_table.get();
+        let table_count = Self::tables_needed(names.len(), per_table);
+        if self.tables.get().len() < table_count {
+            return Err(TournamentError::NotEnoughTables {
+                have: self.tables.get().len(),
+                need: table_count,
+            });
+        }
+
+        let mut seatings = Vec::new();
+        for (index, players) in Self::assign_tables(&names, table_count)
+            .into_iter()
+            .enumerate()
+        {
+            let table = self
+                .tables
+                .get()
+                .get(index)
+                .map(|t| t.chain_id)
+                .expect("Table exists");
+            for (seat, player_name) in players.into_iter().enumerate() {
+                let mut entrant = self.load_entrant(&player_name).await?;
+                entrant.table = Some(table);
+                entrant.chips = *self.starting_stack.get();
+                seatings.push(TableSeating {
+                    table,
+                    seat: seat as u8,
+                    player_name: player_name.clone(),
+                    owner: entrant.owner.clone(),
+                    chips: *self.starting_stack.get(),
+                });
+                self.tables.get_mut()[index]
+                    .seats
+                    .insert(seat as u8, player_name);
+                self.save_entrant(entrant);
+            }
+        }
+        // Unused tables are released
+        self.tables.get_mut().truncate(table_count);
+        self.status.set(TournamentStatus::Running);
+        self.blind_level_started_ms.set(now_ms);
+        self.update_payouts();
+        Ok(seatings)
+    }
+
+    /// Prizes follow the pool as rebuys and add-ons grow it
+    fn update_payouts(&mut self) {
+        let pool = *self.prize_pool.get();
+        let entrants = self.registration_order.get().len();
+        let payouts = match (self.satellite.get(), self.payout_structure.get()) {
+            (Some(satellite), _) => Self::satellite_payouts(pool, satellite.ticket_value, entrants),
+            (None, Some(structure)) => structure.payouts(pool, entrants),
+            (None, None) => return,
+        };
+        self.payouts.set(payouts);
+    }
+
+    /// How many tickets a satellite pool buys. Someone always misses out.
+    pub fn satellite_tickets(pool: u64, ticket_value: u64, entrants: usize) -> u32 {
+        if ticket_value == 0 {
+            return 0;
+        }
+        (pool / ticket_value).min(entrants.saturating_sub(1) as u64) as u32
+    }
+
+    /// A ticket for each of the top places, and what is left over in chips
+    /// to the next place. When the pool covers more tickets than there are
+    /// places to give them to, the ticket winners share the extra in chips
+    /// instead, first place taking the odd chips.
+    pub fn satellite_payouts(pool: u64, ticket_value: u64, entrants: usize) -> Vec<u64> {
+        let tickets = Self::satellite_tickets(pool, ticket_value, entrants);
+        let leftover = pool - tickets as u64 * ticket_value;
+        if tickets > 0 && leftover >= ticket_value {
+            let share = leftover / tickets as u64;
+            let odd_chips = leftover % tickets as u64;
+            return (0..tickets)
+                .map(|place| {
+                    let odd = if place == 0 { odd_chips } else { 0 };
+                    ticket_value + share + odd
+                })
+                .collect();
+        }
+        let mut payouts = vec![ticket_value; tickets as usize];
+        if leftover > 0 {
+            payouts.push(leftover);
+        }
+        payouts
+    }
+
+    /// Tickets this satellite awards; 0 when it is not one
+    fn tickets_awarded(&self) -> u32 {
+        self.satellite.get().as_ref().map_or(0, |satellite| {
+            Self::satellite_tickets(
+                *self.prize_pool.get(),
+                satellite.ticket_value,
+                self.registration_order.get().len(),
+            )
+        })
+    }
+
+    /// Rebuys must end on a level the schedule has, and the add-on break
+    /// must come no earlier than the end of the rebuy period
+    pub fn validate_purchase_levels(
+        schedule: &BlindSchedule,
+        rebuy_period: Option<&RebuyPeriod>,
+        add_on: Option<&AddOn>,
+    ) -> Result<(), TournamentError> {
+        let levels = schedule.levels.len() as u32;
+        if let Some(period) = rebuy_period {
+            if period.last_level >= levels {
+                return Err(TournamentError::PurchaseNotAllowed(format!(
+                    "Rebuys cannot run past level {} of the blind schedule",
+                    levels.saturating_sub(1)
+                )));
+            }
+        }
+        if let Some(add_on) = add_on {
+            let after_rebuys = rebuy_period.map_or(0, |period| period.last_level);
+            if add_on.level >= levels || add_on.level < after_rebuys {
+                return Err(TournamentError::PurchaseNotAllowed(
+                    "The add-on break must be a schedule level no earlier than the last rebuy level".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// `Message::StackReport`
+    pub async fn record_stacks(
+        &mut self,
+        table: ChainId,
+        stacks: &[(String, u64)],
+        big_blind_order: &[String],
+    ) {
+        if let Some(reported) = self
+            .tables
+            .get_mut()
+            .iter_mut()
+            .find(|reported| reported.chain_id == table)
+        {
+            reported.big_blind_order = big_blind_order.to_vec();
+        }
+        for (player_name, chips) in stacks {
+            let Ok(mut entrant) = self.load_entrant(player_name).await else {
+                continue;
+            };
+            if entrant.table == Some(table) {
+                entrant.chips = *chips;
+                self.save_entrant(entrant);
+            }
+        }
+    }
+
+    /// `Rebuy`: the buy-in is split between the prize pool and the player's
+    /// bounty as at registration, and the player's table adds a starting
+    /// stack
+    pub async fn rebuy(&mut self, player_name: &str) -> Result<ChipGrant, TournamentError> {
+        let mut entrant = self.load_entrant(player_name).await?;
+        let Some(period) = self.rebuy_period.get().clone() else {
+            return Err(TournamentError::PurchaseNotAllowed(
+                "This tournament has no rebuys".to_string(),
+            ));
+        };
+        Self::check_rebuy(&period, *self.blind_level.get(), &entrant)?;
+        let chips = *self.starting_stack.get();
+        let (to_pool, bounty) = self.split_buy_in();
+        *self.prize_pool.get_mut() += to_pool;
+        entrant.bounty += bounty;
+        entrant.rebuys += 1;
+        self.grant_chips(entrant, chips)
+    }
+
+    pub fn check_rebuy(
+        period: &RebuyPeriod,
+        level: u32,
+        entrant: &Entrant,
+    ) -> Result<(), TournamentError> {
+        if entrant.table.is_none() {
+            return Err(TournamentError::EntrantNotFound(entrant.name.clone()));
+        }
+        if level > period.last_level {
+            return Err(TournamentError::PurchaseNotAllowed(
+                "The rebuy period is over".to_string(),
+            ));
+        }
+        if entrant.chips > period.stack_threshold {
+            return Err(TournamentError::PurchaseNotAllowed(format!(
+                "Rebuys are only for stacks of {} or less",
+                period.stack_threshold
+            )));
+        }
+        if period.max_rebuys.is_some_and(|max| entrant.rebuys >= max) {
+            return Err(TournamentError::PurchaseNotAllowed(format!(
+                "{} has used all their rebuys",
+                entrant.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// `AddOn`: once per player, during the add-on break
+    pub async fn take_add_on(&mut self, player_name: &str) -> Result<ChipGrant, TournamentError> {
+        let mut entrant = self.load_entrant(player_name).await?;
+        let Some(add_on) = self.add_on.get().clone() else {
+            return Err(TournamentError::PurchaseNotAllowed(
+                "This tournament has no add-on".to_string(),
+            ));
+        };
+        Self::check_add_on(&add_on, *self.blind_level.get(), &entrant)?;
+        *self.prize_pool.get_mut() += add_on.price;
+        entrant.took_add_on = true;
+        self.grant_chips(entrant, add_on.chips)
+    }
+
+    pub fn check_add_on(
+        add_on: &AddOn,
+        level: u32,
+        entrant: &Entrant,
+    ) -> Result<(), TournamentError> {
+        if entrant.table.is_none() {
+            return Err(TournamentError::EntrantNotFound(entrant.name.clone()));
+        }
+        if level != add_on.level {
+            return Err(TournamentError::PurchaseNotAllowed(format!(
+                "The add-on is only sold during level {}",
+                add_on.level
+            )));
+        }
+        if entrant.took_add_on {
+            return Err(TournamentError::PurchaseNotAllowed(format!(
+                "{} has already taken the add-on",
+                entrant.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn grant_chips(
+        &mut self,
+        mut entrant: Entrant,
+        chips: u64,
+    ) -> Result<ChipGrant, TournamentError> {
+        let table = entrant
+            .table
+            .ok_or_else(|| TournamentError::EntrantNotFound(entrant.name.clone()))?;
+        entrant.chips += chips;
+        let grant = ChipGrant {
+            table,
+            player_name: entrant.name.clone(),
+            chips,
+        };
+        self.save_entrant(entrant);
+        self.update_payouts();
+        Ok(grant)
+    }
+
+    /// The level every table plays, sent with `TournamentBlindLevel`
+    pub fn current_blind_level(&self) -> Option<(u32, BlindLevel)> {
+        let index = *self.blind_level.get();
+        let level = self.blind_schedule.get().levels.get(index as usize)?;
+        Some((index, level.clone()))
+    }
+
+    /// Call with each block timestamp while running. Returns the new level
+    /// for every table once the current one has expired.
+    pub fn advance_blind_level(&mut self, now_ms: u64) -> Option<(u32, BlindLevel)> {
+        if *self.status.get() != TournamentStatus::Running {
+            return None;
+        }
+        let next = PokerState::next_blind_level(
+            &self.blind_schedule.get().levels,
+            *self.blind_level.get(),
+            *self.blind_level_started_ms.get(),
+            0,
+            now_ms,
+            0,
+        )?;
+        self.blind_level.set(next);
+        self.blind_level_started_ms.set(now_ms);
+        self.current_blind_level()
+    }
+
+    pub fn tables_needed(entrants: usize, players_per_table: u8) -> usize {
+        entrants.div_ceil(players_per_table.max(2) as usize)
+    }
+
+    /// Deal players out to the tables in turn, so table sizes differ by at
+    /// most one
+    pub fn assign_tables(names: &[String], table_count: usize) -> Vec<Vec<String>> {
+        let mut tables = vec![Vec::new(); table_count];
+        for (index, name) in names.iter().enumerate() {
+            tables[index % table_count].push(name.clone());
+        }
+        tables
+    }
+
+    /// `Message::PlayerEliminated`: records the player's finishing place,
+    /// and the winner's when one player is left. Half the player's bounty is
+    /// paid to whoever knocked them out and half goes on that player's own
+    /// head; the winner collects their own bounty. A satellite ends once
+    /// everyone left has won a ticket, bigger stacks taking the higher
+    /// places. Returns what is now owed, for `PrizeAwarded`,
+    /// `BountyAwarded` and `TicketAwarded`.
+    pub async fn record_elimination(
+        &mut self,
+        table: ChainId,
+        player_name: &str,
+        eliminated_by: &[String],
+    ) -> Result<EliminationPayouts, TournamentError> {
+        if *self.status.get() != TournamentStatus::Running {
+            return Err(TournamentError::NotRunning);
+        }
+        let mut entrant = self.load_entrant(player_name).await?;
+        if entrant.table != Some(table) || entrant.finish.is_some() {
+            return Err(TournamentError::EntrantNotFound(player_name.to_string()));
+        }
+        let bounties = self
+            .award_knockout(table, player_name, entrant.bounty, eliminated_by)
+            .await;
+        let remaining = self.remaining_players();
+        let place = remaining.len() as u32;
+        entrant.table = None;
+        entrant.finish = Some(place);
+        entrant.bounty = 0;
+        let mut payouts = EliminationPayouts {
+            prizes: self.payout(&entrant).into_iter().collect(),
+            bounties,
+        };
+        self.save_entrant(entrant);
+        for table in self.tables.get_mut() {
+            table.seats.retain(|_, name| name != player_name);
+        }
+        self.elimination_order
+            .get_mut()
+            .push(player_name.to_string());
+
+        let remaining = self.remaining_players();
+        if remaining.len() > 1 && remaining.len() > self.tickets_awarded() as usize {
+            return Ok(payouts);
+        }
+        let mut finishers = Vec::new();
+        for name in &remaining {
+            finishers.push(self.load_entrant(name).await?);
+        }
+        finishers.sort_by_key(|finisher| std::cmp::Reverse(finisher.chips));
+        for (index, mut finisher) in finishers.into_iter().enumerate() {
+            finisher.finish = Some(index as u32 + 1);
+            payouts.prizes.extend(self.payout(&finisher));
+            let bounty = std::mem::take(&mut finisher.bounty);
+            if bounty > 0 {
+                payouts.bounties.push(BountyPayout {
+                    player_name: finisher.name.clone(),
+                    owner: finisher.owner.clone(),
+                    knocked_out: None,
+                    amount: bounty,
+                });
+            }
+            self.save_entrant(finisher);
+        }
+        self.status.set(TournamentStatus::Finished);
+        Ok(payouts)
+    }
+
+    /// Shares a knocked-out player's bounty between those still at the
+    /// table who won their last chips. A bounty nobody claims goes into the
+    /// prize pool.
+    async fn award_knockout(
+        &mut self,
+        table: ChainId,
+        knocked_out: &str,
+        bounty: u64,
+        eliminated_by: &[String],
+    ) -> Vec<BountyPayout> {
+        let mut hunters: Vec<Entrant> = Vec::new();
+        for name in eliminated_by {
+            if name == knocked_out || hunters.iter().any(|hunter| &hunter.name == name) {
+                continue;
+            }
+            if let Ok(hunter) = self.load_entrant(name).await {
+                if hunter.table == Some(table) && hunter.finish.is_none() {
+                    hunters.push(hunter);
+                }
+            }
+        }
+        if hunters.is_empty() {
+            if bounty > 0 {
+                *self.prize_pool.get_mut() += bounty;
+                self.update_payouts();
+            }
+            return Vec::new();
+        }
+
+        let shares = Self::knockout_shares(bounty, hunters.len());
+        let mut payouts = Vec::new();
+        for (mut hunter, (paid, added)) in hunters.into_iter().zip(shares) {
+            hunter.bounty += added;
+            if paid > 0 {
+                payouts.push(BountyPayout {
+                    player_name: hunter.name.clone(),
+                    owner: hunter.owner.clone(),
+                    knocked_out: Some(knocked_out.to_string()),
+                    amount: paid,
+                });
+            }
+            self.save_entrant(hunter);
+        }
+        payouts
+    }
+
+    /// Each hunter's cut of a bounty as (paid now, added to their own
+    /// bounty). A split pot splits the bounty evenly, odd chips to the first
+    /// hunter; the odd chip of a cut is paid.
+    pub fn knockout_shares(bounty: u64, hunters: usize) -> Vec<(u64, u64)> {
+        if hunters == 0 {
+            return Vec::new();
+        }
+        let cut = bounty / hunters as u64;
+        let odd_chips = bounty % hunters as u64;
+        (0..hunters)
+            .map(|index| {
+                let cut = if index == 0 { cut + odd_chips } else { cut };
+                let added = cut / 2;
+                (cut - added, added)
+            })
+            .collect()
+    }
+
+    fn payout(&self, entrant: &Entrant) -> Option<Payout> {
+        let place = entrant.finish?;
+        let amount = *self.payouts.get().get(place.checked_sub(1)? as usize)?;
+        let ticket = self
+            .satellite
+            .get()
+            .as_ref()
+            .filter(|_| place <= self.tickets_awarded())
+            .map(|satellite| satellite.target);
+        Some(Payout {
+            player_name: entrant.name.clone(),
+            owner: entrant.owner.clone(),
+            place,
+            amount,
+            ticket,
+        })
+    }
+
+    /// Entrants still seated somewhere
+    pub fn remaining_players(&self) -> Vec<String> {
+        self.tables
+            .get()
+            .iter()
+            .flat_map(|table| table.seats.values().cloned())
+            .collect()
+    }
+
+    /// Call after each elimination. Moves players from the biggest table to
+    /// the smallest until no two tables differ by more than one player.
+    /// Each move is sent to the player's table as `TournamentMovePlayer`.
+    pub async fn rebalance(&mut self) -> Vec<SeatMove> {
+        let per_table = *self.players_per_table.get();
+        let sizes: Vec<usize> = self.tables.get().iter().map(|t| t.seats.len()).collect();
+        let mut moves = Vec::new();
+        for (from, to) in Self::balancing_moves(&sizes) {
+            let Some(player_name) = Self::player_to_move(&self.tables.get()[from]) else {
+                continue;
+            };
+            let Some(seat) = Self::free_seat(&self.tables.get()[to].seats, per_table) else {
+                continue;
+            };
+            if let Some(seat_move) = self.move_player(&player_name, from, to, seat).await {
+                moves.push(seat_move);
+            }
+        }
+        moves
+    }
+
+    /// Who leaves a table being broken down: the player due to post the big
+    /// blind next, so nobody dodges the blinds by moving. Falls back to the
+    /// highest seat when the table has not reported anyone still there.
+    pub fn player_to_move(table: &TournamentTable) -> Option<String> {
+        let seated = |name: &&String| table.seats.values().any(|player| player == *name);
+        table
+            .big_blind_order
+            .iter()
+            .find(seated)
+            .or_else(|| table.seats.values().next_back())
+            .cloned()
+    }
+
+    /// Pairs of (from, to) table indices, one per player to move
+    pub fn balancing_moves(sizes: &[usize]) -> Vec<(usize, usize)> {
+        let mut sizes = sizes.to_vec();
+        let mut moves = Vec::new();
+        loop {
+            let biggest = (0..sizes.len()).max_by_key(|&index| (sizes[index], usize::MAX - index));
+            let smallest = (0..sizes.len()).min_by_key(|&index| sizes[index]);
+            match (biggest, smallest) {
+                (Some(from), Some(to)) if sizes[from] > sizes[to] + 1 => {
+                    sizes[from] -= 1;
+                    sizes[to] += 1;
+                    moves.push((from, to));
+                }
+                _ => return moves,
+            }
+        }
+    }
+
+    /// Once everyone left fits at one table, bring them all to the final
+    /// table chain with seats drawn by lot and release the other tables.
+    /// Without a final table chain the biggest table is kept, and only the
+    /// players joining it draw seats. Each move is sent to the player's
+    /// table as `TournamentMovePlayer`, which passes the stack on.
+    pub async fn merge_to_final_table(&mut self, seed: [u8; 32]) -> Vec<SeatMove> {
+        let per_table = *self.players_per_table.get();
+        let sizes: Vec<usize> = self.tables.get().iter().map(|t| t.seats.len()).collect();
+        let Some(biggest) = Self::final_table(&sizes, per_table) else {
+            return Vec::new();
+        };
+        let target = match *self.final_table_chain.get() {
+            Some(chain_id) => {
+                self.tables.get_mut().push(TournamentTable {
+                    chain_id,
+                    seats: BTreeMap::new(),
+                    big_blind_order: Vec::new(),
+                });
+                sizes.len()
+            }
+            None => biggest,
+        };
+        let movers: Vec<(usize, String)> = (0..sizes.len())
+            .filter(|&from| from != target)
+            .flat_map(|from| {
+                self.tables.get()[from]
+                    .seats
+                    .values()
+                    .map(|name| (from, name.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let free: Vec<u8> = (0..per_table)
+            .filter(|seat| !self.tables.get()[target].seats.contains_key(seat))
+            .collect();
+        let seats = Self::draw_seats(free, movers.len(), seed);
+
+        let mut moves = Vec::new();
+        for ((from, player_name), seat) in movers.into_iter().zip(seats) {
+            if let Some(seat_move) = self.move_player(&player_name, from, target, seat).await {
+                moves.push(seat_move);
+            }
+        }
+        let final_table = self.tables.get()[target].clone();
+        self.tables.set(vec![final_table]);
+        self.final_table_chain.set(None);
+        moves
+    }
+
+    /// `count` of the `free` seats, drawn by lot
+    pub fn draw_seats(mut free: Vec<u8>, count: usize, seed: [u8; 32]) -> Vec<u8> {
+        PokerState::shuffle_with_seed(&mut free, seed);
+        free.truncate(count);
+        free
+    }
+
+    /// Index of the table to merge into, when more than one table is in use
+    /// and the players left fit at one
+    pub fn final_table(sizes: &[usize], players_per_table: u8) -> Option<usize> {
+        let in_use = sizes.iter().filter(|&&size| size > 0).count();
+        let remaining: usize = sizes.iter().sum();
+        if in_use < 2 || remaining > players_per_table as usize {
+            return None;
+        }
+        (0..sizes.len()).max_by_key(|&index| (sizes[index], usize::MAX - index))
+    }
+
+    /// Lowest seat nobody sits in
+    pub fn free_seat(seats: &BTreeMap<u8, String>, players_per_table: u8) -> Option<u8> {
+        (0..players_per_table).find(|seat| !seats.contains_key(seat))
+    }
+
+    async fn move_player(
+        &mut self,
+        player_name: &str,
+        from: usize,
+        to: usize,
+        seat: u8,
+    ) -> Option<SeatMove> {
+        let mut entrant = self.load_entrant(player_name).await.ok()?;
+        let tables = self.tables.get_mut();
+        tables[from].seats.retain(|_, name| name != player_name);
+        tables[to].seats.insert(seat, player_name.to_string());
+        let seat_move = SeatMove {
+            player_name: player_name.to_string(),
+            from: tables[from].chain_id,
+            to: tables[to].chain_id,
+            seat,
+        };
+        entrant.table = Some(seat_move.to);
+        self.save_entrant(entrant);
+        Some(seat_move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("player{}", index)).collect()
+    }
+
+    #[test]
+    fn test_field_is_dealt_out_evenly() {
+        assert_eq!(TournamentState::tables_needed(20, 9), 3);
+        assert_eq!(TournamentState::tables_needed(18, 9), 2);
+        let tables = TournamentState::assign_tables(&names(20), 3);
+        let sizes: Vec<usize> = tables.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![7, 7, 6]);
+        assert_eq!(tables[1][0], "player1");
+    }
+
+    #[test]
+    fn test_balancing_moves_from_biggest_to_smallest() {
+        assert_eq!(TournamentState::balancing_moves(&[9, 9, 8]), vec![]);
+        assert_eq!(TournamentState::balancing_moves(&[9, 6, 8]), vec![(0, 1)]);
+        assert_eq!(
+            TournamentState::balancing_moves(&[9, 4, 9]),
+            vec![(0, 1), (2, 1), (0, 1)]
+        );
+    }
+
+    #[test]
+    fn test_final_table_once_everyone_fits() {
+        assert_eq!(TournamentState::final_table(&[5, 5], 9), None);
+        assert_eq!(TournamentState::final_table(&[4, 5], 9), Some(1));
+        assert_eq!(TournamentState::final_table(&[9, 0], 9), None);
+    }
+
+    #[test]
+    fn test_free_seat() {
+        let mut seats = BTreeMap::new();
+        seats.insert(0, "alice".to_string());
+        seats.insert(2, "bob".to_string());
+        assert_eq!(TournamentState::free_seat(&seats, 3), Some(1));
+        seats.insert(1, "carol".to_string());
+        assert_eq!(TournamentState::free_seat(&seats, 3), None);
+    }
+
+    #[test]
+    fn test_standard_payouts_pay_the_top_of_the_field() {
+        let structure = PayoutStructure::Standard { percent_paid: 15 };
+        assert_eq!(structure.places_paid(100), 15);
+        assert_eq!(structure.places_paid(21), 4);
+        assert_eq!(structure.places_paid(3), 1);
+
+        let payouts = structure.payouts(10_000, 21);
+        // Shares of 1, 1/2, 1/3 and 1/4; the odd chip goes to first place
+        assert_eq!(payouts, vec![4801, 2400, 1599, 1200]);
+        assert_eq!(payouts.iter().sum::<u64>(), 10_000);
+        assert!(payouts.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn test_fixed_payouts_and_remainders() {
+        let structure = PayoutStructure::Fixed(vec![50, 30, 20]);
+        assert!(structure.validate().is_ok());
+        assert_eq!(structure.payouts(1001, 10), vec![501, 300, 200]);
+        // Two entrants share the whole pool 5:3
+        assert_eq!(structure.payouts(800, 2), vec![500, 300]);
+        assert!(PayoutStructure::Fixed(vec![50, 30]).validate().is_err());
+        assert!(PayoutStructure::Standard { percent_paid: 0 }
+            .validate()
+            .is_err());
+    }
+
+    fn entrant(chips: u64) -> Entrant {
+        Entrant {
+            name: "alice".to_string(),
+            owner: "alice".to_string(),
+            table: Some(ChainId::default()),
+            finish: None,
+            chips,
+            rebuys: 0,
+            took_add_on: false,
+            bounty: 0,
+            ticket: false,
+        }
+    }
+
+    #[test]
+    fn test_rebuys_only_for_short_stacks_in_the_period() {
+        let period = RebuyPeriod {
+            last_level: 3,
+            stack_threshold: 1500,
+            max_rebuys: Some(1),
+        };
+        assert!(TournamentState::check_rebuy(&period, 3, &entrant(1500)).is_ok());
+        assert!(TournamentState::check_rebuy(&period, 4, &entrant(0)).is_err());
+        assert!(TournamentState::check_rebuy(&period, 0, &entrant(1501)).is_err());
+        let mut used = entrant(0);
+        used.rebuys = 1;
+        assert!(TournamentState::check_rebuy(&period, 0, &used).is_err());
+    }
+
+    #[test]
+    fn test_one_add_on_at_the_break() {
+        let add_on = AddOn {
+            level: 4,
+            price: 100,
+            chips: 3000,
+        };
+        assert!(TournamentState::check_add_on(&add_on, 4, &entrant(9000)).is_ok());
+        assert!(TournamentState::check_add_on(&add_on, 3, &entrant(9000)).is_err());
+        let mut taken = entrant(9000);
+        taken.took_add_on = true;
+        assert!(TournamentState::check_add_on(&add_on, 4, &taken).is_err());
+    }
+
+    #[test]
+    fn test_purchase_levels_checked_against_schedule() {
+        let schedule = BlindSchedule::turbo(20);
+        let period = |last_level| RebuyPeriod {
+            last_level,
+            stack_threshold: 1500,
+            max_rebuys: None,
+        };
+        let add_on = |level| AddOn {
+            level,
+            price: 100,
+            chips: 3000,
+        };
+        let check = |rebuys: Option<&RebuyPeriod>, add_on: Option<&AddOn>| {
+            TournamentState::validate_purchase_levels(&schedule, rebuys, add_on)
+        };
+        assert!(check(Some(&period(5)), Some(&add_on(5))).is_ok());
+        assert!(check(Some(&period(5)), Some(&add_on(4))).is_err());
+        assert!(check(Some(&period(20)), None).is_err());
+        assert!(check(None, Some(&add_on(0))).is_ok());
+    }
+
+    fn table(players: &[&str]) -> TournamentTable {
+        TournamentTable {
+            chain_id: ChainId::default(),
+            seats: players
+                .iter()
+                .enumerate()
+                .map(|(seat, name)| (seat as u8, name.to_string()))
+                .collect(),
+            big_blind_order: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_late_registration() {
+        assert!(TournamentState::late_registration_open(Some(4), 4));
+        assert!(!TournamentState::late_registration_open(Some(4), 5));
+        assert!(!TournamentState::late_registration_open(None, 0));
+
+        let tables = vec![
+            table(&["a", "b", "c"]),
+            table(&["d", "e"]),
+            table(&["f", "g"]),
+        ];
+        assert_eq!(TournamentState::table_for_late_entrant(&tables, 3), Some(1));
+        let full = vec![table(&["a", "b"]), table(&["c", "d"])];
+        assert_eq!(TournamentState::table_for_late_entrant(&full, 2), None);
+    }
+
+    #[test]
+    fn test_knockout_shares() {
+        assert_eq!(TournamentState::knockout_shares(500, 1), vec![(250, 250)]);
+        assert_eq!(TournamentState::knockout_shares(101, 1), vec![(51, 50)]);
+        assert_eq!(
+            TournamentState::knockout_shares(301, 2),
+            vec![(76, 75), (75, 75)]
+        );
+        assert!(TournamentState::knockout_shares(500, 0).is_empty());
+    }
+
+    #[test]
+    fn test_satellite_pays_tickets_then_chips() {
+        assert_eq!(TournamentState::satellite_tickets(2_500, 1_000, 10), 2);
+        assert_eq!(
+            TournamentState::satellite_payouts(2_500, 1_000, 10),
+            vec![1_000, 1_000, 500]
+        );
+        assert_eq!(
+            TournamentState::satellite_payouts(3_000, 1_000, 10),
+            vec![1_000, 1_000, 1_000]
+        );
+        // Never a ticket for everyone
+        assert_eq!(TournamentState::satellite_tickets(3_000, 1_000, 3), 2);
+        assert_eq!(TournamentState::satellite_tickets(3_000, 0, 3), 0);
+    }
+
+    #[test]
+    fn test_satellite_surplus_goes_to_ticket_winners() {
+        // Ten entrants at 100 for 50 tickets: nine tickets, and the 550 left
+        // is shared by the winners rather than paid to the bubble
+        let payouts = TournamentState::satellite_payouts(1_000, 50, 10);
+        assert_eq!(payouts.len(), 9);
+        assert_eq!(payouts[0], 50 + 61 + 1);
+        assert!(payouts[1..].iter().all(|&amount| amount == 50 + 61));
+        assert_eq!(payouts.iter().sum::<u64>(), 1_000);
+
+        // A pool that would buy everyone a ticket is split among all but one
+        assert_eq!(
+            TournamentState::satellite_payouts(300, 100, 3),
+            vec![150, 150]
+        );
+        assert_eq!(
+            TournamentState::satellite_payouts(250, 100, 3),
+            vec![100, 100, 50]
+        );
+    }
+
+    #[test]
+    fn test_final_table_seats_drawn_by_lot() {
+        let seed = [7u8; 32];
+        let seats = TournamentState::draw_seats((0..9).collect(), 9, seed);
+        let mut sorted = seats.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..9).collect::<Vec<u8>>());
+        assert_eq!(
+            TournamentState::draw_seats((0..9).collect(), 9, seed),
+            seats
+        );
+
+        let joining = TournamentState::draw_seats(vec![1, 4, 6], 2, seed);
+        assert_eq!(joining.len(), 2);
+        assert!(joining.iter().all(|seat| [1, 4, 6].contains(seat)));
+    }
+
+    #[test]
+    fn test_next_big_blind_moves_when_balancing() {
+        let mut reported = table(&["a", "b", "c", "d"]);
+        assert_eq!(
+            TournamentState::player_to_move(&reported),
+            Some("d".to_string())
+        );
+        reported.big_blind_order = ["x", "c", "d", "a", "b"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        assert_eq!(
+            TournamentState::player_to_move(&reported),
+            Some("c".to_string())
+        );
+    }
+}
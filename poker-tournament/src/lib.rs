@@ -0,0 +1,18 @@
+//! Poker Tournament - Multi-Table Tournament Coordinator
+//!
+//! This contract runs multi-table tournaments across poker table chains.
+//! It registers players, seats them at table chains, tracks eliminations
+//! reported by the tables and moves players to keep tables balanced.
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+pub mod contract;
+pub mod service;
+pub mod state;
+
+// Re-export types from poker-tournament-types
+pub use poker_tournament_types::{
+    AddOn, BountyPayout, ChipGrant, EliminationPayouts, Entrant, Message, Operation, Parameters,
+    Payout, PayoutStructure, PokerTournamentAbi, RebuyPeriod, Satellite, SeatMove, TableSeating,
+    TournamentError, TournamentState, TournamentStatus, TournamentTable,
+};
@@ -0,0 +1,702 @@
+//! Betting round progression and the rules every bet has to follow: whose
+//! turn it is, what they may do and when the round is over. `PokerState`
+//! runs its turn, bet-size and legal-action checks through here.
+
+use crate::{
+    BettingSituation, BettingStructure, LegalActions, PlayerAction, PlayerStatus, PokerError,
+    PokerPlayer,
+};
+
+/// Raises allowed on a fixed-limit street, counting the bet as none
+pub const FIXED_LIMIT_RAISE_CAP: u8 = 4;
+
+/// Raise increment when going from `current_bet` to `new_bet`, if it is a
+/// full raise that resets the minimum and reopens the betting
+pub fn full_raise_size(current_bet: u64, new_bet: u64, min_raise: u64) -> Option<u64> {
+    let size = new_bet.saturating_sub(current_bet);
+    (size > 0 && size >= min_raise).then_some(size)
+}
+
+/// One player's part in the current betting round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seat {
+    pub name: String,
+    /// Chips still behind
+    pub chips: u64,
+    /// Total put in on this street
+    pub bet: u64,
+    /// Total put in this hand, for the wager cap
+    pub total_bet: u64,
+    pub folded: bool,
+    pub all_in: bool,
+    /// Not dealt in. The seat keeps its place so indices still match
+    /// `player_order`, but it never acts.
+    pub sitting_out: bool,
+    /// Acted on this street; blinds do not count
+    pub acted: bool,
+}
+
+impl Seat {
+    fn in_hand(&self) -> bool {
+        !self.folded && !self.sitting_out
+    }
+
+    fn can_act(&self) -> bool {
+        self.in_hand() && !self.all_in
+    }
+
+    /// Chips the seat can still put in this hand under the table's cap
+    pub fn wager_left(&self, wager_cap: Option<u64>) -> u64 {
+        match wager_cap {
+            Some(cap) => self.chips.min(cap.saturating_sub(self.total_bet)),
+            None => self.chips,
+        }
+    }
+}
+
+impl From<&PokerPlayer> for Seat {
+    fn from(player: &PokerPlayer) -> Self {
+        Self {
+            name: player.name.clone(),
+            chips: player.chips,
+            bet: player.current_bet,
+            total_bet: player.total_bet_this_hand,
+            folded: player.has_folded,
+            all_in: player.is_all_in,
+            sitting_out: player.status == PlayerStatus::SittingOut,
+            acted: false,
+        }
+    }
+}
+
+impl BettingSituation {
+    /// A player who already matched the last full bet can only call a short
+    /// all-in; anyone else may still raise
+    fn reopened_for(&self, seat: &Seat) -> bool {
+        !(seat.acted && seat.bet >= self.full_bet_level)
+    }
+
+    /// What `seat` may do, with exact amounts
+    pub fn legal_actions(&self, seat: &Seat) -> LegalActions {
+        if !seat.can_act() {
+            return LegalActions::default();
+        }
+        let current_bet = self.current_bet;
+        let to_call = current_bet.saturating_sub(seat.bet);
+        let max_total = seat.bet + seat.wager_left(self.wager_cap);
+
+        // Largest total the structure allows, and the smallest full-sized one
+        let (min_total, limit) = match self.structure {
+            BettingStructure::FixedLimit => {
+                let total = current_bet + self.bet_size;
+                (total, total)
+            }
+            BettingStructure::PotLimit => {
+                (current_bet + self.min_raise, self.pot_limit_max_bet(seat))
+            }
+            BettingStructure::NoLimit => (current_bet + self.min_raise, max_total),
+        };
+        let capped = self.structure == BettingStructure::FixedLimit
+            && current_bet > 0
+            && self.raises_this_street >= FIXED_LIMIT_RAISE_CAP;
+        let can_raise =
+            max_total > current_bet && !capped && (current_bet == 0 || self.reopened_for(seat));
+        let range = can_raise.then(|| {
+            let max = limit.min(max_total);
+            (min_total.min(max), max)
+        });
+
+        LegalActions {
+            can_fold: true,
+            can_check: to_call == 0,
+            call_amount: (to_call > 0).then(|| to_call.min(max_total - seat.bet)),
+            bet_range: range.filter(|_| current_bet == 0),
+            raise_range: range.filter(|_| current_bet > 0),
+            can_all_in: max_total > seat.bet
+                && (max_total <= current_bet || range.is_some_and(|(_, max)| max == max_total)),
+        }
+    }
+
+    /// Every rule `action` has to pass apart from whose turn it is: checks
+    /// and calls must fit the bet, and bets and raises the chips behind, the
+    /// wager cap and the table's structure
+    pub fn check_action(&self, seat: &Seat, action: &PlayerAction) -> Result<(), PokerError> {
+        let invalid = |reason: &str| PokerError::InvalidAction {
+            action: action.clone(),
+            reason: reason.to_string(),
+        };
+        if !seat.can_act() {
+            return Err(invalid("Player has no action in this round"));
+        }
+        let to_call = self.current_bet.saturating_sub(seat.bet);
+        match action {
+            PlayerAction::Fold => Ok(()),
+            PlayerAction::Check if to_call > 0 => Err(invalid("Cannot check facing a bet")),
+            PlayerAction::Check => Ok(()),
+            PlayerAction::Call if to_call == 0 => Err(invalid("There is nothing to call")),
+            PlayerAction::Call => Ok(()),
+            PlayerAction::AllIn if seat.wager_left(self.wager_cap) == 0 => {
+                Err(invalid("Player has no chips left to bet"))
+            }
+            PlayerAction::AllIn if !self.legal_actions(seat).can_all_in => Err(invalid(
+                "Going all-in here would bet more than this street allows",
+            )),
+            PlayerAction::AllIn => Ok(()),
+            PlayerAction::Bet(total) | PlayerAction::Raise(total) => {
+                let available = seat.wager_left(self.wager_cap);
+                if *total > seat.bet + available {
+                    return Err(PokerError::InsufficientChips {
+                        needed: total.saturating_sub(seat.bet),
+                        available,
+                    });
+                }
+                if self.structure == BettingStructure::FixedLimit {
+                    self.check_bet_or_raise(seat, action)?;
+                    return self.check_fixed_limit(action);
+                }
+                self.check_pot_limit(seat, action)?;
+                self.check_min_raise(seat, action)
+            }
+        }
+    }
+
+    /// `Bet` only opens a street and `Raise` only answers a bet, and only a
+    /// full raise lets a player who has acted raise again
+    fn check_bet_or_raise(&self, seat: &Seat, action: &PlayerAction) -> Result<(), PokerError> {
+        let invalid = |reason: String| PokerError::InvalidAction {
+            action: action.clone(),
+            reason,
+        };
+        match action {
+            PlayerAction::Bet(_) if self.current_bet > 0 => Err(invalid(format!(
+                "There is already a bet of {}; raise instead",
+                self.current_bet
+            ))),
+            PlayerAction::Raise(_) if self.current_bet == 0 => {
+                Err(invalid("There is no bet to raise".to_string()))
+            }
+            PlayerAction::Raise(_) if !self.reopened_for(seat) => Err(invalid(
+                "An all-in short of a full raise does not reopen the betting".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// A bet must be at least `min_raise`, and a raise must add at least
+    /// `min_raise` to `current_bet`; an all-in for less is allowed
+    pub fn check_min_raise(&self, seat: &Seat, action: &PlayerAction) -> Result<(), PokerError> {
+        self.check_bet_or_raise(seat, action)?;
+        let (amount, minimum) = match action {
+            PlayerAction::Bet(amount) => (*amount, self.min_raise),
+            PlayerAction::Raise(amount) => (*amount, self.current_bet + self.min_raise),
+            _ => return Ok(()),
+        };
+        let all_in = amount == seat.bet + seat.wager_left(self.wager_cap);
+        if amount <= self.current_bet || (amount < minimum && !all_in) {
+            return Err(PokerError::InvalidAction {
+                action: action.clone(),
+                reason: format!("The bet must total at least {}", minimum),
+            });
+        }
+        Ok(())
+    }
+
+    /// Bets are exactly one `bet_size`, raises exactly one more on top of
+    /// `current_bet`, and a street allows `FIXED_LIMIT_RAISE_CAP` raises
+    pub fn check_fixed_limit(&self, action: &PlayerAction) -> Result<(), PokerError> {
+        let expected = match action {
+            PlayerAction::Bet(_) => self.bet_size,
+            PlayerAction::Raise(_) => {
+                if self.raises_this_street >= FIXED_LIMIT_RAISE_CAP {
+                    return Err(PokerError::RaiseCapReached {
+                        cap: FIXED_LIMIT_RAISE_CAP,
+                    });
+                }
+                self.current_bet + self.bet_size
+            }
+            _ => return Ok(()),
+        };
+        match action {
+            PlayerAction::Bet(amount) | PlayerAction::Raise(amount) if *amount != expected => {
+                Err(PokerError::InvalidAction {
+                    action: action.clone(),
+                    reason: format!("Fixed-limit bets on this street must total {}", expected),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Pot-limit bets and raises may not total more than
+    /// `pot_limit_max_bet`; other structures have no maximum here
+    pub fn check_pot_limit(&self, seat: &Seat, action: &PlayerAction) -> Result<(), PokerError> {
+        let amount = match action {
+            PlayerAction::Bet(amount) | PlayerAction::Raise(amount) => *amount,
+            _ => return Ok(()),
+        };
+        if self.structure == BettingStructure::PotLimit {
+            let max_bet = self.pot_limit_max_bet(seat);
+            if amount > max_bet {
+                return Err(PokerError::InvalidAction {
+                    action: action.clone(),
+                    reason: format!("Pot-limit bets can be at most {}", max_bet),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Largest total `seat` may bet to under pot-limit: calling first, then
+    /// raising by the pot including that call. `pot` includes every bet
+    /// already made on this street.
+    pub fn pot_limit_max_bet(&self, seat: &Seat) -> u64 {
+        let to_call = self.current_bet.saturating_sub(seat.bet);
+        self.current_bet + self.pot + to_call
+    }
+}
+
+/// Where the round stands after an action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoundStatus {
+    ToAct(String),
+    /// Everyone still in has matched the bet, or no more betting is possible
+    Closed,
+}
+
+/// A single street of betting. Seats are in table order; the round starts
+/// with `to_act` and moves left until nobody owes an action.
+#[derive(Debug, Clone)]
+pub struct BettingRound {
+    seats: Vec<Seat>,
+    to_act: Option<usize>,
+    situation: BettingSituation,
+}
+
+impl BettingRound {
+    /// Start a round from the seated players in `player_order`.
+    /// `first_to_act` is an index into them, as from
+    /// `PokerState::first_to_act`. Blinds or straddles already in
+    /// `current_bet` count as bets but not as actions, so their posters still
+    /// get the option.
+    pub fn from_players(
+        players: &[PokerPlayer],
+        first_to_act: usize,
+        situation: BettingSituation,
+    ) -> Self {
+        Self::resume(players, first_to_act, &[], situation)
+    }
+
+    /// Pick a round up part way through, with `acted` the players who have
+    /// already acted on this street
+    pub fn resume(
+        players: &[PokerPlayer],
+        to_act: usize,
+        acted: &[String],
+        situation: BettingSituation,
+    ) -> Self {
+        let seats = players
+            .iter()
+            .map(|player| Seat {
+                acted: acted.contains(&player.name),
+                ..Seat::from(player)
+            })
+            .collect();
+        Self::new(seats, to_act, situation)
+    }
+
+    pub fn new(seats: Vec<Seat>, first_to_act: usize, situation: BettingSituation) -> Self {
+        let mut round = Self {
+            seats,
+            to_act: None,
+            situation,
+        };
+        round.to_act = round.next_to_act(first_to_act);
+        round
+    }
+
+    pub fn seats(&self) -> &[Seat] {
+        &self.seats
+    }
+
+    pub fn seat(&self, player_name: &str) -> Option<&Seat> {
+        self.seats.iter().find(|seat| seat.name == player_name)
+    }
+
+    pub fn situation(&self) -> &BettingSituation {
+        &self.situation
+    }
+
+    pub fn current_bet(&self) -> u64 {
+        self.situation.current_bet
+    }
+
+    /// Smallest increment for the next raise: the last full raise, or the
+    /// opening minimum
+    pub fn min_raise(&self) -> u64 {
+        self.situation.min_raise
+    }
+
+    pub fn to_act(&self) -> Option<&str> {
+        self.to_act.map(|index| self.seats[index].name.as_str())
+    }
+
+    /// Index of the player to act, in the order the seats were given
+    pub fn to_act_index(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn status(&self) -> RoundStatus {
+        match self.to_act() {
+            Some(name) => RoundStatus::ToAct(name.to_string()),
+            None => RoundStatus::Closed,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.to_act.is_none()
+    }
+
+    /// Check it is `player_name`'s turn
+    pub fn require_turn(&self, player_name: &str) -> Result<usize, PokerError> {
+        match self.to_act {
+            Some(index) if self.seats[index].name == player_name => Ok(index),
+            _ => Err(PokerError::NotPlayersTurn(player_name.to_string())),
+        }
+    }
+
+    /// What `player_name` may do; nothing when it is not their turn
+    pub fn legal_actions(&self, player_name: &str) -> LegalActions {
+        match self.require_turn(player_name) {
+            Ok(index) => self.situation.legal_actions(&self.seats[index]),
+            Err(_) => LegalActions::default(),
+        }
+    }
+
+    /// Apply an action from the player whose turn it is and move the turn on.
+    /// `Bet` and `Raise` amounts are the player's total for the street.
+    pub fn act(
+        &mut self,
+        player_name: &str,
+        action: &PlayerAction,
+    ) -> Result<RoundStatus, PokerError> {
+        let index = self.require_turn(player_name)?;
+        self.situation.check_action(&self.seats[index], action)?;
+
+        let seat = &self.seats[index];
+        let to_call = self.situation.current_bet.saturating_sub(seat.bet);
+        let wager_left = seat.wager_left(self.situation.wager_cap);
+        let all_in_total = seat.bet + wager_left;
+        match action {
+            PlayerAction::Fold => self.seats[index].folded = true,
+            PlayerAction::Check => {}
+            PlayerAction::Call => self.put_in(index, to_call.min(wager_left))?,
+            PlayerAction::Bet(total) | PlayerAction::Raise(total) => {
+                self.raise_to(index, *total)?
+            }
+            PlayerAction::AllIn => self.raise_to(index, all_in_total)?,
+        }
+        if matches!(action, PlayerAction::Raise(_)) {
+            self.situation.raises_this_street = self.situation.raises_this_street.saturating_add(1);
+        }
+
+        self.seats[index].acted = true;
+        self.to_act = self.next_to_act(index + 1);
+        Ok(self.status())
+    }
+
+    /// Raise or call all-in up to `total`. Only a full raise moves the full
+    /// bet level and the minimum; a short all-in leaves those who acted able
+    /// to call or fold only.
+    fn raise_to(&mut self, index: usize, total: u64) -> Result<(), PokerError> {
+        let seat_bet = self.seats[index].bet;
+        self.put_in(index, total.saturating_sub(seat_bet))?;
+        let situation = &mut self.situation;
+        if total <= situation.current_bet {
+            return Ok(());
+        }
+        if let Some(size) = full_raise_size(situation.current_bet, total, situation.min_raise) {
+            situation.min_raise = size;
+            situation.full_bet_level = total;
+        }
+        situation.current_bet = total;
+        Ok(())
+    }
+
+    /// Move `amount` from the seat's stack to the pot, all or nothing
+    fn put_in(&mut self, index: usize, amount: u64) -> Result<(), PokerError> {
+        let wager_cap = self.situation.wager_cap;
+        let seat = &mut self.seats[index];
+        let Some(chips) = seat.chips.checked_sub(amount) else {
+            return Err(PokerError::InsufficientChips {
+                needed: amount,
+                available: seat.chips,
+            });
+        };
+        seat.chips = chips;
+        seat.bet += amount;
+        seat.total_bet += amount;
+        if seat.wager_left(wager_cap) == 0 {
+            seat.all_in = true;
+        }
+        self.situation.pot += amount;
+        Ok(())
+    }
+
+    /// The first seat from `start`, going round the table, that still owes
+    /// an action. None once the round is over.
+    fn next_to_act(&self, start: usize) -> Option<usize> {
+        let in_hand = self.seats.iter().filter(|s| s.in_hand()).count();
+        if in_hand <= 1 {
+            return None;
+        }
+        let can_act: Vec<&Seat> = self.seats.iter().filter(|s| s.can_act()).collect();
+        // A lone player with chips has nobody left to bet against
+        if let [last] = can_act.as_slice() {
+            if last.bet >= self.situation.current_bet {
+                return None;
+            }
+        }
+
+        let count = self.seats.len();
+        (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|&index| {
+                let seat = &self.seats[index];
+                seat.can_act() && (!seat.acted || seat.bet < self.situation.current_bet)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat(name: &str, chips: u64, bet: u64) -> Seat {
+        Seat {
+            name: name.to_string(),
+            chips,
+            bet,
+            total_bet: bet,
+            folded: false,
+            all_in: false,
+            sitting_out: false,
+            acted: false,
+        }
+    }
+
+    fn situation(structure: BettingStructure, pot: u64, current_bet: u64) -> BettingSituation {
+        BettingSituation {
+            structure,
+            pot,
+            current_bet,
+            min_raise: 20,
+            full_bet_level: current_bet,
+            bet_size: 20,
+            raises_this_street: 0,
+            wager_cap: None,
+        }
+    }
+
+    fn no_limit(current_bet: u64) -> BettingSituation {
+        situation(BettingStructure::NoLimit, current_bet, current_bet)
+    }
+
+    /// Three-handed pre-flop with 10/20 blinds; alice is on the button
+    fn preflop() -> BettingRound {
+        let seats = vec![
+            seat("alice", 1000, 0),
+            seat("bob", 990, 10),
+            seat("carol", 980, 20),
+        ];
+        BettingRound::new(seats, 0, no_limit(20))
+    }
+
+    #[test]
+    fn test_big_blind_gets_the_option() {
+        let mut round = preflop();
+        assert_eq!(
+            round.act("alice", &PlayerAction::Call).unwrap(),
+            RoundStatus::ToAct("bob".to_string())
+        );
+        assert_eq!(
+            round.act("bob", &PlayerAction::Call).unwrap(),
+            RoundStatus::ToAct("carol".to_string())
+        );
+        assert_eq!(
+            round.act("carol", &PlayerAction::Check).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_full_raise_reopens_the_action() {
+        let mut round = preflop();
+        round.act("alice", &PlayerAction::Call).unwrap();
+        round.act("bob", &PlayerAction::Call).unwrap();
+        round.act("carol", &PlayerAction::Raise(80)).unwrap();
+        assert_eq!(round.min_raise(), 60);
+        assert_eq!(round.to_act(), Some("alice"));
+        round.act("alice", &PlayerAction::Raise(140)).unwrap();
+        round.act("bob", &PlayerAction::Fold).unwrap();
+        assert_eq!(
+            round.act("carol", &PlayerAction::Call).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_short_all_in_lets_earlier_players_only_call() {
+        let seats = vec![
+            seat("alice", 1000, 0),
+            seat("bob", 130, 0),
+            seat("carol", 1000, 0),
+        ];
+        let mut round = BettingRound::new(seats, 0, no_limit(0));
+        round.act("alice", &PlayerAction::Bet(100)).unwrap();
+        // Bob's all-in for 130 is short of a full raise to 200
+        round.act("bob", &PlayerAction::AllIn).unwrap();
+        assert_eq!(round.current_bet(), 130);
+        assert_eq!(round.min_raise(), 100);
+        // Carol has not acted yet and may raise
+        round.act("carol", &PlayerAction::Call).unwrap();
+        assert_eq!(round.to_act(), Some("alice"));
+        assert!(round.act("alice", &PlayerAction::Raise(300)).is_err());
+        assert_eq!(round.legal_actions("alice").raise_range, None);
+        assert_eq!(
+            round.act("alice", &PlayerAction::Call).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_round_closes_when_everyone_else_folds() {
+        let mut round = preflop();
+        round.act("alice", &PlayerAction::Raise(60)).unwrap();
+        round.act("bob", &PlayerAction::Fold).unwrap();
+        assert_eq!(
+            round.act("carol", &PlayerAction::Fold).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_all_in_callers_leave_no_one_to_act() {
+        let seats = vec![seat("alice", 1000, 0), seat("bob", 300, 0)];
+        let mut round = BettingRound::new(seats, 0, no_limit(0));
+        round.act("alice", &PlayerAction::Bet(500)).unwrap();
+        assert_eq!(
+            round.act("bob", &PlayerAction::Call).unwrap(),
+            RoundStatus::Closed
+        );
+        assert!(round.seats()[1].all_in);
+        assert_eq!(round.seats()[1].bet, 300);
+    }
+
+    #[test]
+    fn test_out_of_turn_and_undersized_actions_rejected() {
+        let mut round = preflop();
+        assert!(matches!(
+            round.act("carol", &PlayerAction::Check),
+            Err(PokerError::NotPlayersTurn(_))
+        ));
+        assert!(round.act("alice", &PlayerAction::Check).is_err());
+        assert!(round.act("alice", &PlayerAction::Raise(30)).is_err());
+        assert!(matches!(
+            round.act("alice", &PlayerAction::Raise(5000)),
+            Err(PokerError::InsufficientChips { .. })
+        ));
+        assert_eq!(round.to_act(), Some("alice"));
+    }
+
+    #[test]
+    fn test_put_in_is_all_or_nothing() {
+        let mut round = preflop();
+        assert_eq!(
+            round.put_in(0, 1001),
+            Err(PokerError::InsufficientChips {
+                needed: 1001,
+                available: 1000
+            })
+        );
+        assert_eq!(round.seat("alice"), Some(&seat("alice", 1000, 0)));
+        round.put_in(0, 1000).unwrap();
+        let alice = round.seat("alice").unwrap();
+        assert_eq!((alice.chips, alice.bet), (0, 1000));
+        assert!(alice.all_in);
+    }
+
+    #[test]
+    fn test_sitting_out_players_keep_their_seat_index() {
+        let mut sitting_out = PokerPlayer::new("sam", "sam", 1000, 0);
+        sitting_out.status = PlayerStatus::SittingOut;
+        let players = vec![
+            sitting_out,
+            PokerPlayer::new("alice", "alice", 1000, 1),
+            PokerPlayer::new("bob", "bob", 1000, 2),
+        ];
+        // Index 1 in `player_order` is alice, even with sam sitting out
+        let mut round = BettingRound::from_players(&players, 1, no_limit(0));
+        assert_eq!(round.to_act(), Some("alice"));
+        round.act("alice", &PlayerAction::Check).unwrap();
+        assert_eq!(
+            round.act("bob", &PlayerAction::Check).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_pot_limit_caps_bets_at_the_pot() {
+        let seats = vec![seat("alice", 1000, 0), seat("bob", 1000, 0)];
+        let mut round = BettingRound::new(seats, 0, situation(BettingStructure::PotLimit, 100, 0));
+        assert!(round.act("alice", &PlayerAction::Bet(150)).is_err());
+        round.act("alice", &PlayerAction::Bet(100)).unwrap();
+        // Calling 100 makes the pot 300, so the raise can total 100 + 300
+        assert_eq!(round.legal_actions("bob").raise_range, Some((200, 400)));
+        assert!(round.act("bob", &PlayerAction::Raise(450)).is_err());
+        assert!(round.act("bob", &PlayerAction::AllIn).is_err());
+        round.act("bob", &PlayerAction::Raise(400)).unwrap();
+        assert_eq!(round.situation().pot, 600);
+    }
+
+    #[test]
+    fn test_fixed_limit_sizes_and_raise_cap() {
+        let seats = vec![seat("alice", 1000, 0), seat("bob", 1000, 0)];
+        let mut round = BettingRound::new(seats, 0, situation(BettingStructure::FixedLimit, 30, 0));
+        assert!(round.act("alice", &PlayerAction::Bet(30)).is_err());
+        round.act("alice", &PlayerAction::Bet(20)).unwrap();
+        let mut total = 20;
+        for raiser in ["bob", "alice", "bob", "alice"] {
+            total += 20;
+            round.act(raiser, &PlayerAction::Raise(total)).unwrap();
+        }
+        assert_eq!(
+            round.act("bob", &PlayerAction::Raise(total + 20)),
+            Err(PokerError::RaiseCapReached {
+                cap: FIXED_LIMIT_RAISE_CAP
+            })
+        );
+        assert_eq!(
+            round.act("bob", &PlayerAction::Call).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_wager_cap_puts_player_all_in_at_the_cap() {
+        let seats = vec![seat("alice", 1000, 0), seat("bob", 1000, 0)];
+        let mut capped = no_limit(0);
+        capped.wager_cap = Some(300);
+        let mut round = BettingRound::new(seats, 0, capped);
+        assert!(matches!(
+            round.act("alice", &PlayerAction::Bet(400)),
+            Err(PokerError::InsufficientChips { available: 300, .. })
+        ));
+        round.act("alice", &PlayerAction::AllIn).unwrap();
+        assert!(round.seats()[0].all_in);
+        assert_eq!(round.seats()[0].chips, 700);
+        assert_eq!(
+            round.act("bob", &PlayerAction::Call).unwrap(),
+            RoundStatus::Closed
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Invariants checked after every operation during a hand
+//!
+//! Returning an error from an operation aborts it, so none of its state
+//! changes are saved; a failed check rolls the table back to where it was.
+
+use crate::{PokerError, PokerState};
+
+/// Where every chip at the table is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChipLedger {
+    pub stacks: u64,
+    /// All pots of the current hand
+    pub pot: u64,
+    /// Fees waiting to seed the next pot
+    pub rabbit_hunt_fees: u64,
+    pub rake: u64,
+    /// The insurance pool plus claims it has set aside
+    pub insurance: u64,
+}
+
+impl ChipLedger {
+    pub async fn of(state: &PokerState) -> Self {
+        // Players who have left the table may still hold chips
+        let mut stacks = 0;
+        for name in state
+            .players
+            .indices()
+            .await
+            .expect("Failed to load players")
+        {
+            if let Some(player) = state
+                .players
+                .get(&name)
+                .await
+                .expect("Failed to load player")
+            {
+                stacks += player.chips;
+            }
+        }
+        let held: u64 = state
+            .insurance_offers
+            .get()
+            .iter()
+            .filter(|offer| offer.accepted)
+            .map(|offer| offer.coverage - offer.premium)
+            .sum();
+        Self {
+            stacks,
+            pot: *state.pot.get(),
+            rabbit_hunt_fees: *state.rabbit_hunt_fees.get(),
+            rake: *state.rake_collected.get(),
+            insurance: *state.insurance_pool.get() + held,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.stacks + self.pot + self.rabbit_hunt_fees + self.rake + self.insurance
+    }
+
+    pub fn check(&self, expected: u64) -> Result<(), PokerError> {
+        let actual = self.total();
+        if actual != expected {
+            return Err(PokerError::ChipsNotConserved { expected, actual });
+        }
+        Ok(())
+    }
+}
+
+/// Call when a hand starts. Posting blinds and antes only moves chips
+/// within the table, so it can come before or after.
+pub async fn record_hand_start(state: &mut PokerState) {
+    let total = ChipLedger::of(state).await.total();
+    state.chips_at_hand_start.set(total);
+}
+
+/// Call at the end of every operation. Chips only enter or leave the table
+/// between hands, so there is nothing to check then.
+pub async fn check_chip_conservation(state: &PokerState) -> Result<(), PokerError> {
+    if !PokerState::is_hand_in_progress(state.phase.get()) {
+        return Ok(());
+    }
+    ChipLedger::of(state)
+        .await
+        .check(*state.chips_at_hand_start.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger() -> ChipLedger {
+        ChipLedger {
+            stacks: 1800,
+            pot: 200,
+            rabbit_hunt_fees: 0,
+            rake: 0,
+            insurance: 500,
+        }
+    }
+
+    #[test]
+    fn test_moving_chips_between_buckets_keeps_the_total() {
+        let before = ledger();
+        let mut after = before;
+        // 10 raked from the pot, the rest paid out
+        after.pot = 0;
+        after.rake = 10;
+        after.stacks += 190;
+        assert!(after.check(before.total()).is_ok());
+    }
+
+    #[test]
+    fn test_created_chips_are_reported() {
+        let mut after = ledger();
+        after.stacks += 5;
+        assert_eq!(
+            after.check(ledger().total()),
+            Err(PokerError::ChipsNotConserved {
+                expected: 2500,
+                actual: 2505,
+            })
+        );
+    }
+}
@@ -7,6 +7,13 @@ use linera_sdk::{
     views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub mod betting;
+pub mod invariants;
+
+pub use betting::{BettingRound, RoundStatus, Seat};
+pub use invariants::ChipLedger;
 
 // ============================================================================
 // ABI
@@ -16,7 +23,7 @@ pub struct PokerAbi;
 
 impl ContractAbi for PokerAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = Result<(), PokerError>;
 }
 
 impl ServiceAbi for PokerAbi {
@@ -64,15 +71,95 @@ pub struct PokerPlayer {
     pub address: String,
     pub name: String,
     pub chips: u64,
+    /// Seat number, fixed for as long as the player stays at the table
     pub position: u8,
     pub status: PlayerStatus,
     pub current_bet: u64,
     pub total_bet_this_hand: u64,
     pub hole_cards_commitment: Option<CardCommitment>,
-    pub hole_cards: Option<[Card; 2]>,
+    pub hole_cards: Option<Vec<Card>>,
     pub has_folded: bool,
     pub is_all_in: bool,
     pub last_action: Option<PlayerAction>,
+    pub consecutive_timeouts: u8,
+    pub bounty_amount: u64,
+    pub bounties_won: u64,
+    /// Set while the player's connection is lost; cleared on `SitIn`
+    pub disconnected_at_ms: Option<u64>,
+    /// Dead blind owed after missing the blinds, collected with the next hand
+    pub missed_blind_due: u64,
+    /// Live straddle posted before the deal
+    pub straddle_posted: u64,
+    /// Asked to see the undealt board after this hand
+    pub wants_rabbit_hunt: bool,
+    /// Top up to `auto_rebuy_to` whenever the stack ends a hand below this
+    pub auto_rebuy_threshold: Option<u64>,
+    pub auto_rebuy_to: Option<u64>,
+    /// Chips paid in ahead to fund auto-rebuys; not in play
+    pub auto_rebuy_reserve: u64,
+    pub rebuys_used: u32,
+    /// Face-up cards in Stud games; `hole_cards` holds the ones dealt down
+    pub up_cards: Vec<Card>,
+    /// Set once the player has discarded (or stood pat) in the draw
+    pub has_drawn: bool,
+    /// Keep losing hands hidden at showdown unless shown with `ShowHand`
+    pub auto_muck: bool,
+    /// Blinds that came round while the player sat out; settled on return
+    pub missed_small_blind: bool,
+    pub missed_big_blind: bool,
+    /// Live big blind owed on return; counts toward the player's bet
+    pub live_blind_due: u64,
+    /// Sitting out until the big blind reaches them instead of posting
+    pub waiting_for_big_blind: bool,
+    /// Big blinds that have passed the player while sitting out
+    pub big_blinds_missed: u32,
+}
+
+impl PokerPlayer {
+    /// A player taking a seat with `chips`, before any hand
+    pub fn new(address: &str, name: &str, chips: u64, position: u8) -> Self {
+        Self {
+            address: address.to_string(),
+            name: name.to_string(),
+            chips,
+            position,
+            status: PlayerStatus::Active,
+            current_bet: 0,
+            total_bet_this_hand: 0,
+            hole_cards_commitment: None,
+            hole_cards: None,
+            has_folded: false,
+            is_all_in: false,
+            last_action: None,
+            consecutive_timeouts: 0,
+            bounty_amount: 0,
+            bounties_won: 0,
+            disconnected_at_ms: None,
+            missed_blind_due: 0,
+            straddle_posted: 0,
+            wants_rabbit_hunt: false,
+            auto_rebuy_threshold: None,
+            auto_rebuy_to: None,
+            auto_rebuy_reserve: 0,
+            rebuys_used: 0,
+            up_cards: Vec::new(),
+            has_drawn: false,
+            auto_muck: false,
+            missed_small_blind: false,
+            missed_big_blind: false,
+            live_blind_due: 0,
+            waiting_for_big_blind: false,
+            big_blinds_missed: 0,
+        }
+    }
+
+    pub fn stack_depth(&self, big_blind: u64) -> StackDepthCategory {
+        PokerState::compute_stack_depth_category(self.chips, big_blind)
+    }
+
+    pub fn m_ratio(&self, small_blind: u64, big_blind: u64, ante: u64, players: u8) -> f64 {
+        PokerState::compute_m_ratio(self.chips, small_blind, big_blind, ante, players)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -99,6 +186,10 @@ pub struct CardCommitment {
     pub cards_hash: [u8; 32],
     pub salt: String,
     pub committed_at: u64,
+    /// The hash covers these two, so a commitment only verifies for the
+    /// player and hand it was made for
+    pub player_address: String,
+    pub hand_number: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -111,6 +202,14 @@ pub enum GamePhase {
     River,
     Showdown,
     HandComplete,
+    /// Stud streets, named for the card each player holds after the deal
+    ThirdStreet,
+    FourthStreet,
+    FifthStreet,
+    SixthStreet,
+    SeventhStreet,
+    /// Five Card Draw: players discard and are dealt replacements
+    Draw,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -127,6 +226,828 @@ pub enum HandRank {
     RoyalFlush,
 }
 
+/// Table settings a rule vote may change; `None` leaves a setting untouched
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TableConfigPatch {
+    pub small_blind: Option<u64>,
+    pub big_blind: Option<u64>,
+    pub buy_in_min: Option<u64>,
+    pub buy_in_max: Option<u64>,
+    pub max_consecutive_timeouts: Option<u8>,
+}
+
+/// A pending player vote on a table rule change
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleProposal {
+    pub id: u32,
+    pub proposer: String,
+    pub change: TableConfigPatch,
+    pub votes_for: u32,
+    pub votes_against: u32,
+    pub expires_at_hand: u64,
+    pub voters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ProposalOutcome {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+impl RuleProposal {
+    /// Hands a proposal stays open for voting
+    pub const VOTING_WINDOW_HANDS: u64 = 3;
+
+    pub fn record_vote(&mut self, voter: &str, vote: bool) -> Result<(), PokerError> {
+        if self.voters.iter().any(|v| v == voter) {
+            return Err(PokerError::AlreadyDone(format!(
+                "{} has already voted on proposal {}",
+                voter, self.id
+            )));
+        }
+        self.voters.push(voter.to_string());
+        if vote {
+            self.votes_for += 1;
+        } else {
+            self.votes_against += 1;
+        }
+        Ok(())
+    }
+
+    /// Approved once more than half of the active players vote yes; rejected
+    /// as soon as that can no longer happen. Ties are rejections.
+    pub fn outcome(&self, active_players: usize, current_hand: u64) -> ProposalOutcome {
+        let active = active_players as u32;
+        if self.votes_for * 2 > active {
+            return ProposalOutcome::Approved;
+        }
+        let undecided = active.saturating_sub(self.votes_for + self.votes_against);
+        if (self.votes_for + undecided) * 2 <= active {
+            return ProposalOutcome::Rejected;
+        }
+        if current_hand >= self.expires_at_hand {
+            return ProposalOutcome::Expired;
+        }
+        ProposalOutcome::Pending
+    }
+}
+
+/// A pot and the players still able to win it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SidePot {
+    pub amount: u64,
+    pub eligible_players: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum StreakType {
+    Win,
+    Loss,
+    #[default]
+    Neutral,
+}
+
+/// How one player took part in a completed hand, for collusion statistics
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HandParticipation {
+    pub player: String,
+    pub folded_preflop: bool,
+    pub bet_or_raised: bool,
+}
+
+/// Per-pair history used to spot soft-play between two players
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PairStats {
+    pub hands_in_pot_together: u32,
+    pub times_neither_bet_raised: u32,
+    pub times_one_folded_preflop: u32,
+}
+
+impl PairStats {
+    /// Hands together before the score is given full weight
+    pub const MIN_SAMPLE_HANDS: u32 = 20;
+
+    pub fn record_hand(&mut self, first: &HandParticipation, second: &HandParticipation) {
+        match (first.folded_preflop, second.folded_preflop) {
+            (false, false) => {
+                self.hands_in_pot_together += 1;
+                if !first.bet_or_raised && !second.bet_or_raised {
+                    self.times_neither_bet_raised += 1;
+                }
+            }
+            (true, true) => {}
+            _ => self.times_one_folded_preflop += 1,
+        }
+    }
+
+    /// 0.0 for normal play, approaching 1.0 when the pair checks down 90%+
+    /// of the pots they play together over a meaningful sample
+    pub fn suspicion_score(&self) -> f32 {
+        if self.hands_in_pot_together == 0 {
+            return 0.0;
+        }
+        let together = self.hands_in_pot_together as f32;
+        let check_down_rate = self.times_neither_bet_raised as f32 / together;
+        let sample_weight = (together / Self::MIN_SAMPLE_HANDS as f32).min(1.0);
+        ((check_down_rate - 0.5) / 0.4).clamp(0.0, 1.0) * sample_weight
+    }
+}
+
+/// A hand marked for manual review by the table creator
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FlaggedHand {
+    pub hand_number: u64,
+    pub reason: String,
+    pub flagged_by: String,
+    pub timestamp_ms: u64,
+    pub resolved: bool,
+    pub resolution: Option<String>,
+}
+
+/// Chips one player handed to another between hands
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChipTransfer {
+    /// Last hand played before the transfer
+    pub hand_number: u64,
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub timestamp_ms: u64,
+}
+
+/// One message in the table chat
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatEntry {
+    pub sender: String,
+    pub message: String,
+    pub timestamp_ms: u64,
+}
+
+/// Effective stack size in big blinds: >= 100, >= 40, >= 20, >= 10, below 10
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StackDepthCategory {
+    DeepStack,
+    MidStack,
+    ShortStack,
+    CriticalStack,
+    PushFoldStack,
+}
+
+/// One entry in a hand's action log, in the order it happened. `Bet` and
+/// `Raise` amounts are the player's total commitment for the street.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HandEvent {
+    HandStarted {
+        hand_number: u64,
+        dealer: String,
+    },
+    BlindPosted {
+        player: String,
+        amount: u64,
+    },
+    PlayerActed {
+        player: String,
+        action: PlayerAction,
+    },
+    CommunityCardsDealt {
+        phase: GamePhase,
+        cards: Vec<Card>,
+    },
+    ShowdownRevealed {
+        player: String,
+        hand_rank: HandRank,
+    },
+    PotAwarded {
+        player: String,
+        amount: u64,
+    },
+    /// The first hand's button, and how it was chosen
+    InitialButtonAssigned {
+        player: String,
+        method: String,
+    },
+}
+
+impl HandEvent {
+    /// Whether `player` is named in this event
+    pub fn involves(&self, player: &str) -> bool {
+        match self {
+            HandEvent::HandStarted { dealer, .. } => dealer == player,
+            HandEvent::BlindPosted { player: name, .. }
+            | HandEvent::PlayerActed { player: name, .. }
+            | HandEvent::ShowdownRevealed { player: name, .. }
+            | HandEvent::PotAwarded { player: name, .. }
+            | HandEvent::InitialButtonAssigned { player: name, .. } => name == player,
+            HandEvent::CommunityCardsDealt { .. } => false,
+        }
+    }
+}
+
+/// Share of the prize pool paid to one finishing position
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TournamentPayout {
+    pub rank: u8,
+    pub percentage: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TournamentConfig {
+    pub buy_in: u64,
+    /// Taken from the buy-in pool before prizes are paid
+    pub rake_percentage: u8,
+    pub payout_structure: Vec<TournamentPayout>,
+    /// Sit & Go: the tournament starts once this many players have registered
+    pub players: u8,
+    /// Tournament chips each player starts with
+    pub starting_stack: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum GameVariant {
+    #[default]
+    TexasHoldem,
+    Omaha,
+    Razz,
+    SevenCardStud,
+    SevenCardStudHiLo,
+    /// Pot-limit Omaha with the pot split between high and 8-or-better low
+    OmahaHiLo,
+    /// Hold'em with the deuces through fives removed
+    ShortDeckHoldem,
+    FiveCardDraw,
+    /// Three hole cards, one discarded before the flop is dealt
+    Pineapple,
+    /// Three hole cards, one discarded after the flop betting
+    CrazyPineapple,
+    /// Deuce-to-seven lowball with three draws
+    DeuceToSevenTripleDraw,
+    /// Four-card lowball with three draws, ranked by `BadugiRank`
+    Badugi,
+}
+
+impl GameVariant {
+    /// Cards dealt to each player before the first betting round
+    pub fn starting_cards(&self) -> usize {
+        match self {
+            GameVariant::TexasHoldem | GameVariant::ShortDeckHoldem => 2,
+            GameVariant::Omaha | GameVariant::OmahaHiLo | GameVariant::Badugi => 4,
+            GameVariant::FiveCardDraw | GameVariant::DeuceToSevenTripleDraw => 5,
+            // Two down, one up
+            GameVariant::Razz | GameVariant::SevenCardStud | GameVariant::SevenCardStudHiLo => 3,
+            GameVariant::Pineapple | GameVariant::CrazyPineapple => 3,
+        }
+    }
+
+    pub fn is_stud(&self) -> bool {
+        matches!(
+            self,
+            GameVariant::Razz | GameVariant::SevenCardStud | GameVariant::SevenCardStudHiLo
+        )
+    }
+
+    pub fn uses_community_cards(&self) -> bool {
+        matches!(
+            self,
+            GameVariant::TexasHoldem
+                | GameVariant::Omaha
+                | GameVariant::OmahaHiLo
+                | GameVariant::ShortDeckHoldem
+                | GameVariant::Pineapple
+                | GameVariant::CrazyPineapple
+        )
+    }
+
+    /// Number of times players may draw in a hand
+    pub fn draw_rounds(&self) -> u8 {
+        match self {
+            GameVariant::FiveCardDraw => 1,
+            GameVariant::DeuceToSevenTripleDraw | GameVariant::Badugi => 3,
+            _ => 0,
+        }
+    }
+
+    /// Street on which Pineapple players must throw away their third card;
+    /// it has to be gone before the next street is dealt
+    pub fn discard_phase(&self) -> Option<GamePhase> {
+        match self {
+            GameVariant::Pineapple => Some(GamePhase::PreFlop),
+            GameVariant::CrazyPineapple => Some(GamePhase::Flop),
+            _ => None,
+        }
+    }
+
+    /// Omaha is played pot-limit: no bet may exceed the pot
+    pub fn is_pot_limit(&self) -> bool {
+        matches!(self, GameVariant::Omaha | GameVariant::OmahaHiLo)
+    }
+
+    /// Whether the pot is split between the best high and best low hand
+    pub fn is_hi_lo(&self) -> bool {
+        matches!(
+            self,
+            GameVariant::OmahaHiLo | GameVariant::SevenCardStudHiLo
+        )
+    }
+}
+
+/// How large bets and raises may be
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BettingStructure {
+    #[default]
+    NoLimit,
+    /// No bet may exceed the pot after calling
+    PotLimit,
+    FixedLimit,
+}
+
+/// HORSE-style rotation: play each variant for `hands_per_rotation` hands,
+/// or that many orbits of the table when `rotate_by_orbit` is set
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MixedGameSchedule {
+    pub variants: Vec<GameVariant>,
+    pub hands_per_rotation: u8,
+    pub rotate_by_orbit: bool,
+}
+
+impl MixedGameSchedule {
+    /// Hands each variant is played for at a table of `player_count`
+    pub fn hands_per_change(&self, player_count: usize) -> u64 {
+        if self.rotate_by_orbit {
+            self.hands_per_rotation as u64 * player_count as u64
+        } else {
+            self.hands_per_rotation as u64
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), PokerError> {
+        if self.variants.is_empty() || self.hands_per_rotation == 0 {
+            return Err(PokerError::InvalidConfig(
+                "A mixed game needs at least one variant and one hand per rotation".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Why an operation was rejected
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PokerError {
+    TableFull,
+    PlayerNotFound(String),
+    InvalidAction {
+        action: PlayerAction,
+        reason: String,
+    },
+    InvalidPhase {
+        expected: GamePhase,
+        actual: GamePhase,
+    },
+    InsufficientChips {
+        needed: u64,
+        available: u64,
+    },
+    DuplicatePlayer(String),
+    NotPlayersTurn(String),
+    CommitmentVerificationFailed,
+    DeckCorrupted,
+    TableNotFound(String),
+    NotEnoughPlayers {
+        have: usize,
+        need: usize,
+    },
+    HandNumberOverflow,
+    /// Fixed-limit street already has its maximum number of raises
+    RaiseCapReached {
+        cap: u8,
+    },
+    /// Caller may not do this, e.g. is not the table creator
+    Unauthorized(String),
+    /// Chips were created or lost during the hand
+    ChipsNotConserved {
+        expected: u64,
+        actual: u64,
+    },
+    /// A table, blind, rebuy or tournament setting is out of range
+    InvalidConfig(String),
+    /// The table's rules do not offer this
+    NotEnabled(String),
+    /// Tournament registration is over
+    RegistrationClosed,
+    /// A buy-in outside the table's limits
+    BuyInOutOfRange {
+        min: u64,
+        max: u64,
+    },
+    /// Seat is off the table or already taken
+    SeatUnavailable(u8),
+    /// Needs a hand in progress and there is none
+    NoHandInProgress,
+    /// The session has been paid out or reached its hand limit
+    SessionComplete,
+    /// Nothing is waiting to be accepted or claimed, such as an insurance
+    /// offer, a rule proposal or rakeback
+    NoOffer(String),
+    /// The player already did this and cannot do it again
+    AlreadyDone(String),
+    /// A cap on flags, rebuys or players has been reached
+    LimitReached(String),
+    GameNotStale,
+    /// Any other rule the request breaks
+    InvalidOperation(String),
+}
+
+impl std::fmt::Display for PokerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PokerError::TableFull => write!(f, "Table is full"),
+            PokerError::PlayerNotFound(name) => write!(f, "Player {} not found", name),
+            PokerError::InvalidAction { action, reason } => {
+                write!(f, "Invalid action {:?}: {}", action, reason)
+            }
+            PokerError::InvalidPhase { expected, actual } => {
+                write!(
+                    f,
+                    "Expected phase {:?}, but the table is in {:?}",
+                    expected, actual
+                )
+            }
+            PokerError::InsufficientChips { needed, available } => {
+                write!(f, "Needs {} chips but only {} available", needed, available)
+            }
+            PokerError::DuplicatePlayer(name) => write!(f, "{} is already seated", name),
+            PokerError::NotPlayersTurn(name) => write!(f, "It is not {}'s turn", name),
+            PokerError::CommitmentVerificationFailed => {
+                write!(f, "Revealed cards do not match the commitment")
+            }
+            PokerError::DeckCorrupted => write!(f, "Deck integrity check failed"),
+            PokerError::TableNotFound(table_id) => write!(f, "Table {} not found", table_id),
+            PokerError::NotEnoughPlayers { have, need } => {
+                write!(f, "Need {} players to start, only {} ready", need, have)
+            }
+            PokerError::HandNumberOverflow => write!(f, "Hand number cannot be incremented"),
+            PokerError::RaiseCapReached { cap } => {
+                write!(f, "Betting is capped at {} raises this street", cap)
+            }
+            PokerError::Unauthorized(reason) => write!(f, "{}", reason),
+            PokerError::ChipsNotConserved { expected, actual } => write!(
+                f,
+                "Chip total changed during the hand: expected {}, found {}",
+                expected, actual
+            ),
+            PokerError::InvalidConfig(reason) => write!(f, "Invalid configuration: {}", reason),
+            PokerError::NotEnabled(reason) => write!(f, "{}", reason),
+            PokerError::RegistrationClosed => {
+                write!(f, "Registration closed when the tournament started")
+            }
+            PokerError::BuyInOutOfRange { min, max } => {
+                write!(f, "Buy-in must be between {} and {}", min, max)
+            }
+            PokerError::SeatUnavailable(seat) => write!(f, "Seat {} is not available", seat),
+            PokerError::NoHandInProgress => write!(f, "There is no hand in progress"),
+            PokerError::SessionComplete => write!(f, "This session is complete"),
+            PokerError::NoOffer(reason) => write!(f, "{}", reason),
+            PokerError::AlreadyDone(reason) => write!(f, "{}", reason),
+            PokerError::LimitReached(reason) => write!(f, "{}", reason),
+            PokerError::GameNotStale => write!(f, "Game is not stale"),
+            PokerError::InvalidOperation(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PokerError {}
+
+/// Reason a deck failed `validate_deck_integrity`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeckError {
+    DuplicateCard(Card),
+    MissingCard(Card),
+    WrongDeckSize(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeckIntegrityResult {
+    pub valid: bool,
+    pub errors: Vec<DeckError>,
+}
+
+impl DeckIntegrityResult {
+    /// Guard for code paths that must not move chips on a corrupted deck
+    pub fn ensure_valid(&self) -> Result<(), PokerError> {
+        if !self.valid {
+            return Err(PokerError::DeckCorrupted);
+        }
+        Ok(())
+    }
+}
+
+/// Everything the table stores about one player, for data portability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerDataExport {
+    pub player: PokerPlayer,
+    pub leaderboard: LeaderboardData,
+    /// Transfers this player sent or received
+    pub transfers: Vec<ChipTransfer>,
+    pub chat_messages: Vec<ChatEntry>,
+    /// Flagged hands this player raised or played in
+    pub flagged_hand_numbers: Vec<u64>,
+}
+
+/// How often a player has taken each kind of action
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActionFrequency {
+    pub fold: u32,
+    pub check: u32,
+    pub call: u32,
+    pub bet: u32,
+    pub raise: u32,
+    pub all_in: u32,
+}
+
+impl ActionFrequency {
+    pub fn record(&mut self, action: &PlayerAction) {
+        let counter = match action {
+            PlayerAction::Fold => &mut self.fold,
+            PlayerAction::Check => &mut self.check,
+            PlayerAction::Call => &mut self.call,
+            PlayerAction::Bet(_) => &mut self.bet,
+            PlayerAction::Raise(_) => &mut self.raise,
+            PlayerAction::AllIn => &mut self.all_in,
+        };
+        *counter += 1;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.fold + self.check + self.call + self.bet + self.raise + self.all_in
+    }
+}
+
+/// How long a blind level lasts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LevelDuration {
+    /// Wall-clock time, measured with block timestamps
+    Millis(u64),
+    Hands(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlindLevel {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+    pub duration: LevelDuration,
+}
+
+impl BlindLevel {
+    /// Whether this level has run its course since it started
+    pub fn is_expired(
+        &self,
+        started_ms: u64,
+        started_hand: u64,
+        now_ms: u64,
+        hand_number: u64,
+    ) -> bool {
+        match self.duration {
+            LevelDuration::Millis(ms) => now_ms.saturating_sub(started_ms) >= ms,
+            LevelDuration::Hands(hands) => hand_number.saturating_sub(started_hand) >= hands as u64,
+        }
+    }
+}
+
+/// Blind levels played in order; the last one lasts until the end. An empty
+/// schedule keeps the blinds fixed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BlindSchedule {
+    pub levels: Vec<BlindLevel>,
+}
+
+/// Big blind of each preset level, in percent of the starting big blind
+const PRESET_BLIND_STEPS: [u64; 20] = [
+    100, 150, 200, 300, 400, 500, 600, 800, 1000, 1200, 1500, 2000, 2500, 3000, 4000, 5000, 6000,
+    8000, 10000, 12000,
+];
+
+/// Preset levels before antes come in
+const PRESET_LEVELS_WITHOUT_ANTE: usize = 3;
+
+impl BlindSchedule {
+    /// 15-minute levels
+    pub fn standard(starting_big_blind: u64) -> Self {
+        Self::preset(starting_big_blind, LevelDuration::Millis(15 * 60_000))
+    }
+
+    /// 8-minute levels
+    pub fn turbo(starting_big_blind: u64) -> Self {
+        Self::preset(starting_big_blind, LevelDuration::Millis(8 * 60_000))
+    }
+
+    /// 3-minute levels
+    pub fn hyper(starting_big_blind: u64) -> Self {
+        Self::preset(starting_big_blind, LevelDuration::Millis(3 * 60_000))
+    }
+
+    /// The preset blind steps, each level lasting `duration`. From the fourth
+    /// level everyone antes a tenth of the big blind.
+    pub fn preset(starting_big_blind: u64, duration: LevelDuration) -> Self {
+        let levels = PRESET_BLIND_STEPS
+            .iter()
+            .enumerate()
+            .map(|(index, percent)| {
+                let big_blind = starting_big_blind * percent / 100;
+                BlindLevel {
+                    small_blind: big_blind / 2,
+                    big_blind,
+                    ante: if index < PRESET_LEVELS_WITHOUT_ANTE {
+                        0
+                    } else {
+                        big_blind / 10
+                    },
+                    duration,
+                }
+            })
+            .collect();
+        Self { levels }
+    }
+
+    /// Every level must last a while, and blinds may never go down
+    pub fn validate(&self) -> Result<(), PokerError> {
+        for level in &self.levels {
+            let empty = matches!(
+                level.duration,
+                LevelDuration::Millis(0) | LevelDuration::Hands(0)
+            );
+            if empty || level.big_blind == 0 || level.small_blind > level.big_blind {
+                return Err(PokerError::InvalidConfig(
+                    "Each blind level needs a duration and a big blind at least the small blind"
+                        .to_string(),
+                ));
+            }
+        }
+        if self
+            .levels
+            .windows(2)
+            .any(|pair| pair[1].big_blind < pair[0].big_blind)
+        {
+            return Err(PokerError::InvalidConfig(
+                "Blind levels must not decrease".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Who posts the blinds in a hand under the dead button rule. Seats index
+/// `player_order`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlindPositions {
+    /// Acts last after the flop; with a dead button no player owns it and
+    /// this is the seat just before the first blind
+    pub dealer: u8,
+    pub dead_button: bool,
+    /// `None` when the small blind is dead
+    pub small_blind: Option<u8>,
+    pub big_blind: u8,
+}
+
+/// Insurance offered to an all-in favourite before the runout
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InsuranceOffer {
+    pub player: String,
+    pub hand_number: u64,
+    /// Chance of winning the pot, 0.0 to 1.0
+    pub equity: f64,
+    /// Paid to the player if the hand is lost
+    pub coverage: u64,
+    /// Owed by the player whichever way the hand goes
+    pub premium: u64,
+    pub accepted: bool,
+}
+
+/// Rules of the game dealt at a table. Every rule check reads them from
+/// here; only the stakes, which blind levels and rule votes change, live
+/// outside.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameRules {
+    /// Variant dealt when no mixed game schedule is set
+    pub variant: GameVariant,
+    pub betting_structure: BettingStructure,
+    /// Posted by every dealt-in player before each hand; 0 for no antes
+    pub ante: u64,
+    /// Only the big blind posts `ante`, once for the whole table
+    pub big_blind_ante: bool,
+    /// Forced opening bet paid by the lowest up-card in Stud games
+    pub bring_in: u64,
+    pub straddle_allowed: bool,
+    /// Players all-in before the river may agree to deal the rest twice
+    pub run_it_twice_allowed: bool,
+    /// Cap games: most a player can wager in one hand; reaching it counts
+    /// as all-in for the rest of the hand
+    pub wager_cap: Option<u64>,
+    /// Let players see the undealt board once a hand ends early
+    pub rabbit_hunt_enabled: bool,
+    /// Charged to each player who sees a rabbit hunt, paid into the next pot
+    pub rabbit_hunt_fee: u64,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            variant: GameVariant::TexasHoldem,
+            betting_structure: BettingStructure::NoLimit,
+            ante: 0,
+            big_blind_ante: false,
+            bring_in: 5,
+            straddle_allowed: true,
+            run_it_twice_allowed: false,
+            wager_cap: None,
+            rabbit_hunt_enabled: false,
+            rabbit_hunt_fee: 0,
+        }
+    }
+}
+
+/// What the player to act may do, with exact amounts. Bet and raise
+/// ranges are (min, max) totals for the street, as in `PlayerAction`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LegalActions {
+    pub can_fold: bool,
+    pub can_check: bool,
+    /// Chips needed to call; less than the bet when calling puts them all-in
+    pub call_amount: Option<u64>,
+    pub bet_range: Option<(u64, u64)>,
+    pub raise_range: Option<(u64, u64)>,
+    pub can_all_in: bool,
+}
+
+/// The betting on the current street, as seen by the player to act
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BettingSituation {
+    pub structure: BettingStructure,
+    pub pot: u64,
+    pub current_bet: u64,
+    pub min_raise: u64,
+    pub full_bet_level: u64,
+    /// The fixed-limit bet size for this street
+    pub bet_size: u64,
+    pub raises_this_street: u8,
+    pub wager_cap: Option<u64>,
+}
+
+/// Why a hand in progress was called off with `CancelHand`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CancelReason {
+    /// A player never committed to their hole cards
+    CommitmentsMissing,
+    /// Every player but one timed out before anyone chose to act
+    EveryoneElseTimedOut,
+}
+
+/// A `JoinTable` that arrived while every seat was taken
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WaitingPlayer {
+    pub address: String,
+    pub player_name: String,
+    pub buy_in: u64,
+    pub wait_for_big_blind: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum JoinOutcome {
+    Seated(Box<PokerPlayer>),
+    /// Queued behind `position` other players
+    Waitlisted {
+        position: usize,
+    },
+}
+
+/// A player knocked out of a tournament table and the players who won their
+/// last chips
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Elimination {
+    pub player_name: String,
+    pub eliminated_by: Vec<String>,
+}
+
+/// A tournament player told to change tables
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TournamentMove {
+    pub player_name: String,
+    /// Destination table chain
+    pub to_table: String,
+    pub seat: u8,
+}
+
+/// A tournament stack leaving for another table
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StackMigration {
+    pub player_name: String,
+    pub address: String,
+    pub chips: u64,
+    pub to_table: String,
+    pub seat: u8,
+}
+
 // ============================================================================
 // Operations
 // ============================================================================
@@ -141,10 +1062,15 @@ pub enum Operation {
         buy_in_min: u64,
         buy_in_max: u64,
     },
+    /// Once the game is running, a newcomer either posts a big blind with
+    /// their first hand or waits for the big blind to reach them. Without a
+    /// `seat` the player takes the lowest free seat.
     JoinTable {
         table_id: String,
         buy_in: u64,
         player_name: String,
+        wait_for_big_blind: bool,
+        seat: Option<u8>,
     },
     LeaveTable {
         table_id: String,
@@ -159,7 +1085,7 @@ pub enum Operation {
         commitment: CardCommitment,
     },
     RevealCards {
-        cards: [Card; 2],
+        cards: Vec<Card>,
         salt: String,
     },
     TimeoutPlayer {
@@ -169,78 +1095,861 @@ pub enum Operation {
         player_name: String,
         chips: u64,
         chain_id: String,
+        won_hand: bool,
     },
+    /// `biggest_pot` is taken from the table's own record, not the caller
     SubmitToArena {
         player_name: String,
         chips: u64,
         hands_won: u64,
         hands_played: u64,
-        biggest_pot: u64,
         chain_id: String, // Player's actual chain ID
     },
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TableConfig {
-    pub table_id: String,
-    pub table_name: String,
-    pub max_players: u8,
-    pub small_blind: u64,
-    pub big_blind: u64,
-    pub buy_in_min: u64,
-    pub buy_in_max: u64,
-}
-
-// ============================================================================
-// State
-// ============================================================================
-
-/// Leaderboard entry data
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-pub struct LeaderboardData {
-    pub chips: u64,
-    pub chain_id: String,
-}
-
-#[derive(RootView)]
-#[view(context = ViewStorageContext)]
-pub struct PokerState {
-    pub table_id: RegisterView<String>,
-    pub table_name: RegisterView<String>,
-    pub max_players: RegisterView<u8>,
-    pub small_blind: RegisterView<u64>,
-    pub big_blind: RegisterView<u64>,
-    pub buy_in_min: RegisterView<u64>,
+    /// Table creator only: every seated player posts `ante_per_player` and the
+    /// hand starts on the flop with no pre-flop betting
+    InitiateBombPot {
+        table_id: String,
+        ante_per_player: u64,
+    },
+    /// Any seated player may propose a rule change for a table vote
+    ProposeRuleVote {
+        change: TableConfigPatch,
+    },
+    VoteOnRule {
+        proposal_id: u32,
+        vote: bool,
+    },
+    /// Broadcast ICM prize equity for the current stacks. Refused above
+    /// ten players, where the exact model gets too expensive.
+    RequestIcmEquity {
+        payouts: Vec<u64>,
+    },
+    /// Table creator only, before the first hand of a bounty tournament
+    SetBounty {
+        player_name: String,
+        amount: u64,
+    },
+    FlagHandForReview {
+        hand_number: u64,
+        reason: String,
+        flagged_by: String,
+    },
+    /// Table creator only
+    ResolveFlaggedHand {
+        hand_number: u64,
+        resolution: String,
+    },
+    /// Table creator only, for disputes and negotiated deals
+    DeclareTournamentResult {
+        final_rankings: Vec<(String, u8)>,
+    },
+    /// Table creator only
+    TriggerOrphanRecovery {
+        table_id: String,
+    },
+    /// Sent by the service when it loses a player's connection
+    MarkDisconnected {
+        player_name: String,
+        timestamp_ms: u64,
+    },
+    /// Keep the seat and chips but stop being dealt in
+    SitOut {
+        player_name: String,
+    },
+    /// Sit back in after `SitOut` or a disconnect. A player who missed blinds
+    /// either posts them with the next hand or waits for the big blind.
+    SitIn {
+        player_name: String,
+        wait_for_big_blind: bool,
+    },
+    /// Table creator only: void the hand and refund bets if the deck is corrupted
+    VoidHandDueToCorruption {
+        table_id: String,
+        hand_number: u64,
+    },
+    /// Table creator only: void a hand that has stopped progressing
+    ResetStaleGame {
+        table_id: String,
+    },
+    /// Callable only by the player whose data is exported
+    ExportPlayerData {
+        player_name: String,
+    },
+    /// Table creator only: end the session now and pay out
+    FinalizeSession {
+        table_id: String,
+    },
+    /// After showdown: publish the burned cards for deck verification
+    RevealBurnCards {
+        table_id: String,
+    },
+    RequestRabbitHunt {
+        table_id: String,
+        player_name: String,
+    },
+    /// Pass `None` for both to turn auto-rebuy off and get the unused
+    /// reserve back. `deposit` is paid in with the operation and funds the
+    /// top-ups.
+    SetAutoRebuy {
+        player_name: String,
+        threshold: Option<u64>,
+        top_up_to: Option<u64>,
+        deposit: u64,
+    },
+    /// During the draw: throw away `cards` for the same number of new ones.
+    /// An empty list stands pat.
+    DiscardCards {
+        player_name: String,
+        cards: Vec<Card>,
+    },
+    /// Pineapple: throw away one of the three hole cards
+    DiscardHoleCard {
+        player_name: String,
+        card: Card,
+    },
+    /// Table creator only; `None` ends the rotation
+    SetMixedGameSchedule {
+        schedule: Option<MixedGameSchedule>,
+    },
+    /// Before the deal: the player under the gun posts a live 2x big blind
+    PostStraddle {
+        player_name: String,
+    },
+    /// All-in favourite takes the insurance offered for this hand
+    AcceptInsurance {
+        player_name: String,
+    },
+    /// Cash out the rakeback a player has earned so far
+    ClaimRakeback {
+        player_name: String,
+    },
+    /// Hide losing hands at showdown from now on, or stop hiding them
+    SetAutoMuck {
+        player_name: String,
+        enabled: bool,
+    },
+    /// At showdown or after the hand: show the hole cards anyway
+    ShowHand {
+        player_name: String,
+    },
+    /// At showdown, in turn: give up the pot without showing
+    MuckHand {
+        player_name: String,
+    },
+    /// Any seated player: call off a hand that cannot finish and refund it
+    CancelHand {
+        player_name: String,
+    },
+    /// Between hands at a cash table: buy more chips, up to the maximum
+    /// buy-in
+    AddChips {
+        player_name: String,
+        amount: u64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableConfig {
+    pub table_id: String,
+    pub table_name: String,
+    pub creator: String,
+    pub max_players: u8,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub buy_in_min: u64,
+    pub buy_in_max: u64,
+    /// Timeouts in a row before a player is automatically sat out
+    pub max_consecutive_timeouts: u8,
+    /// Split knockout bounties between the eliminator and a jackpot
+    pub progressive_bounty: bool,
+    /// Tournament buy-in and payouts; `None` for cash games
+    pub tournament: Option<TournamentConfig>,
+    /// How long a disconnected player has to return before auto-acting
+    pub disconnect_grace_period_ms: u64,
+    /// How long a hand may sit in one phase before it can be reset as stale
+    pub stale_threshold_ms: u64,
+    /// Fixed-length formats end after this many hands
+    pub max_hands_per_session: Option<u32>,
+    /// Cash tables may let players buy more chips between hands
+    pub rebuys_enabled: bool,
+    /// `None` allows unlimited rebuys
+    pub max_rebuys_per_session: Option<u32>,
+    /// Players with chips needed before a hand can be dealt
+    pub min_players_to_start: u8,
+    pub rules: GameRules,
+    pub mixed_schedule: Option<MixedGameSchedule>,
+    /// Make every Nth hand a bomb pot with `bomb_pot_ante` from each player
+    pub bomb_pot_frequency: Option<u32>,
+    pub bomb_pot_ante: u64,
+    /// Blinds and antes step up through these levels; empty keeps them fixed
+    pub blind_schedule: BlindSchedule,
+    /// Offer insurance to all-in favourites, backed by `insurance_pool`
+    pub insurance_enabled: bool,
+    pub insurance_pool: u64,
+    /// House edge added to the fair insurance premium
+    pub insurance_margin_percent: u8,
+    /// House cut of each pot, up to `rake_cap` a hand; `None` is uncapped
+    pub rake_percent: u8,
+    pub rake_cap: Option<u64>,
+    /// Hands that end before the flop are not raked
+    pub no_flop_no_drop: bool,
+    /// Share of their paid rake players can claim back as chips
+    pub rakeback_percent: u8,
+    /// Time each player has to show or muck in turn at showdown
+    pub showdown_reveal_timeout_ms: u64,
+    /// How long players have to commit to their hole cards before the hand
+    /// can be cancelled
+    pub commitment_timeout_ms: u64,
+    /// Players sitting out this many orbits lose their seat; `None` never
+    pub max_sit_out_orbits: Option<u32>,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            table_id: String::new(),
+            table_name: String::new(),
+            creator: String::new(),
+            max_players: 9,
+            small_blind: 10,
+            big_blind: 20,
+            buy_in_min: 400,
+            buy_in_max: 2000,
+            max_consecutive_timeouts: 2,
+            progressive_bounty: false,
+            tournament: None,
+            disconnect_grace_period_ms: 30_000,
+            stale_threshold_ms: 600_000,
+            max_hands_per_session: None,
+            rebuys_enabled: false,
+            max_rebuys_per_session: None,
+            min_players_to_start: 2,
+            rules: GameRules::default(),
+            mixed_schedule: None,
+            bomb_pot_frequency: None,
+            bomb_pot_ante: 0,
+            blind_schedule: BlindSchedule::default(),
+            insurance_enabled: false,
+            insurance_pool: 0,
+            insurance_margin_percent: 5,
+            rake_percent: 0,
+            rake_cap: None,
+            no_flop_no_drop: true,
+            rakeback_percent: 0,
+            showdown_reveal_timeout_ms: 30_000,
+            commitment_timeout_ms: 60_000,
+            max_sit_out_orbits: Some(3),
+        }
+    }
+}
+
+impl TableConfig {
+    pub fn validate(&self) -> Result<(), PokerError> {
+        if self.min_players_to_start < 2 || self.min_players_to_start > self.max_players {
+            return Err(PokerError::InvalidConfig(format!(
+                "Minimum players to start must be between 2 and {}",
+                self.max_players
+            )));
+        }
+        if let Some(schedule) = &self.mixed_schedule {
+            schedule.validate()?;
+        }
+        self.blind_schedule.validate()?;
+        if let Some(tournament) = &self.tournament {
+            if tournament.players < 2
+                || tournament.players > self.max_players
+                || tournament.starting_stack == 0
+            {
+                return Err(PokerError::InvalidConfig(format!(
+                    "A Sit & Go needs between 2 and {} players and a starting stack",
+                    self.max_players
+                )));
+            }
+        }
+        if self.rake_percent > 100 || self.rakeback_percent > 100 {
+            return Err(PokerError::InvalidConfig(
+                "Rake and rakeback can be at most 100 percent".to_string(),
+            ));
+        }
+        if self
+            .bomb_pot_frequency
+            .is_some_and(|frequency| frequency > 0)
+            && self.bomb_pot_ante == 0
+        {
+            return Err(PokerError::InvalidConfig(
+                "Scheduled bomb pots need an ante".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// Leaderboard entry data
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LeaderboardData {
+    pub chips: u64,
+    pub chain_id: String,
+    pub win_streak: u32,
+    pub loss_streak: u32,
+    pub current_streak_type: StreakType,
+    pub current_streak_length: u32,
+    pub longest_win_streak: u32,
+    pub longest_loss_streak: u32,
+}
+
+impl LeaderboardData {
+    /// Extend or restart the current streak; all-time records only ever grow
+    pub fn record_hand(&mut self, won_hand: bool) {
+        if won_hand {
+            self.win_streak += 1;
+            self.loss_streak = 0;
+            self.current_streak_type = StreakType::Win;
+            self.current_streak_length = self.win_streak;
+            self.longest_win_streak = self.longest_win_streak.max(self.win_streak);
+        } else {
+            self.loss_streak += 1;
+            self.win_streak = 0;
+            self.current_streak_type = StreakType::Loss;
+            self.current_streak_length = self.loss_streak;
+            self.longest_loss_streak = self.longest_loss_streak.max(self.loss_streak);
+        }
+    }
+}
+
+#[derive(RootView)]
+#[view(context = ViewStorageContext)]
+pub struct PokerState {
+    pub table_id: RegisterView<String>,
+    pub table_name: RegisterView<String>,
+    pub max_players: RegisterView<u8>,
+    pub small_blind: RegisterView<u64>,
+    pub big_blind: RegisterView<u64>,
+    pub buy_in_min: RegisterView<u64>,
     pub buy_in_max: RegisterView<u64>,
     pub players: MapView<String, PokerPlayer>,
     pub player_order: RegisterView<Vec<String>>,
+    /// Seat number of each player in `player_order`, in the same order
+    pub occupied_seats: RegisterView<Vec<u8>>,
     pub phase: RegisterView<GamePhase>,
+    /// Seat number of the button. Stays put when players come and go, even
+    /// if the button's own seat is vacated.
     pub dealer_position: RegisterView<u8>,
+    /// Seat number of the player to act
     pub current_player_index: RegisterView<u8>,
     pub deck: RegisterView<Vec<Card>>,
     pub community_cards: RegisterView<Vec<Card>>,
     pub pot: RegisterView<u64>,
     pub current_bet: RegisterView<u64>,
     pub hand_number: RegisterView<u64>,
+    /// Set once in `initialize`; every hand's shuffle is derived from it
     pub random_seed: RegisterView<[u8; 32]>,
     pub leaderboard: MapView<String, LeaderboardData>,
+    pub table_creator: RegisterView<String>,
+    pub bomb_pot_this_hand: RegisterView<bool>,
+    pub max_consecutive_timeouts: RegisterView<u8>,
+    pub pending_proposal: RegisterView<Option<RuleProposal>>,
+    pub next_proposal_id: RegisterView<u32>,
+    pub bounty_pool: RegisterView<u64>,
+    pub progressive_bounty: RegisterView<bool>,
+    pub stake_at_hand_start: MapView<String, u64>,
+    pub soft_play_detector: MapView<(String, String), PairStats>,
+    pub flagged_hands: RegisterView<Vec<FlaggedHand>>,
+    pub tournament_config: RegisterView<Option<TournamentConfig>>,
+    /// Busted players, first out first
+    pub elimination_order: RegisterView<Vec<String>>,
+    /// Buy-ins paid in by registered players
+    pub prize_pool: RegisterView<u64>,
+    /// Set once the Sit & Go has filled up; registration is closed after
+    pub tournament_started: RegisterView<bool>,
+    pub mixed_schedule: RegisterView<Option<MixedGameSchedule>>,
+    pub current_variant_index: RegisterView<u8>,
+    /// Whether the last all-in was a full raise that lets acted players go again
+    pub reopened_by_all_in: RegisterView<bool>,
+    /// Largest single pot each player has won at this table
+    pub biggest_pot_won: MapView<String, u64>,
+    pub disconnect_grace_period_ms: RegisterView<u64>,
+    /// Variant, betting structure, antes and the other rules of the game
+    pub rules: RegisterView<GameRules>,
+    /// Antes, dead blinds and straddles in the pot before any betting
+    pub initial_pot: RegisterView<u64>,
+    pub stale_threshold_ms: RegisterView<u64>,
+    /// When `phase` last changed
+    pub last_state_change_ms: RegisterView<u64>,
+    /// Exports waiting for the service to pick them up, by player
+    pub pending_exports: MapView<String, PlayerDataExport>,
+    pub chip_transfer_log: RegisterView<Vec<ChipTransfer>>,
+    /// Most recent chat messages, oldest first
+    pub chat_log: RegisterView<Vec<ChatEntry>>,
+    pub max_hands_per_session: RegisterView<Option<u32>>,
+    /// Set once the session has been paid out; no further hands start
+    pub session_complete: RegisterView<bool>,
+    /// Cards burned face down this hand, in the order they were burned
+    pub burn_cards: RegisterView<Vec<Card>>,
+    /// Events of the current hand, in order
+    pub hand_history: RegisterView<Vec<HandEvent>>,
+    /// Events of finished hands, by hand number
+    pub past_hands: MapView<u64, Vec<HandEvent>>,
+    /// Board cards shown after a hand ended early, by hand number
+    pub rabbit_hunt_log: MapView<u64, Vec<Card>>,
+    pub rebuys_enabled: RegisterView<bool>,
+    pub max_rebuys_per_session: RegisterView<Option<u32>>,
+    pub action_frequencies: MapView<String, ActionFrequency>,
+    /// Last player to bet or raise on the current street
+    pub last_aggressor: RegisterView<Option<String>>,
+    /// Players who have shown at this showdown, in order
+    pub shown_at_showdown: RegisterView<Vec<String>>,
+    pub min_players_to_start: RegisterView<u8>,
+    /// Cards thrown away in the draw this hand
+    pub discard_pile: RegisterView<Vec<Card>>,
+    /// Draws finished so far this hand
+    pub draw_rounds_completed: RegisterView<u8>,
+    pub bomb_pot_frequency: RegisterView<Option<u32>>,
+    pub bomb_pot_ante: RegisterView<u64>,
+    /// Raises made on the current street, for the fixed-limit cap
+    pub raises_this_street: RegisterView<u8>,
+    /// Straddle posted this hand; it sets the pre-flop bet and raise size
+    pub straddle_amount: RegisterView<u64>,
+    pub blind_schedule: RegisterView<BlindSchedule>,
+    pub blind_level: RegisterView<u32>,
+    /// When the current blind level began, by block time and hand number
+    pub blind_level_started_ms: RegisterView<u64>,
+    pub blind_level_started_hand: RegisterView<u64>,
+    /// Blinds of the last hand and the seating they were posted in, so the
+    /// big blind keeps moving forward when players leave
+    pub small_blind_player: RegisterView<Option<String>>,
+    pub big_blind_player: RegisterView<Option<String>>,
+    pub blind_seat_order: RegisterView<Vec<String>>,
+    pub dead_button: RegisterView<bool>,
+    pub insurance_enabled: RegisterView<bool>,
+    pub insurance_margin_percent: RegisterView<u8>,
+    /// House funds that pay insurance claims; accepted offers hold back
+    /// their worst case until settled
+    pub insurance_pool: RegisterView<u64>,
+    pub insurance_offers: RegisterView<Vec<InsuranceOffer>>,
+    /// Rabbit hunt fees waiting to seed the next hand's pot
+    pub rabbit_hunt_fees: RegisterView<u64>,
+    /// Main pot first, then one side pot per all-in level; they add up to
+    /// `pot`
+    pub side_pots: RegisterView<Vec<SidePot>>,
+    pub rake_percent: RegisterView<u8>,
+    pub rake_cap: RegisterView<Option<u64>>,
+    pub no_flop_no_drop: RegisterView<bool>,
+    /// Rake taken from all pots so far, held for the operator to withdraw
+    pub rake_collected: RegisterView<u64>,
+    pub rakeback_percent: RegisterView<u8>,
+    /// Lifetime rake each player has paid, and how much of it has already
+    /// been returned as rakeback
+    pub rake_paid: MapView<String, u64>,
+    pub rakeback_claimed: MapView<String, u64>,
+    /// Size of the last full bet or raise this street and the total it bet
+    /// to; all-ins short of a full raise change neither
+    pub last_raise_size: RegisterView<u64>,
+    pub full_bet_level: RegisterView<u64>,
+    /// Players who have acted on the current street
+    pub acted_this_street: RegisterView<Vec<String>>,
+    /// Players who chose to show their cards this hand
+    pub shown_voluntarily: RegisterView<Vec<String>>,
+    pub showdown_reveal_timeout_ms: RegisterView<u64>,
+    /// When the current player's turn to show or muck began
+    pub showdown_turn_started_ms: RegisterView<u64>,
+    /// Every chip at the table when the hand began, for the conservation
+    /// check in `invariants`
+    pub chips_at_hand_start: RegisterView<u64>,
+    pub commitment_timeout_ms: RegisterView<u64>,
+    pub max_sit_out_orbits: RegisterView<Option<u32>>,
+    /// Players waiting for a seat, first come first served
+    pub waiting_list: RegisterView<Vec<WaitingPlayer>>,
+    /// Tournament chips bought during a hand, added once it is over
+    pub pending_tournament_chips: MapView<String, u64>,
+    /// Tournament moves that came in during a hand, made once it is over
+    pub pending_tournament_moves: RegisterView<Vec<TournamentMove>>,
 }
 
 impl PokerState {
-    pub async fn initialize(&mut self, config: TableConfig) {
+    /// Set up a new table. `chain_id` and `created_ms`, the block time, seed
+    /// the table's shuffles so that no caller chooses them.
+    pub async fn initialize(&mut self, config: TableConfig, chain_id: &str, created_ms: u64) {
+        self.random_seed.set(Self::table_seed(chain_id, created_ms));
         self.table_id.set(config.table_id);
         self.table_name.set(config.table_name);
+        self.table_creator.set(config.creator);
         self.max_players.set(config.max_players);
         self.small_blind.set(config.small_blind);
         self.big_blind.set(config.big_blind);
         self.buy_in_min.set(config.buy_in_min);
         self.buy_in_max.set(config.buy_in_max);
+        self.max_consecutive_timeouts
+            .set(config.max_consecutive_timeouts);
         self.phase.set(GamePhase::WaitingForPlayers);
         self.hand_number.set(0);
         self.pot.set(0);
         self.current_bet.set(0);
+        self.occupied_seats.set(Vec::new());
         self.dealer_position.set(0);
         self.current_player_index.set(0);
+        self.bomb_pot_this_hand.set(false);
+        self.progressive_bounty.set(config.progressive_bounty);
+        self.tournament_config.set(config.tournament);
+        self.prize_pool.set(0);
+        self.tournament_started.set(false);
+        self.disconnect_grace_period_ms
+            .set(config.disconnect_grace_period_ms);
+        self.rules.set(config.rules);
+        self.stale_threshold_ms.set(config.stale_threshold_ms);
+        self.max_hands_per_session.set(config.max_hands_per_session);
+        self.rebuys_enabled.set(config.rebuys_enabled);
+        self.max_rebuys_per_session
+            .set(config.max_rebuys_per_session);
+        self.min_players_to_start.set(config.min_players_to_start);
+        self.mixed_schedule.set(config.mixed_schedule);
+        self.bomb_pot_frequency.set(config.bomb_pot_frequency);
+        self.bomb_pot_ante.set(config.bomb_pot_ante);
+        if let Some(level) = config.blind_schedule.levels.first() {
+            self.small_blind.set(level.small_blind);
+            self.big_blind.set(level.big_blind);
+            self.rules.get_mut().ante = level.ante;
+        }
+        self.blind_schedule.set(config.blind_schedule);
+        self.insurance_enabled.set(config.insurance_enabled);
+        self.insurance_pool.set(config.insurance_pool);
+        self.insurance_margin_percent
+            .set(config.insurance_margin_percent);
+        self.rake_percent.set(config.rake_percent);
+        self.rake_cap.set(config.rake_cap);
+        self.no_flop_no_drop.set(config.no_flop_no_drop);
+        self.rakeback_percent.set(config.rakeback_percent);
+        self.showdown_reveal_timeout_ms
+            .set(config.showdown_reveal_timeout_ms);
+        self.commitment_timeout_ms.set(config.commitment_timeout_ms);
+        self.max_sit_out_orbits.set(config.max_sit_out_orbits);
+    }
+
+    /// Admin operations are restricted to whoever created the table
+    pub fn require_table_creator(
+        table_creator: &str,
+        caller: &str,
+        action: &str,
+    ) -> Result<(), PokerError> {
+        if caller != table_creator {
+            return Err(PokerError::Unauthorized(format!(
+                "Only the table creator can {}",
+                action
+            )));
+        }
+        Ok(())
+    }
+
+    /// Every operation names its table; reject ones meant for another
+    pub fn require_table(&self, table_id: &str) -> Result<(), PokerError> {
+        Self::check_table_id(self.table_id.get(), table_id)
+    }
+
+    pub fn check_table_id(this_table: &str, table_id: &str) -> Result<(), PokerError> {
+        if this_table != table_id {
+            return Err(PokerError::TableNotFound(table_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// A new player needs a free seat and a name nobody at the table uses
+    pub fn check_seat_available(
+        player_order: &[String],
+        max_players: u8,
+        player_name: &str,
+    ) -> Result<(), PokerError> {
+        if player_order.iter().any(|name| name == player_name) {
+            return Err(PokerError::DuplicatePlayer(player_name.to_string()));
+        }
+        if player_order.len() >= max_players as usize {
+            return Err(PokerError::TableFull);
+        }
+        Ok(())
+    }
+
+    pub fn require_turn(&self, player_name: &str) -> Result<(), PokerError> {
+        Self::check_turn(
+            self.player_order.get(),
+            self.occupied_seats.get(),
+            *self.current_player_index.get(),
+            player_name,
+        )
+    }
+
+    pub fn check_turn(
+        player_order: &[String],
+        occupied_seats: &[u8],
+        current_seat: u8,
+        player_name: &str,
+    ) -> Result<(), PokerError> {
+        let current = occupied_seats
+            .iter()
+            .position(|&seat| seat == current_seat)
+            .and_then(|index| player_order.get(index));
+        match current {
+            Some(current) if current == player_name => Ok(()),
+            _ => Err(PokerError::NotPlayersTurn(player_name.to_string())),
+        }
+    }
+
+    /// Bets larger than the stack must be made as an all-in
+    pub fn require_chips(player: &PokerPlayer, needed: u64) -> Result<(), PokerError> {
+        if player.chips < needed {
+            return Err(PokerError::InsufficientChips {
+                needed,
+                available: player.chips,
+            });
+        }
+        Ok(())
+    }
+
+    /// Load seated players in seat order
+    pub async fn seated_players(&self) -> Vec<PokerPlayer> {
+        let mut players = Vec::new();
+        for name in self.player_order.get() {
+            if let Some(player) = self.players.get(name).await.expect("Failed to load player") {
+                players.push(player);
+            }
+        }
+        players
+    }
+
+    /// Write back players previously loaded with `seated_players`
+    pub fn save_players(&mut self, players: &[PokerPlayer]) {
+        for player in players {
+            self.players
+                .insert(&player.name, player.clone())
+                .expect("Failed to save player");
+        }
+    }
+
+    /// Start the next hand as a bomb pot: deal everyone in, collect the ante
+    /// from every seated player and skip straight to the flop. Returns the
+    /// total pot.
+    pub async fn initiate_bomb_pot(
+        &mut self,
+        caller: &str,
+        ante_per_player: u64,
+    ) -> Result<u64, PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "initiate a bomb pot")?;
+        self.start_bomb_pot(ante_per_player).await
+    }
+
+    /// Called from `StartHand`: when the coming hand is due a scheduled bomb
+    /// pot, start it and return the total pot
+    pub async fn start_scheduled_bomb_pot(&mut self) -> Result<Option<u64>, PokerError> {
+        let next_hand = Self::next_hand_number(*self.hand_number.get())?;
+        if !Self::is_scheduled_bomb_pot(next_hand, *self.bomb_pot_frequency.get()) {
+            return Ok(None);
+        }
+        let ante = *self.bomb_pot_ante.get();
+        self.start_bomb_pot(ante).await.map(Some)
+    }
+
+    /// Every `frequency`th hand is a bomb pot
+    pub fn is_scheduled_bomb_pot(hand_number: u64, frequency: Option<u32>) -> bool {
+        match frequency {
+            Some(frequency) if frequency > 0 => hand_number % frequency as u64 == 0,
+            _ => false,
+        }
+    }
+
+    async fn start_bomb_pot(&mut self, ante_per_player: u64) -> Result<u64, PokerError> {
+        if ante_per_player == 0 {
+            return Err(PokerError::InvalidOperation(
+                "Bomb pot ante must be greater than zero".to_string(),
+            ));
+        }
+        if Self::is_hand_in_progress(self.phase.get()) {
+            return Err(PokerError::InvalidPhase {
+                expected: GamePhase::WaitingForPlayers,
+                actual: self.phase.get().clone(),
+            });
+        }
+        if !self.current_variant().uses_community_cards() {
+            return Err(PokerError::NotEnabled(
+                "Bomb pots are only played in games with a flop".to_string(),
+            ));
+        }
+
+        let participants = self
+            .seated_players()
+            .await
+            .iter()
+            .filter(|p| p.status != PlayerStatus::SittingOut && p.chips > 0)
+            .count();
+        if participants < 2 {
+            return Err(PokerError::NotEnoughPlayers {
+                have: participants,
+                need: 2,
+            });
+        }
+
+        self.begin_hand().await?;
+        let mut players = self.seated_players().await;
+        let collected = Self::collect_bomb_pot_antes(&mut players, ante_per_player);
+        self.save_players(&players);
+
+        let fees = std::mem::take(self.rabbit_hunt_fees.get_mut());
+        let total_pot = *self.pot.get() + collected + fees;
+        self.pot.set(total_pot);
+        self.initial_pot.set(collected + fees);
+        self.bomb_pot_this_hand.set(true);
+        self.phase.set(Self::first_betting_phase(true));
+        self.deal_community_cards(3, true);
+        let first = self.occupied_seats.get()[self.first_to_act()];
+        self.current_player_index.set(first);
+        Ok(total_pot)
+    }
+
+    /// Setup every hand shares, from `StartHand` or a bomb pot, before any
+    /// chips go in: move the button and record who owes the blinds, clear
+    /// the last hand's board and betting, snapshot the starting stacks and
+    /// deal hole cards from a fresh deck. Returns the new hand number.
+    pub async fn begin_hand(&mut self) -> Result<u64, PokerError> {
+        self.assign_button();
+        let hand_number = self.increment_hand_number()?;
+
+        self.current_bet.set(0);
+        self.community_cards.set(Vec::new());
+        self.burn_cards.set(Vec::new());
+        self.discard_pile.set(Vec::new());
+        self.draw_rounds_completed.set(0);
+        self.raises_this_street.set(0);
+        self.straddle_amount.set(0);
+        self.last_aggressor.set(None);
+        self.reset_raise_tracking();
+        self.shown_at_showdown.set(Vec::new());
+        self.shown_voluntarily.set(Vec::new());
+        self.side_pots.set(Vec::new());
+        self.bomb_pot_this_hand.set(false);
+        self.snapshot_hand_start_stacks().await;
+        invariants::record_hand_start(self).await;
+
+        let variant = self.current_variant();
+        let mut deck = Self::shuffle_deck_for_variant(self.hand_seed(hand_number), variant);
+        let mut players = self.seated_players().await;
+        let mut dealt_in: Vec<&mut PokerPlayer> = players
+            .iter_mut()
+            .filter(|p| p.status != PlayerStatus::SittingOut && p.chips > 0)
+            .collect();
+        let hands = Self::deal_hole_cards(&mut deck, dealt_in.len(), variant);
+        for (player, cards) in dealt_in.iter_mut().zip(hands) {
+            player.hole_cards = Some(cards);
+            player.up_cards = Vec::new();
+            player.has_drawn = false;
+            player.has_folded = false;
+            player.is_all_in = false;
+            player.current_bet = 0;
+            player.total_bet_this_hand = 0;
+            player.last_action = None;
+            player.status = PlayerStatus::Active;
+        }
+        self.save_players(&players);
+        self.deck.set(deck);
+        Ok(hand_number)
+    }
+
+    /// The seed a table created on `chain_id` at `created_ms` deals from
+    pub fn table_seed(chain_id: &str, created_ms: u64) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(chain_id.as_bytes())
+            .chain_update(created_ms.to_le_bytes())
+            .finalize()
+            .into()
+    }
+
+    /// The table's seed hashed with the hand number, so every hand is dealt
+    /// from a different shuffle
+    fn hand_seed(&self, hand_number: u64) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(self.random_seed.get())
+            .chain_update(hand_number.to_le_bytes())
+            .finalize()
+            .into()
+    }
+
+    /// Take `ante_per_player` (or whatever is left) from every player who is
+    /// not sitting out. Short stacks are put all-in. Returns the amount collected.
+    pub fn collect_bomb_pot_antes(players: &mut [PokerPlayer], ante_per_player: u64) -> u64 {
+        let mut collected = 0;
+        for player in players.iter_mut() {
+            if player.status == PlayerStatus::SittingOut || player.chips == 0 {
+                continue;
+            }
+            let ante = Self::move_chips_to_pot(player, ante_per_player.min(player.chips))
+                .expect("Ante is capped at the stack");
+            player.current_bet = 0;
+            player.total_bet_this_hand = ante;
+            player.has_folded = false;
+            player.last_action = None;
+            if player.chips == 0 {
+                player.is_all_in = true;
+                player.status = PlayerStatus::AllIn;
+            } else {
+                player.is_all_in = false;
+                player.status = PlayerStatus::Active;
+            }
+            collected += ante;
+        }
+        collected
+    }
+
+    /// Record a missed action for `player_name`. Returns true if the player
+    /// was automatically sat out as a result.
+    pub async fn timeout_player(&mut self, player_name: &str) -> Result<bool, PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        let sat_out = Self::register_timeout(&mut player, *self.max_consecutive_timeouts.get());
+        self.save_players(&[player]);
+        Ok(sat_out)
+    }
+
+    /// Sit out every player who has reached the timeout limit. Returns the
+    /// names of players whose status changed.
+    pub async fn check_for_dormant_players(&mut self) -> Vec<String> {
+        let max_timeouts = *self.max_consecutive_timeouts.get();
+        let mut players = self.seated_players().await;
+        let mut sat_out = Vec::new();
+        for player in players.iter_mut() {
+            if Self::apply_dormancy(player, max_timeouts) {
+                sat_out.push(player.name.clone());
+            }
+        }
+        self.save_players(&players);
+        sat_out
+    }
+
+    /// Count a missed action and sit the player out once the limit is hit
+    pub fn register_timeout(player: &mut PokerPlayer, max_consecutive_timeouts: u8) -> bool {
+        player.consecutive_timeouts = player.consecutive_timeouts.saturating_add(1);
+        Self::apply_dormancy(player, max_consecutive_timeouts)
+    }
+
+    /// Any successful action clears the timeout streak
+    pub fn register_action(player: &mut PokerPlayer) {
+        player.consecutive_timeouts = 0;
+    }
+
+    fn apply_dormancy(player: &mut PokerPlayer, max_consecutive_timeouts: u8) -> bool {
+        if player.status == PlayerStatus::SittingOut
+            || player.consecutive_timeouts < max_consecutive_timeouts
+        {
+            return false;
+        }
+        player.status = PlayerStatus::SittingOut;
+        true
+    }
+
+    /// First street with betting action; bomb pots skip pre-flop entirely
+    pub fn first_betting_phase(bomb_pot: bool) -> GamePhase {
+        if bomb_pot {
+            GamePhase::Flop
+        } else {
+            GamePhase::PreFlop
+        }
     }
 
     pub fn create_deck() -> Vec<Card> {
@@ -257,21 +1966,7492 @@ impl PokerState {
         deck
     }
 
-    pub fn shuffle_deck(seed: [u8; 32]) -> Vec<Card> {
+    /// The 36-card six-to-ace deck for Short Deck, otherwise the full deck
+    pub fn create_deck_for_variant(variant: GameVariant) -> Vec<Card> {
         let mut deck = Self::create_deck();
-        for i in (1..deck.len()).rev() {
-            let j = Self::deterministic_random(seed, i) % (i + 1);
-            deck.swap(i, j);
+        if variant == GameVariant::ShortDeckHoldem {
+            deck.retain(|card| card.rank >= Rank::Six);
         }
         deck
     }
 
-    fn deterministic_random(seed: [u8; 32], index: usize) -> usize {
-        let mut result = 0usize;
-        for i in 0..4 {
-            result ^= (seed[i] as usize) << (i * 8);
+    pub fn shuffle_deck(seed: [u8; 32]) -> Vec<Card> {
+        Self::shuffle_deck_for_variant(seed, GameVariant::TexasHoldem)
+    }
+
+    pub fn shuffle_deck_for_variant(seed: [u8; 32], variant: GameVariant) -> Vec<Card> {
+        let mut deck = Self::create_deck_for_variant(variant);
+        Self::shuffle_with_seed(&mut deck, seed);
+        deck
+    }
+
+    /// Fisher-Yates driven by `seed`, as used for the deck
+    pub fn shuffle_with_seed<T>(items: &mut [T], seed: [u8; 32]) {
+        for i in (1..items.len()).rev() {
+            let j = (Self::deterministic_random(seed, i) % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Called from `StartHand`: the first hand draws the button by lot, every
+    /// later hand moves the big blind one player to the left under the dead
+    /// button rule. Returns the dealer's index in `player_order`.
+    pub fn assign_button(&mut self) -> u8 {
+        let player_names = self.player_order.get().clone();
+        let hand_number = *self.hand_number.get();
+        let positions = self.next_blind_positions();
+        let dealer = positions.dealer;
+        let seat_name = |seat: u8| player_names.get(seat as usize).cloned();
+        self.small_blind_player
+            .set(positions.small_blind.and_then(seat_name));
+        self.big_blind_player.set(seat_name(positions.big_blind));
+        self.dead_button.set(positions.dead_button);
+        self.blind_seat_order.set(player_names.clone());
+        if hand_number == 0 {
+            if let Some(player) = player_names.get(dealer as usize) {
+                self.hand_history
+                    .get_mut()
+                    .push(HandEvent::InitialButtonAssigned {
+                        player: player.clone(),
+                        method: "random draw".to_string(),
+                    });
+            }
+        }
+        let dealer_seat = self
+            .occupied_seats
+            .get()
+            .get(dealer as usize)
+            .copied()
+            .unwrap_or(0);
+        self.dealer_position.set(dealer_seat);
+        dealer
+    }
+
+    /// Blind positions for the next hand at the current table
+    pub fn next_blind_positions(&self) -> BlindPositions {
+        Self::dead_button_positions(
+            self.blind_seat_order.get(),
+            self.small_blind_player.get().as_deref(),
+            self.big_blind_player.get().as_deref(),
+            self.player_order.get(),
+            Self::button_for_hand(
+                *self.dealer_position.get(),
+                self.occupied_seats.get(),
+                self.player_order.get(),
+                *self.hand_number.get(),
+                *self.random_seed.get(),
+            ),
+        )
+    }
+
+    /// Players in the order the big blind will reach them, next hand's big
+    /// blind first, for the tournament coordinator's `StackReport`
+    pub fn big_blind_order(&self) -> Vec<String> {
+        if self.player_order.get().len() < 2 {
+            return self.player_order.get().clone();
+        }
+        Self::rotate_from(
+            self.player_order.get(),
+            self.next_blind_positions().big_blind as usize,
+        )
+    }
+
+    /// `player_names` starting at `start` and wrapping round
+    pub fn rotate_from(player_names: &[String], start: usize) -> Vec<String> {
+        let mut rotated = player_names.to_vec();
+        if !rotated.is_empty() {
+            rotated.rotate_left(start % player_names.len());
+        }
+        rotated
+    }
+
+    /// Dead button rule: the big blind moves to the next player still seated
+    /// after last hand's big blind, so nobody skips it or posts it twice. The
+    /// last big blind posts the small blind and the last small blind takes
+    /// the button; when either has left, that blind or the button is dead.
+    /// Heads-up the other player has the button and small blind. Without a
+    /// previous big blind the blinds follow `default_dealer`.
+    pub fn dead_button_positions(
+        previous_order: &[String],
+        previous_small_blind: Option<&str>,
+        previous_big_blind: Option<&str>,
+        player_names: &[String],
+        default_dealer: u8,
+    ) -> BlindPositions {
+        let count = player_names.len();
+        let seat_of = |name: &str| {
+            player_names
+                .iter()
+                .position(|seated| seated == name)
+                .map(|seat| seat as u8)
+        };
+        let big_blind = previous_big_blind.and_then(|previous| {
+            let start = previous_order.iter().position(|name| name == previous)?;
+            (1..=previous_order.len())
+                .map(|step| &previous_order[(start + step) % previous_order.len()])
+                .find_map(|name| seat_of(name))
+        });
+        let Some(big_blind) = big_blind.filter(|_| count >= 2) else {
+            return Self::live_button_positions(default_dealer, count);
+        };
+        if count == 2 {
+            let other = (big_blind + 1) % 2;
+            return BlindPositions {
+                dealer: other,
+                dead_button: false,
+                small_blind: Some(other),
+                big_blind,
+            };
+        }
+
+        let small_blind = previous_big_blind
+            .and_then(seat_of)
+            .filter(|&seat| seat != big_blind);
+        let button = previous_small_blind
+            .and_then(seat_of)
+            .filter(|&seat| seat != big_blind && Some(seat) != small_blind);
+        match button {
+            Some(dealer) => BlindPositions {
+                dealer,
+                dead_button: false,
+                small_blind,
+                big_blind,
+            },
+            None => {
+                let first_blind = small_blind.unwrap_or(big_blind) as usize;
+                BlindPositions {
+                    dealer: ((first_blind + count - 1) % count) as u8,
+                    dead_button: true,
+                    small_blind,
+                    big_blind,
+                }
+            }
+        }
+    }
+
+    /// Blinds directly behind a button every player can see
+    pub fn live_button_positions(dealer: u8, player_count: usize) -> BlindPositions {
+        BlindPositions {
+            dealer,
+            dead_button: false,
+            small_blind: Self::small_blind_seat(dealer as usize, player_count)
+                .map(|seat| seat as u8),
+            big_blind: Self::big_blind_seat(dealer as usize, player_count) as u8,
+        }
+    }
+
+    /// Index in `player_names` of the next button: the first player seated
+    /// after last hand's button seat
+    pub fn button_for_hand(
+        dealer_seat: u8,
+        occupied_seats: &[u8],
+        player_names: &[String],
+        hand_number: u64,
+        seed: [u8; 32],
+    ) -> u8 {
+        if player_names.is_empty() {
+            return 0;
+        }
+        if hand_number == 0 {
+            return Self::assign_initial_button(player_names, seed);
+        }
+        let previous = Self::index_at_or_before_seat(occupied_seats, dealer_seat);
+        ((previous + 1) % player_names.len()) as u8
+    }
+
+    /// Index of the player in `seat`, or when the seat is empty, of the
+    /// nearest player seated before it. Lets a vacated button still mark
+    /// where the action starts.
+    pub fn index_at_or_before_seat(occupied_seats: &[u8], seat: u8) -> usize {
+        occupied_seats
+            .iter()
+            .rposition(|&occupied| occupied <= seat)
+            .unwrap_or(occupied_seats.len().saturating_sub(1))
+    }
+
+    /// The button's index in `player_order`
+    fn dealer_index(&self) -> usize {
+        Self::index_at_or_before_seat(self.occupied_seats.get(), *self.dealer_position.get())
+    }
+
+    /// Index in `player_names` of the first button, drawn uniformly
+    pub fn assign_initial_button(player_names: &[String], seed: [u8; 32]) -> u8 {
+        if player_names.is_empty() {
+            return 0;
+        }
+        (Self::deterministic_random(seed, 0) % player_names.len() as u64) as u8
+    }
+
+    /// SHA-256 of the whole seed and `index`, so every seed byte counts
+    fn deterministic_random(seed: [u8; 32], index: usize) -> u64 {
+        let digest = Sha256::new()
+            .chain_update(seed)
+            .chain_update((index as u64).to_le_bytes())
+            .finalize();
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(word)
+    }
+
+    /// Players who count towards rule-vote majorities
+    pub async fn active_player_count(&self) -> usize {
+        self.seated_players()
+            .await
+            .iter()
+            .filter(|p| p.status != PlayerStatus::SittingOut)
+            .count()
+    }
+
+    pub async fn propose_rule_vote(
+        &mut self,
+        proposer: &str,
+        change: TableConfigPatch,
+    ) -> Result<u32, PokerError> {
+        if !self.player_order.get().iter().any(|name| name == proposer) {
+            return Err(PokerError::PlayerNotFound(proposer.to_string()));
+        }
+        if self.pending_proposal.get().is_some() {
+            return Err(PokerError::InvalidOperation(
+                "Another rule proposal is already pending".to_string(),
+            ));
+        }
+        let id = *self.next_proposal_id.get();
+        self.next_proposal_id.set(id + 1);
+        self.pending_proposal.set(Some(RuleProposal {
+            id,
+            proposer: proposer.to_string(),
+            change,
+            votes_for: 0,
+            votes_against: 0,
+            expires_at_hand: *self.hand_number.get() + RuleProposal::VOTING_WINDOW_HANDS,
+            voters: Vec::new(),
+        }));
+        Ok(id)
+    }
+
+    pub async fn vote_on_rule(
+        &mut self,
+        voter: &str,
+        proposal_id: u32,
+        vote: bool,
+    ) -> Result<ProposalOutcome, PokerError> {
+        if !self.player_order.get().iter().any(|name| name == voter) {
+            return Err(PokerError::PlayerNotFound(voter.to_string()));
+        }
+        let active_players = self.active_player_count().await;
+        let hand_number = *self.hand_number.get();
+        let proposal = self
+            .pending_proposal
+            .get_mut()
+            .as_mut()
+            .filter(|p| p.id == proposal_id)
+            .ok_or_else(|| {
+                PokerError::NoOffer(format!("Proposal {} is not pending", proposal_id))
+            })?;
+        proposal.record_vote(voter, vote)?;
+        Ok(proposal.outcome(active_players, hand_number))
+    }
+
+    /// Called at the start of each hand: apply an approved proposal, or drop
+    /// one that was rejected or has expired
+    pub async fn resolve_pending_proposal(&mut self) -> Option<ProposalOutcome> {
+        let proposal = self.pending_proposal.get().clone()?;
+        let outcome = proposal.outcome(self.active_player_count().await, *self.hand_number.get());
+        match outcome {
+            ProposalOutcome::Pending => return None,
+            ProposalOutcome::Approved => self.apply_config_patch(&proposal.change),
+            ProposalOutcome::Rejected | ProposalOutcome::Expired => {}
+        }
+        self.pending_proposal.set(None);
+        Some(outcome)
+    }
+
+    pub fn apply_config_patch(&mut self, patch: &TableConfigPatch) {
+        if let Some(small_blind) = patch.small_blind {
+            self.small_blind.set(small_blind);
+        }
+        if let Some(big_blind) = patch.big_blind {
+            self.big_blind.set(big_blind);
+        }
+        if let Some(buy_in_min) = patch.buy_in_min {
+            self.buy_in_min.set(buy_in_min);
+        }
+        if let Some(buy_in_max) = patch.buy_in_max {
+            self.buy_in_max.set(buy_in_max);
+        }
+        if let Some(max_timeouts) = patch.max_consecutive_timeouts {
+            self.max_consecutive_timeouts.set(max_timeouts);
+        }
+    }
+
+    /// Load a single player or report them missing
+    pub async fn load_player(&self, player_name: &str) -> Result<PokerPlayer, PokerError> {
+        self.players
+            .get(&player_name.to_string())
+            .await
+            .expect("Failed to load player")
+            .ok_or_else(|| PokerError::PlayerNotFound(player_name.to_string()))
+    }
+
+    pub async fn set_bounty(
+        &mut self,
+        caller: &str,
+        player_name: &str,
+        amount: u64,
+    ) -> Result<(), PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "set bounties")?;
+        if *self.hand_number.get() > 0 {
+            return Err(PokerError::InvalidOperation(
+                "Bounties can only be set before the first hand".to_string(),
+            ));
+        }
+        let mut player = self.load_player(player_name).await?;
+        player.bounty_amount = amount;
+        self.save_players(&[player]);
+        Ok(())
+    }
+
+    /// Pay out the bounty of a busted player. Returns the amount credited to
+    /// the eliminator.
+    pub async fn award_bounty(
+        &mut self,
+        eliminator: &str,
+        eliminated: &str,
+    ) -> Result<u64, PokerError> {
+        let mut winner = self.load_player(eliminator).await?;
+        let mut loser = self.load_player(eliminated).await?;
+        if loser.chips > 0 {
+            return Err(PokerError::InvalidOperation(format!(
+                "{} has not been eliminated",
+                eliminated
+            )));
         }
-        result ^= index;
-        result
+
+        let (paid, to_pool) =
+            Self::split_bounty(&mut winner, &mut loser, *self.progressive_bounty.get());
+        self.bounty_pool.set(*self.bounty_pool.get() + to_pool);
+        self.save_players(&[winner, loser]);
+        Ok(paid)
+    }
+
+    /// Move the eliminated player's bounty to the eliminator. Progressive
+    /// tables send half to the bounty pool. Returns (paid, added to pool).
+    pub fn split_bounty(
+        eliminator: &mut PokerPlayer,
+        eliminated: &mut PokerPlayer,
+        progressive: bool,
+    ) -> (u64, u64) {
+        let bounty = std::mem::take(&mut eliminated.bounty_amount);
+        let to_pool = if progressive { bounty / 2 } else { 0 };
+        let paid = bounty - to_pool;
+        eliminator.bounties_won += paid;
+        (paid, to_pool)
+    }
+
+    /// Tournament over: the winner collects the accumulated bounty pool
+    pub async fn distribute_bounty_pool(&mut self, winner: &str) -> Result<u64, PokerError> {
+        let mut player = self.load_player(winner).await?;
+        let pool = *self.bounty_pool.get();
+        player.bounties_won += pool;
+        self.bounty_pool.set(0);
+        self.save_players(&[player]);
+        Ok(pool)
+    }
+
+    pub async fn update_leaderboard(
+        &mut self,
+        player_name: &str,
+        chips: u64,
+        chain_id: String,
+        won_hand: bool,
+    ) -> LeaderboardData {
+        let key = player_name.to_string();
+        let mut entry = self
+            .leaderboard
+            .get(&key)
+            .await
+            .expect("Failed to load leaderboard entry")
+            .unwrap_or_default();
+        entry.chips = chips;
+        entry.chain_id = chain_id;
+        entry.record_hand(won_hand);
+        self.leaderboard
+            .insert(&key, entry.clone())
+            .expect("Failed to save leaderboard entry");
+        entry
+    }
+
+    /// 1-based rank by chips; tied players share the same rank
+    pub async fn leaderboard_rank(&self, player_name: &str) -> Option<usize> {
+        Self::rank_by_chips(&self.leaderboard_entries().await, player_name)
+    }
+
+    pub async fn leaderboard_top_n(&self, n: usize) -> Vec<(String, LeaderboardData)> {
+        Self::leaderboard_page_of(self.leaderboard_entries().await, 0, n)
+    }
+
+    /// Entries for the zero-based `page`, richest first
+    pub async fn leaderboard_page(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Vec<(String, LeaderboardData)> {
+        Self::leaderboard_page_of(self.leaderboard_entries().await, page, page_size)
+    }
+
+    async fn leaderboard_entries(&self) -> Vec<(String, LeaderboardData)> {
+        let mut entries = Vec::new();
+        for name in self
+            .leaderboard
+            .indices()
+            .await
+            .expect("Failed to load leaderboard")
+        {
+            if let Some(entry) = self
+                .leaderboard
+                .get(&name)
+                .await
+                .expect("Failed to load leaderboard entry")
+            {
+                entries.push((name, entry));
+            }
+        }
+        entries
+    }
+
+    pub fn rank_by_chips(
+        entries: &[(String, LeaderboardData)],
+        player_name: &str,
+    ) -> Option<usize> {
+        let (_, player) = entries.iter().find(|(name, _)| name == player_name)?;
+        let ahead = entries
+            .iter()
+            .filter(|(_, entry)| entry.chips > player.chips)
+            .count();
+        Some(ahead + 1)
+    }
+
+    /// Sort by chips, most first, with ties in name order, and slice out one page
+    pub fn leaderboard_page_of(
+        mut entries: Vec<(String, LeaderboardData)>,
+        page: usize,
+        page_size: usize,
+    ) -> Vec<(String, LeaderboardData)> {
+        entries.sort_by(|(a_name, a), (b_name, b)| {
+            b.chips.cmp(&a.chips).then_with(|| a_name.cmp(b_name))
+        });
+        entries
+            .into_iter()
+            .skip(page.saturating_mul(page_size))
+            .take(page_size)
+            .collect()
+    }
+
+    /// True while a hand is being played
+    pub fn is_hand_in_progress(phase: &GamePhase) -> bool {
+        !matches!(
+            phase,
+            GamePhase::WaitingForPlayers | GamePhase::HandComplete
+        )
+    }
+
+    /// Table stakes: chips can only be added between hands
+    pub fn enforce_table_stakes(&self) -> Result<(), PokerError> {
+        if Self::is_hand_in_progress(self.phase.get()) {
+            return Err(PokerError::InvalidPhase {
+                expected: GamePhase::WaitingForPlayers,
+                actual: self.phase.get().clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Record every seated player's stack; called when a hand starts
+    pub async fn snapshot_hand_start_stacks(&mut self) {
+        self.stake_at_hand_start.clear();
+        for player in self.seated_players().await {
+            self.stake_at_hand_start
+                .insert(&player.name, player.chips)
+                .expect("Failed to save starting stack");
+        }
+    }
+
+    /// Most `winner` can take from `loser` this hand under table stakes
+    pub async fn max_winnable_from(&self, winner: &str, loser: &str) -> u64 {
+        Self::table_stakes_cap(
+            self.starting_stack(winner).await,
+            self.starting_stack(loser).await,
+        )
+    }
+
+    /// The stack each player dealt into this hand started it with, for pot
+    /// layering
+    pub async fn hand_start_stakes(&self) -> Vec<(String, u64)> {
+        let mut stakes = Vec::new();
+        for player in self.seated_players().await {
+            let stake = self
+                .stake_at_hand_start
+                .get(&player.name)
+                .await
+                .expect("Failed to load starting stack");
+            if let Some(stake) = stake {
+                stakes.push((player.name, stake));
+            }
+        }
+        stakes
+    }
+
+    async fn starting_stack(&self, player_name: &str) -> u64 {
+        self.stake_at_hand_start
+            .get(&player_name.to_string())
+            .await
+            .expect("Failed to load starting stack")
+            .unwrap_or(0)
+    }
+
+    pub fn table_stakes_cap(winner_start_stack: u64, loser_start_stack: u64) -> u64 {
+        winner_start_stack.min(loser_start_stack)
+    }
+
+    /// Pair stats are stored once per unordered pair
+    pub fn pair_key(player_a: &str, player_b: &str) -> (String, String) {
+        if player_a <= player_b {
+            (player_a.to_string(), player_b.to_string())
+        } else {
+            (player_b.to_string(), player_a.to_string())
+        }
+    }
+
+    /// Fold a completed hand into the soft-play statistics of every pair
+    pub async fn record_soft_play_stats(&mut self, participants: &[HandParticipation]) {
+        for (i, first) in participants.iter().enumerate() {
+            for second in &participants[i + 1..] {
+                let key = Self::pair_key(&first.player, &second.player);
+                let mut stats = self
+                    .soft_play_detector
+                    .get(&key)
+                    .await
+                    .expect("Failed to load pair stats")
+                    .unwrap_or_default();
+                stats.record_hand(first, second);
+                self.soft_play_detector
+                    .insert(&key, stats)
+                    .expect("Failed to save pair stats");
+            }
+        }
+    }
+
+    pub async fn softplay_suspicion_score(&self, player_a: &str, player_b: &str) -> f32 {
+        self.soft_play_detector
+            .get(&Self::pair_key(player_a, player_b))
+            .await
+            .expect("Failed to load pair stats")
+            .map(|stats| stats.suspicion_score())
+            .unwrap_or(0.0)
+    }
+
+    /// Unresolved flags allowed at once
+    pub const MAX_UNRESOLVED_FLAGS: usize = 50;
+
+    pub fn flag_hand_for_review(
+        &mut self,
+        hand_number: u64,
+        reason: String,
+        flagged_by: String,
+        timestamp_ms: u64,
+    ) -> Result<(), PokerError> {
+        if hand_number == 0 || hand_number > *self.hand_number.get() {
+            return Err(PokerError::InvalidOperation(format!(
+                "Hand {} has not been played",
+                hand_number
+            )));
+        }
+        Self::push_flag(
+            self.flagged_hands.get_mut(),
+            FlaggedHand {
+                hand_number,
+                reason,
+                flagged_by,
+                timestamp_ms,
+                resolved: false,
+                resolution: None,
+            },
+        )
+    }
+
+    pub fn resolve_flagged_hand(
+        &mut self,
+        caller: &str,
+        hand_number: u64,
+        resolution: String,
+    ) -> Result<(), PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "resolve flagged hands")?;
+        Self::resolve_flag(self.flagged_hands.get_mut(), hand_number, resolution)
+    }
+
+    /// Flags filtered by resolution state; `None` returns all of them
+    pub fn flagged_hands_by_status(&self, resolved: Option<bool>) -> Vec<FlaggedHand> {
+        self.flagged_hands
+            .get()
+            .iter()
+            .filter(|flag| resolved.is_none_or(|resolved| flag.resolved == resolved))
+            .cloned()
+            .collect()
+    }
+
+    pub fn push_flag(flags: &mut Vec<FlaggedHand>, flag: FlaggedHand) -> Result<(), PokerError> {
+        let unresolved = flags.iter().filter(|f| !f.resolved).count();
+        if unresolved >= Self::MAX_UNRESOLVED_FLAGS {
+            return Err(PokerError::LimitReached(
+                "Too many unresolved flagged hands".to_string(),
+            ));
+        }
+        flags.push(flag);
+        Ok(())
+    }
+
+    /// Resolves every open flag on `hand_number`
+    pub fn resolve_flag(
+        flags: &mut [FlaggedHand],
+        hand_number: u64,
+        resolution: String,
+    ) -> Result<(), PokerError> {
+        let mut found = false;
+        for flag in flags
+            .iter_mut()
+            .filter(|f| f.hand_number == hand_number && !f.resolved)
+        {
+            flag.resolved = true;
+            flag.resolution = Some(resolution.clone());
+            found = true;
+        }
+        if !found {
+            return Err(PokerError::InvalidOperation(format!(
+                "Hand {} has no open flags",
+                hand_number
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn compute_stack_depth_category(chips: u64, big_blind: u64) -> StackDepthCategory {
+        if big_blind == 0 {
+            return StackDepthCategory::DeepStack;
+        }
+        match chips / big_blind {
+            100.. => StackDepthCategory::DeepStack,
+            40.. => StackDepthCategory::MidStack,
+            20.. => StackDepthCategory::ShortStack,
+            10.. => StackDepthCategory::CriticalStack,
+            _ => StackDepthCategory::PushFoldStack,
+        }
+    }
+
+    /// Harrington's M: how many orbits the stack survives on blinds and antes
+    pub fn compute_m_ratio(
+        chips: u64,
+        small_blind: u64,
+        big_blind: u64,
+        ante: u64,
+        players: u8,
+    ) -> f64 {
+        let cost_per_orbit = small_blind + big_blind + ante * players as u64;
+        if cost_per_orbit == 0 {
+            return f64::INFINITY;
+        }
+        chips as f64 / cost_per_orbit as f64
+    }
+
+    /// Stack relative to the table average
+    pub fn compute_q_ratio(chips: u64, average_stack: u64) -> f64 {
+        if average_stack == 0 {
+            return 0.0;
+        }
+        chips as f64 / average_stack as f64
+    }
+
+    pub fn record_elimination(&mut self, player_name: &str) {
+        let eliminated = self.elimination_order.get_mut();
+        if !eliminated.iter().any(|name| name == player_name) {
+            eliminated.push(player_name.to_string());
+        }
+    }
+
+    /// Once a single player has chips left, returns the final rankings and
+    /// prizes for `Message::TournamentComplete`
+    pub async fn check_tournament_complete(
+        &self,
+    ) -> Option<(Vec<(String, u8)>, Vec<(String, u64)>)> {
+        self.tournament_config.get().as_ref()?;
+        let players = self.seated_players().await;
+        let mut remaining = players.iter().filter(|player| player.chips > 0);
+        let survivor = remaining.next()?;
+        if remaining.next().is_some() {
+            return None;
+        }
+
+        let final_rankings = Self::final_rankings(&survivor.name, self.elimination_order.get());
+        let prizes = self.distribute_tournament_prizes(&final_rankings);
+        Some((final_rankings, prizes))
+    }
+
+    pub fn declare_tournament_result(
+        &self,
+        caller: &str,
+        final_rankings: &[(String, u8)],
+    ) -> Result<Vec<(String, u64)>, PokerError> {
+        Self::require_table_creator(
+            self.table_creator.get(),
+            caller,
+            "declare tournament results",
+        )?;
+        if self.tournament_config.get().is_none() {
+            return Err(PokerError::NotEnabled(
+                "This table is not running a tournament".to_string(),
+            ));
+        }
+        if final_rankings.is_empty() || final_rankings.iter().any(|(_, rank)| *rank == 0) {
+            return Err(PokerError::InvalidOperation(
+                "Final rankings must be non-empty and start at rank 1".to_string(),
+            ));
+        }
+        Ok(self.distribute_tournament_prizes(final_rankings))
+    }
+
+    pub fn distribute_tournament_prizes(
+        &self,
+        final_rankings: &[(String, u8)],
+    ) -> Vec<(String, u64)> {
+        match self.tournament_config.get() {
+            Some(config) => {
+                Self::compute_tournament_prizes(config, *self.prize_pool.get(), final_rankings)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Survivor first, then the most recently eliminated players
+    pub fn final_rankings(survivor: &str, elimination_order: &[String]) -> Vec<(String, u8)> {
+        let mut rankings = vec![(survivor.to_string(), 1)];
+        for name in elimination_order.iter().rev() {
+            if name != survivor {
+                rankings.push((name.clone(), rankings.len() as u8 + 1));
+            }
+        }
+        rankings
+    }
+
+    /// Split the buy-in pool, less rake, by finishing rank. Players sharing a
+    /// rank chop the payouts of every position they cover. Rounding leftovers
+    /// go to the better finisher.
+    pub fn compute_tournament_prizes(
+        config: &TournamentConfig,
+        pool: u64,
+        final_rankings: &[(String, u8)],
+    ) -> Vec<(String, u64)> {
+        let prize_pool = pool - pool * config.rake_percentage as u64 / 100;
+
+        let mut ranked: Vec<&(String, u8)> = final_rankings.iter().collect();
+        ranked.sort_by_key(|(_, rank)| *rank);
+
+        let mut prizes: Vec<(String, u64)> = Vec::new();
+        let mut start = 0;
+        while start < ranked.len() {
+            let rank = ranked[start].1;
+            let tied = ranked[start..]
+                .iter()
+                .take_while(|(_, r)| *r == rank)
+                .count();
+            let percentage: u64 = config
+                .payout_structure
+                .iter()
+                .filter(|payout| payout.rank >= rank && payout.rank < rank + tied as u8)
+                .map(|payout| payout.percentage as u64)
+                .sum();
+            let group_prize = prize_pool * percentage / 100;
+            let share = group_prize / tied as u64;
+            let mut odd_chips = group_prize % tied as u64;
+            for (name, _) in &ranked[start..start + tied] {
+                let mut prize = share;
+                if odd_chips > 0 {
+                    prize += 1;
+                    odd_chips -= 1;
+                }
+                prizes.push((name.clone(), prize));
+            }
+            start += tied;
+        }
+
+        let paid: u64 = prizes.iter().map(|(_, prize)| prize).sum();
+        let fully_allocated = config
+            .payout_structure
+            .iter()
+            .map(|payout| payout.percentage as u64)
+            .sum::<u64>()
+            == 100;
+        if let Some((_, prize)) = prizes.first_mut() {
+            if fully_allocated {
+                *prize += prize_pool - paid;
+            }
+        }
+        prizes.retain(|(_, prize)| *prize > 0);
+        prizes
+    }
+
+    pub fn current_variant(&self) -> GameVariant {
+        match self.mixed_schedule.get() {
+            Some(schedule) => schedule
+                .variants
+                .get(*self.current_variant_index.get() as usize)
+                .copied()
+                .unwrap_or_default(),
+            None => self.rules.get().variant,
+        }
+    }
+
+    /// Called at the start of each hand. Returns the new variant and the
+    /// hands until the following change when the rotation moves on.
+    pub fn rotate_mixed_game_variant(&mut self) -> Option<(GameVariant, u8)> {
+        let schedule = self.mixed_schedule.get().clone()?;
+        let hand_number = *self.hand_number.get();
+        let player_count = self.player_order.get().len();
+        let next_index = Self::next_variant_index(
+            &schedule,
+            *self.current_variant_index.get(),
+            hand_number,
+            player_count,
+        )?;
+        self.current_variant_index.set(next_index);
+        Some((
+            schedule.variants[next_index as usize],
+            Self::hands_until_variant_change(&schedule, hand_number, player_count),
+        ))
+    }
+
+    pub fn next_variant_index(
+        schedule: &MixedGameSchedule,
+        current_index: u8,
+        hand_number: u64,
+        player_count: usize,
+    ) -> Option<u8> {
+        let per_rotation = schedule.hands_per_change(player_count);
+        if schedule.variants.is_empty() || per_rotation == 0 {
+            return None;
+        }
+        if hand_number == 0 || hand_number % per_rotation != 0 {
+            return None;
+        }
+        Some(((current_index as usize + 1) % schedule.variants.len()) as u8)
+    }
+
+    /// Saturates at `u8::MAX` for long orbit-based rotations
+    pub fn hands_until_variant_change(
+        schedule: &MixedGameSchedule,
+        hand_number: u64,
+        player_count: usize,
+    ) -> u8 {
+        let per_rotation = schedule.hands_per_change(player_count);
+        if per_rotation == 0 {
+            return 0;
+        }
+        (per_rotation - hand_number % per_rotation).min(u8::MAX as u64) as u8
+    }
+
+    /// Table creator only, between hands: start a mixed game rotation from
+    /// its first variant, or pass `None` to go back to the rules' variant
+    pub fn set_mixed_schedule(
+        &mut self,
+        caller: &str,
+        schedule: Option<MixedGameSchedule>,
+    ) -> Result<(), PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "change the game rotation")?;
+        if Self::is_hand_in_progress(self.phase.get()) {
+            return Err(PokerError::InvalidOperation(
+                "The rotation can only change between hands".to_string(),
+            ));
+        }
+        if let Some(schedule) = &schedule {
+            schedule.validate()?;
+        }
+        self.mixed_schedule.set(schedule);
+        self.current_variant_index.set(0);
+        Ok(())
+    }
+
+    /// Deal the variant's starting cards one at a time around the table
+    pub fn deal_hole_cards(
+        deck: &mut Vec<Card>,
+        player_count: usize,
+        variant: GameVariant,
+    ) -> Vec<Vec<Card>> {
+        let mut hands = vec![Vec::new(); player_count];
+        for _ in 0..variant.starting_cards() {
+            for hand in hands.iter_mut() {
+                if let Some(card) = deck.pop() {
+                    hand.push(card);
+                }
+            }
+        }
+        hands
+    }
+
+    /// Deal `count` cards to the board from the top of the deck, burning one
+    /// first when `burn` is set. Returns the cards dealt.
+    pub fn deal_community_cards(&mut self, count: usize, burn: bool) -> Vec<Card> {
+        let mut deck = self.deck.get().clone();
+        let mut burned = self.burn_cards.get().clone();
+        let cards = Self::burn_and_deal(&mut deck, &mut burned, count, burn);
+        self.community_cards.get_mut().extend_from_slice(&cards);
+        self.deck.set(deck);
+        self.burn_cards.set(burned);
+        // Only aggression on the final street decides who shows first
+        self.last_aggressor.set(None);
+        cards
+    }
+
+    /// The top of the deck is its last card, as in `deal_hole_cards`
+    pub fn burn_and_deal(
+        deck: &mut Vec<Card>,
+        burn_cards: &mut Vec<Card>,
+        count: usize,
+        burn: bool,
+    ) -> Vec<Card> {
+        if burn {
+            burn_cards.extend(deck.pop());
+        }
+        let mut cards = Vec::with_capacity(count);
+        for _ in 0..count {
+            cards.extend(deck.pop());
+        }
+        cards
+    }
+
+    /// Burned cards stay hidden until the hand is over
+    pub fn reveal_burn_cards(&self) -> Result<Vec<Card>, PokerError> {
+        let phase = self.phase.get();
+        if Self::is_hand_in_progress(phase) && *phase != GamePhase::Showdown {
+            return Err(PokerError::InvalidPhase {
+                expected: GamePhase::Showdown,
+                actual: phase.clone(),
+            });
+        }
+        Ok(self.burn_cards.get().clone())
+    }
+
+    pub fn can_go_all_in(player: &PokerPlayer) -> bool {
+        !player.is_all_in && player.chips > 0
+    }
+
+    pub fn all_in_count(players: &[PokerPlayer]) -> usize {
+        players.iter().filter(|p| p.is_all_in).count()
+    }
+
+    pub async fn process_all_in(&mut self, player_name: &str) -> Result<bool, PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        let current_bet = *self.current_bet.get();
+        let (amount, reopens) = Self::apply_capped_all_in(
+            &mut player,
+            current_bet,
+            self.min_raise(),
+            self.rules.get().wager_cap,
+        )?;
+
+        self.pot.set(*self.pot.get() + amount);
+        self.record_bet_level(current_bet, player.current_bet);
+        self.current_bet.set(current_bet.max(player.current_bet));
+        self.reopened_by_all_in.set(reopens);
+        self.save_players(&[player]);
+        invariants::check_chip_conservation(self).await?;
+        Ok(reopens)
+    }
+
+    /// Push the player's whole stack in. An all-in is always allowed, but only
+    /// a full raise reopens the betting to players who have already acted; a
+    /// short all-in is a partial call. Returns (chips moved, reopens betting).
+    pub fn apply_all_in(
+        player: &mut PokerPlayer,
+        current_bet: u64,
+        min_raise: u64,
+    ) -> Result<(u64, bool), PokerError> {
+        Self::apply_capped_all_in(player, current_bet, min_raise, None)
+    }
+
+    /// In a cap game all-in means up to the cap; the rest of the stack is
+    /// kept back for later hands
+    pub fn apply_capped_all_in(
+        player: &mut PokerPlayer,
+        current_bet: u64,
+        min_raise: u64,
+        wager_cap: Option<u64>,
+    ) -> Result<(u64, bool), PokerError> {
+        if !Self::can_go_all_in(player) {
+            return Err(PokerError::InvalidAction {
+                action: PlayerAction::AllIn,
+                reason: "Player is already all-in".to_string(),
+            });
+        }
+
+        let amount = Self::move_chips_to_pot(player, Self::wager_left(player, wager_cap))?;
+        player.current_bet += amount;
+        player.total_bet_this_hand += amount;
+        player.is_all_in = true;
+        player.status = PlayerStatus::AllIn;
+        player.last_action = Some(PlayerAction::AllIn);
+
+        let reopens = player.current_bet >= current_bet + min_raise;
+        Ok((amount, reopens))
+    }
+
+    /// Called at hand completion for every pot winner
+    pub async fn track_biggest_pot(&mut self, winner: &str, winner_prize: u64) -> u64 {
+        let key = winner.to_string();
+        let current = self.biggest_pot_for(winner).await;
+        let biggest = Self::updated_biggest_pot(current, winner_prize);
+        if biggest != current {
+            self.biggest_pot_won
+                .insert(&key, biggest)
+                .expect("Failed to save biggest pot");
+        }
+        biggest
+    }
+
+    /// The recorded value `SubmitToArena` relays as `biggest_pot`
+    pub async fn biggest_pot_for(&self, player_name: &str) -> u64 {
+        self.biggest_pot_won
+            .get(&player_name.to_string())
+            .await
+            .expect("Failed to load biggest pot")
+            .unwrap_or(0)
+    }
+
+    pub fn updated_biggest_pot(current_biggest_pot: u64, winner_prize: u64) -> u64 {
+        current_biggest_pot.max(winner_prize)
+    }
+
+    /// Move chips held by folded or sitting-out players who have left the
+    /// table into the pot. Returns the amount recovered.
+    pub async fn recover_orphaned_chips(&mut self) -> u64 {
+        let mut departed = Vec::new();
+        for name in self
+            .players
+            .indices()
+            .await
+            .expect("Failed to load players")
+        {
+            if self.player_order.get().contains(&name) {
+                continue;
+            }
+            if let Some(player) = self
+                .players
+                .get(&name)
+                .await
+                .expect("Failed to load player")
+            {
+                departed.push(player);
+            }
+        }
+
+        let recovered = Self::collect_orphaned_chips(&mut departed);
+        if recovered > 0 {
+            self.pot.set(*self.pot.get() + recovered);
+            self.save_players(&departed);
+        }
+        recovered
+    }
+
+    /// Table creator only: recover orphaned chips and hand them to the
+    /// remaining active players in proportion to their stacks
+    pub async fn trigger_orphan_recovery(
+        &mut self,
+        caller: &str,
+    ) -> Result<(u64, Vec<(String, u64)>), PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "recover orphaned chips")?;
+        let amount = self.recover_orphaned_chips().await;
+        if amount == 0 {
+            return Ok((0, Vec::new()));
+        }
+
+        let mut players = self.seated_players().await;
+        players.retain(|p| p.status != PlayerStatus::SittingOut && p.chips > 0);
+        let stacks: Vec<(String, u64)> =
+            players.iter().map(|p| (p.name.clone(), p.chips)).collect();
+        let distribution = Self::distribute_proportionally(amount, &stacks);
+        if distribution.is_empty() {
+            return Ok((amount, distribution));
+        }
+
+        for (name, share) in &distribution {
+            if let Some(player) = players.iter_mut().find(|p| &p.name == name) {
+                player.chips += share;
+            }
+        }
+        self.pot.set(*self.pot.get() - amount);
+        self.save_players(&players);
+        invariants::check_chip_conservation(self).await?;
+        Ok((amount, distribution))
+    }
+
+    /// Zero the chips of departed players who are folded or sitting out
+    pub fn collect_orphaned_chips(departed: &mut [PokerPlayer]) -> u64 {
+        departed
+            .iter_mut()
+            .filter(|p| matches!(p.status, PlayerStatus::Folded | PlayerStatus::SittingOut))
+            .map(Self::move_all_chips_to_pot)
+            .sum()
+    }
+
+    /// Split `amount` by stack size; rounding leftovers go one chip at a time
+    /// from the first seat
+    pub fn distribute_proportionally(amount: u64, stacks: &[(String, u64)]) -> Vec<(String, u64)> {
+        let total: u64 = stacks.iter().map(|(_, chips)| chips).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let mut shares: Vec<(String, u64)> = stacks
+            .iter()
+            .map(|(name, chips)| {
+                let share = (amount as u128 * *chips as u128 / total as u128) as u64;
+                (name.clone(), share)
+            })
+            .collect();
+        let mut leftover = amount - shares.iter().map(|(_, share)| share).sum::<u64>();
+        for (_, share) in shares.iter_mut() {
+            if leftover == 0 {
+                break;
+            }
+            *share += 1;
+            leftover -= 1;
+        }
+        shares
+    }
+
+    pub async fn mark_player_disconnected(
+        &mut self,
+        player_name: &str,
+        timestamp_ms: u64,
+    ) -> Result<(), PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        player.disconnected_at_ms.get_or_insert(timestamp_ms);
+        self.save_players(&[player]);
+        Ok(())
+    }
+
+    /// Returns true if the player was reconnecting after a disconnect
+    pub async fn sit_in(
+        &mut self,
+        player_name: &str,
+        wait_for_big_blind: bool,
+    ) -> Result<bool, PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        let reconnected = Self::reconnect(&mut player);
+        let owes_blinds = player.missed_small_blind || player.missed_big_blind;
+        if player.status == PlayerStatus::SittingOut && owes_blinds && wait_for_big_blind {
+            player.waiting_for_big_blind = true;
+            Self::register_action(&mut player);
+        } else if player.status == PlayerStatus::SittingOut {
+            Self::post_missed_blinds(&mut player, *self.small_blind.get(), *self.big_blind.get());
+            player.status = PlayerStatus::Active;
+            player.big_blinds_missed = 0;
+            Self::register_action(&mut player);
+        }
+        self.save_players(&[player]);
+        Ok(reconnected)
+    }
+
+    /// Act for a disconnected player whose turn it is once the grace period
+    /// has run out. Returns the action taken, if any.
+    pub async fn apply_disconnect_timeout(
+        &mut self,
+        player_name: &str,
+        current_time_ms: u64,
+    ) -> Result<Option<PlayerAction>, PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        let to_call = self.current_bet.get().saturating_sub(player.current_bet);
+        let action = Self::disconnect_auto_action(
+            &mut player,
+            current_time_ms,
+            *self.disconnect_grace_period_ms.get(),
+            to_call,
+        );
+        self.save_players(&[player]);
+        Ok(action)
+    }
+
+    /// Check when it is free, otherwise fold, after the grace period
+    pub fn disconnect_auto_action(
+        player: &mut PokerPlayer,
+        current_time_ms: u64,
+        grace_period_ms: u64,
+        to_call: u64,
+    ) -> Option<PlayerAction> {
+        let disconnected_at = player.disconnected_at_ms?;
+        if current_time_ms <= disconnected_at + grace_period_ms {
+            return None;
+        }
+
+        let action = if to_call == 0 {
+            PlayerAction::Check
+        } else {
+            player.has_folded = true;
+            player.status = PlayerStatus::Folded;
+            PlayerAction::Fold
+        };
+        player.last_action = Some(action.clone());
+        Some(action)
+    }
+
+    pub fn reconnect(player: &mut PokerPlayer) -> bool {
+        player.disconnected_at_ms.take().is_some()
+    }
+
+    /// First step of `StartHand`, before the blinds: move every pre-deal
+    /// contribution, including last hand's rabbit hunt fees, into the pot
+    /// and record it as the initial pot
+    pub fn collect_dead_money(&mut self, players: &mut [PokerPlayer]) -> u64 {
+        let ante = self.rules.get().ante;
+        let straddle = players.iter().map(|p| p.straddle_posted).max().unwrap_or(0);
+        let collected = if self.rules.get().big_blind_ante {
+            let seat = match self.big_blind_player.get() {
+                Some(name) => players.iter().position(|player| &player.name == name),
+                None => Some(Self::big_blind_seat(self.dealer_index(), players.len())),
+            };
+            let big_blind_ante = seat
+                .and_then(|seat| players.get_mut(seat))
+                .map_or(0, |player| {
+                    Self::collect_big_blind_ante(player, ante, *self.big_blind.get())
+                });
+            Self::compute_dead_money(players, 0) + big_blind_ante
+        } else {
+            Self::compute_dead_money(players, ante)
+        };
+        let collected = collected + std::mem::take(self.rabbit_hunt_fees.get_mut());
+        self.straddle_amount.set(straddle);
+        self.current_bet
+            .set((*self.current_bet.get()).max(straddle));
+        self.pot.set(*self.pot.get() + collected);
+        self.initial_pot.set(collected);
+        collected
+    }
+
+    /// Deduct antes, dead blinds and straddles from each dealt-in player,
+    /// capped at their stack. Only straddles and live blinds owed count as
+    /// bets.
+    pub fn compute_dead_money(players: &mut [PokerPlayer], ante: u64) -> u64 {
+        let mut collected = 0;
+        for player in players
+            .iter_mut()
+            .filter(|p| p.status != PlayerStatus::SittingOut && p.chips > 0)
+        {
+            let dead = (ante + std::mem::take(&mut player.missed_blind_due)).min(player.chips);
+            Self::move_chips_to_pot(player, dead).expect("Dead money is capped at the stack");
+
+            let live = (std::mem::take(&mut player.straddle_posted)
+                + std::mem::take(&mut player.live_blind_due))
+            .min(player.chips);
+            Self::move_chips_to_pot(player, live).expect("Live money is capped at the stack");
+            player.current_bet += live;
+
+            player.total_bet_this_hand += dead + live;
+            if player.chips == 0 {
+                player.is_all_in = true;
+                player.status = PlayerStatus::AllIn;
+            }
+            collected += dead + live;
+        }
+        collected
+    }
+
+    /// Table creator only: check the current hand's cards and, if the deck is
+    /// corrupted, void the hand and refund every bet to its contributor.
+    /// Returns the integrity report and the refunds paid.
+    pub async fn void_hand_due_to_corruption(
+        &mut self,
+        caller: &str,
+        hand_number: u64,
+    ) -> Result<(DeckIntegrityResult, Vec<(String, u64)>), PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "void a hand")?;
+        if hand_number != *self.hand_number.get() {
+            return Err(PokerError::InvalidOperation(format!(
+                "Hand {} is not in progress",
+                hand_number
+            )));
+        }
+
+        let players = self.seated_players().await;
+        let mut hole_cards: Vec<Vec<Card>> = players
+            .iter()
+            .map(|p| {
+                let mut cards = p.hole_cards.clone().unwrap_or_default();
+                cards.extend_from_slice(&p.up_cards);
+                cards
+            })
+            .collect();
+        // Burned and discarded cards are out of the deck but still accounted for
+        hole_cards.push(self.burn_cards.get().clone());
+        hole_cards.push(self.discard_pile.get().clone());
+        let result = Self::validate_deck_against(
+            &Self::create_deck_for_variant(self.current_variant()),
+            self.deck.get(),
+            self.community_cards.get(),
+            &hole_cards,
+        );
+        if result.valid {
+            return Ok((result, Vec::new()));
+        }
+
+        let refunds = self.void_current_hand(players).await?;
+        Ok((result, refunds))
+    }
+
+    /// Refund every bet of the current hand and go back to waiting for players
+    async fn void_current_hand(
+        &mut self,
+        mut players: Vec<PokerPlayer>,
+    ) -> Result<Vec<(String, u64)>, PokerError> {
+        let refunds = Self::refund_hand_bets(&mut players);
+        let refunded: u64 = refunds.iter().map(|(_, amount)| amount).sum();
+        self.save_players(&players);
+        self.pot.set(self.pot.get().saturating_sub(refunded));
+        self.current_bet.set(0);
+        self.initial_pot.set(0);
+        self.community_cards.set(Vec::new());
+        self.deck.set(Vec::new());
+        self.burn_cards.set(Vec::new());
+        self.discard_pile.set(Vec::new());
+        self.draw_rounds_completed.set(0);
+        self.raises_this_street.set(0);
+        self.straddle_amount.set(0);
+        self.last_aggressor.set(None);
+        self.reset_raise_tracking();
+        self.shown_at_showdown.set(Vec::new());
+        self.shown_voluntarily.set(Vec::new());
+        self.side_pots.set(Vec::new());
+        self.release_insurance();
+        // Checked while the hand is still in progress
+        invariants::check_chip_conservation(self).await?;
+        self.phase.set(GamePhase::WaitingForPlayers);
+        Ok(refunds)
+    }
+
+    /// Change phase and remember when it happened, for stale game detection
+    pub fn set_phase(&mut self, phase: GamePhase, timestamp_ms: u64) {
+        self.phase.set(phase);
+        self.last_state_change_ms.set(timestamp_ms);
+    }
+
+    pub fn is_game_stale(&self, current_time_ms: u64, stale_threshold_ms: u64) -> bool {
+        Self::is_stale(
+            self.phase.get(),
+            *self.last_state_change_ms.get(),
+            current_time_ms,
+            stale_threshold_ms,
+        )
+    }
+
+    /// Only a hand in progress can be stuck; waiting tables are never stale
+    pub fn is_stale(
+        phase: &GamePhase,
+        last_state_change_ms: u64,
+        current_time_ms: u64,
+        stale_threshold_ms: u64,
+    ) -> bool {
+        Self::is_hand_in_progress(phase)
+            && current_time_ms.saturating_sub(last_state_change_ms) > stale_threshold_ms
+    }
+
+    /// Table creator only: void a hand that has not changed phase within the
+    /// stale threshold and refund all bets. Returns the hand number and refunds.
+    pub async fn reset_stale_game(
+        &mut self,
+        caller: &str,
+        current_time_ms: u64,
+    ) -> Result<(u64, Vec<(String, u64)>), PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "reset a stale game")?;
+        if !self.is_game_stale(current_time_ms, *self.stale_threshold_ms.get()) {
+            return Err(PokerError::GameNotStale);
+        }
+
+        let players = self.seated_players().await;
+        let refunds = self.void_current_hand(players).await?;
+        self.last_state_change_ms.set(current_time_ms);
+        Ok((*self.hand_number.get(), refunds))
+    }
+
+    /// Every card of the 52-card deck must appear exactly once across the
+    /// undealt deck, the board and all hole cards
+    pub fn validate_deck_integrity(
+        deck: &[Card],
+        community_cards: &[Card],
+        player_hole_cards: &[Vec<Card>],
+    ) -> DeckIntegrityResult {
+        Self::validate_deck_against(
+            &Self::create_deck(),
+            deck,
+            community_cards,
+            player_hole_cards,
+        )
+    }
+
+    /// As `validate_deck_integrity`, against the variant's own `full_deck`
+    pub fn validate_deck_against(
+        full_deck: &[Card],
+        deck: &[Card],
+        community_cards: &[Card],
+        player_hole_cards: &[Vec<Card>],
+    ) -> DeckIntegrityResult {
+        let cards: Vec<Card> = deck
+            .iter()
+            .chain(community_cards)
+            .chain(player_hole_cards.iter().flatten())
+            .copied()
+            .collect();
+
+        let mut errors = Vec::new();
+        if cards.len() != full_deck.len() {
+            errors.push(DeckError::WrongDeckSize(cards.len()));
+        }
+        let mut seen = std::collections::HashSet::new();
+        for card in &cards {
+            if !seen.insert(*card) {
+                errors.push(DeckError::DuplicateCard(*card));
+            }
+        }
+        for card in full_deck {
+            if !seen.contains(card) {
+                errors.push(DeckError::MissingCard(*card));
+            }
+        }
+
+        DeckIntegrityResult {
+            valid: errors.is_empty(),
+            errors,
+        }
+    }
+
+    /// Give back everything each player put in this hand and reset them for
+    /// the next deal. Returns the amount refunded to each contributor.
+    pub fn refund_hand_bets(players: &mut [PokerPlayer]) -> Vec<(String, u64)> {
+        let mut refunds = Vec::new();
+        for player in players.iter_mut() {
+            let refund = std::mem::take(&mut player.total_bet_this_hand);
+            player.chips += refund;
+            player.current_bet = 0;
+            player.has_folded = false;
+            player.is_all_in = false;
+            player.hole_cards = None;
+            player.hole_cards_commitment = None;
+            if player.status != PlayerStatus::SittingOut {
+                player.status = PlayerStatus::Active;
+            }
+            if refund > 0 {
+                refunds.push((player.name.clone(), refund));
+            }
+        }
+        refunds
+    }
+
+    /// Collect everything stored about `player_name` and keep it for the
+    /// service to retrieve. Players can only export their own data.
+    pub async fn export_player_data(
+        &mut self,
+        caller: &str,
+        player_name: &str,
+    ) -> Result<PlayerDataExport, PokerError> {
+        Self::check_export_access(caller, player_name)?;
+        let player = self.load_player(player_name).await?;
+        let leaderboard = self
+            .leaderboard
+            .get(&player_name.to_string())
+            .await
+            .expect("Failed to load leaderboard entry")
+            .unwrap_or_default();
+        let mut hands_played = Vec::new();
+        for flag in self.flagged_hands.get() {
+            let mut events = self
+                .past_hands
+                .get(&flag.hand_number)
+                .await
+                .expect("Failed to load hand history")
+                .unwrap_or_default();
+            if flag.hand_number == *self.hand_number.get() {
+                events.extend(self.hand_history.get().iter().cloned());
+            }
+            if events.iter().any(|event| event.involves(player_name)) {
+                hands_played.push(flag.hand_number);
+            }
+        }
+        let export = Self::build_player_export(
+            player,
+            leaderboard,
+            self.chip_transfer_log.get(),
+            self.chat_log.get(),
+            self.flagged_hands.get(),
+            &hands_played,
+        );
+        self.pending_exports
+            .insert(&player_name.to_string(), export.clone())
+            .expect("Failed to save player export");
+        Ok(export)
+    }
+
+    pub fn check_export_access(caller: &str, player_name: &str) -> Result<(), PokerError> {
+        if caller != player_name {
+            return Err(PokerError::Unauthorized(format!(
+                "{} cannot export data for {}",
+                caller, player_name
+            )));
+        }
+        Ok(())
+    }
+
+    /// `hands_played` lists hands the player took part in; flagged ones
+    /// among them are exported with the hands the player flagged
+    pub fn build_player_export(
+        player: PokerPlayer,
+        leaderboard: LeaderboardData,
+        transfers: &[ChipTransfer],
+        chat_log: &[ChatEntry],
+        flagged_hands: &[FlaggedHand],
+        hands_played: &[u64],
+    ) -> PlayerDataExport {
+        let transfers = transfers
+            .iter()
+            .filter(|transfer| transfer.from == player.name || transfer.to == player.name)
+            .cloned()
+            .collect();
+        let chat_messages = chat_log
+            .iter()
+            .filter(|entry| entry.sender == player.name)
+            .cloned()
+            .collect();
+        let mut flagged_hand_numbers: Vec<u64> = flagged_hands
+            .iter()
+            .filter(|flag| {
+                flag.flagged_by == player.name || hands_played.contains(&flag.hand_number)
+            })
+            .map(|flag| flag.hand_number)
+            .collect();
+        // A hand can be flagged more than once
+        flagged_hand_numbers.sort_unstable();
+        flagged_hand_numbers.dedup();
+        PlayerDataExport {
+            player,
+            leaderboard,
+            transfers,
+            chat_messages,
+            flagged_hand_numbers,
+        }
+    }
+
+    /// Guard for `StartHand`: a finished or exhausted session deals no more hands
+    pub fn check_session_open(&self) -> Result<(), PokerError> {
+        if *self.session_complete.get()
+            || Self::session_limit_reached(
+                *self.hand_number.get(),
+                *self.max_hands_per_session.get(),
+            )
+        {
+            return Err(PokerError::SessionComplete);
+        }
+        Ok(())
+    }
+
+    /// Call when a hand finishes. Returns the final standings if that hand
+    /// was the last one of the session.
+    pub async fn check_session_limit(&mut self) -> Option<Vec<(String, u64)>> {
+        if *self.session_complete.get()
+            || !Self::session_limit_reached(
+                *self.hand_number.get(),
+                *self.max_hands_per_session.get(),
+            )
+        {
+            return None;
+        }
+        Some(self.complete_session().await)
+    }
+
+    /// Table creator only: end the session early with the same payout
+    pub async fn finalize_session(
+        &mut self,
+        caller: &str,
+    ) -> Result<Vec<(String, u64)>, PokerError> {
+        Self::require_table_creator(self.table_creator.get(), caller, "finalize the session")?;
+        if *self.session_complete.get() {
+            return Err(PokerError::SessionComplete);
+        }
+        Ok(self.complete_session().await)
+    }
+
+    async fn complete_session(&mut self) -> Vec<(String, u64)> {
+        let stacks: Vec<(String, u64)> = self
+            .seated_players()
+            .await
+            .into_iter()
+            .map(|player| (player.name, player.chips))
+            .collect();
+        self.session_complete.set(true);
+        self.phase.set(GamePhase::HandComplete);
+        Self::session_standings(
+            &stacks,
+            self.tournament_config.get().as_ref(),
+            *self.prize_pool.get(),
+        )
+    }
+
+    pub fn session_limit_reached(hand_number: u64, max_hands_per_session: Option<u32>) -> bool {
+        max_hands_per_session.is_some_and(|max| hand_number >= max as u64)
+    }
+
+    /// Remaining chip balances, biggest first. With a payout structure the
+    /// stacks decide the finishing order and the amounts are the prizes,
+    /// paid out of `prize_pool`.
+    pub fn session_standings(
+        stacks: &[(String, u64)],
+        tournament: Option<&TournamentConfig>,
+        prize_pool: u64,
+    ) -> Vec<(String, u64)> {
+        let mut standings = stacks.to_vec();
+        standings.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+        let Some(config) = tournament else {
+            return standings;
+        };
+
+        let rankings: Vec<(String, u8)> = standings
+            .iter()
+            .map(|(name, chips)| {
+                let ahead = standings.iter().filter(|(_, other)| other > chips).count();
+                (name.clone(), ahead as u8 + 1)
+            })
+            .collect();
+        Self::compute_tournament_prizes(config, prize_pool, &rankings)
+    }
+
+    /// When everyone else has folded (or nobody can call any more), give the
+    /// biggest bettor back whatever nobody matched. Returns who got what.
+    pub async fn return_uncalled_bet(&mut self) -> Result<Option<(String, u64)>, PokerError> {
+        let mut players = self.seated_players().await;
+        let current_bet = *self.current_bet.get();
+        let Some(returned) = Self::apply_uncalled_bet_return(&mut players, current_bet) else {
+            return Ok(None);
+        };
+        self.pot.set(self.pot.get().saturating_sub(returned.1));
+        self.current_bet.set(current_bet - returned.1);
+        self.save_players(&players);
+        invariants::check_chip_conservation(self).await?;
+        Ok(Some(returned))
+    }
+
+    /// End of a betting round, before the next street is dealt or the hand
+    /// goes to showdown: refund the uncalled bet and start the next street
+    /// with no bets, raises or actions, whatever the variant. The last
+    /// aggressor is kept for the showdown until the next street is dealt.
+    /// Returns the refund for `UncalledBetReturned`.
+    pub async fn settle_betting_round(&mut self) -> Result<Option<(String, u64)>, PokerError> {
+        let mut players = self.seated_players().await;
+        let returned = Self::close_betting_round(&mut players, *self.current_bet.get());
+        if let Some((_, amount)) = &returned {
+            self.pot.set(self.pot.get().saturating_sub(*amount));
+        }
+        self.current_bet.set(0);
+        self.raises_this_street.set(0);
+        self.straddle_amount.set(0);
+        self.reset_raise_tracking();
+        self.save_players(&players);
+        invariants::check_chip_conservation(self).await?;
+        Ok(returned)
+    }
+
+    pub fn close_betting_round(
+        players: &mut [PokerPlayer],
+        current_bet: u64,
+    ) -> Option<(String, u64)> {
+        let returned = Self::apply_uncalled_bet_return(players, current_bet);
+        for player in players.iter_mut() {
+            player.current_bet = 0;
+        }
+        returned
+    }
+
+    /// Refund the unmatched part of the largest bet this street, folded
+    /// players' bets included in what was matched
+    pub fn apply_uncalled_bet_return(
+        players: &mut [PokerPlayer],
+        current_bet: u64,
+    ) -> Option<(String, u64)> {
+        let raiser = (0..players.len()).max_by_key(|&i| players[i].current_bet)?;
+        let second_highest_bet = players
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != raiser)
+            .map(|(_, p)| p.current_bet)
+            .max()
+            .unwrap_or(0);
+
+        let player = &mut players[raiser];
+        let amount = Self::compute_uncalled_bet_return(player, current_bet, second_highest_bet);
+        if amount == 0 {
+            return None;
+        }
+        player.chips += amount;
+        player.current_bet -= amount;
+        player.total_bet_this_hand -= amount;
+        if player.is_all_in {
+            player.is_all_in = false;
+            player.status = PlayerStatus::Active;
+        }
+        Some((player.name.clone(), amount))
+    }
+
+    pub fn compute_uncalled_bet_return(
+        raiser: &PokerPlayer,
+        current_bet: u64,
+        second_highest_bet: u64,
+    ) -> u64 {
+        raiser
+            .current_bet
+            .min(current_bet)
+            .saturating_sub(second_highest_bet)
+    }
+
+    /// Record `player_name`'s request to see the rest of the board. Once
+    /// every player dealt into the hand has asked, returns the cards that
+    /// would have come and logs them. The hand's result is never affected;
+    /// each player pays `rabbit_hunt_fee` towards the next pot.
+    pub async fn request_rabbit_hunt(
+        &mut self,
+        player_name: &str,
+    ) -> Result<Option<Vec<Card>>, PokerError> {
+        Self::check_rabbit_hunt_allowed(self.rules.get().rabbit_hunt_enabled, self.phase.get())?;
+        if self.community_cards.get().len() >= 5 {
+            return Err(PokerError::InvalidOperation(
+                "The hand reached the river; there is nothing to hunt".to_string(),
+            ));
+        }
+        let fee = self.rules.get().rabbit_hunt_fee;
+        let mut players = self.seated_players().await;
+        let player = players
+            .iter_mut()
+            .find(|p| p.name == player_name && p.hole_cards.is_some())
+            .ok_or_else(|| PokerError::PlayerNotFound(player_name.to_string()))?;
+        Self::require_chips(player, fee)?;
+        player.wants_rabbit_hunt = true;
+
+        if !Self::rabbit_hunt_consented(&players) {
+            self.save_players(&players);
+            return Ok(None);
+        }
+        for player in players.iter_mut() {
+            player.wants_rabbit_hunt = false;
+        }
+        let fees = Self::collect_rabbit_hunt_fees(&mut players, fee);
+        self.rabbit_hunt_fees
+            .set(*self.rabbit_hunt_fees.get() + fees);
+        self.save_players(&players);
+
+        let cards = Self::rabbit_hunt_cards(self.deck.get(), self.community_cards.get());
+        self.rabbit_hunt_log
+            .insert(self.hand_number.get(), cards.clone())
+            .expect("Failed to save rabbit hunt cards");
+        Ok(Some(cards))
+    }
+
+    pub fn check_rabbit_hunt_allowed(enabled: bool, phase: &GamePhase) -> Result<(), PokerError> {
+        if !enabled {
+            return Err(PokerError::NotEnabled(
+                "Rabbit hunting is disabled at this table".to_string(),
+            ));
+        }
+        if *phase != GamePhase::HandComplete {
+            return Err(PokerError::InvalidPhase {
+                expected: GamePhase::HandComplete,
+                actual: phase.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Take the fee from everyone dealt in, or what they have left if a
+    /// stack has dropped below it since asking. Returns the total.
+    pub fn collect_rabbit_hunt_fees(players: &mut [PokerPlayer], fee: u64) -> u64 {
+        players
+            .iter_mut()
+            .filter(|p| p.hole_cards.is_some())
+            .map(|player| {
+                Self::move_chips_to_pot(player, fee.min(player.chips))
+                    .expect("Fee is capped at the stack")
+            })
+            .sum()
+    }
+
+    /// Everyone dealt into the hand has to agree
+    pub fn rabbit_hunt_consented(players: &[PokerPlayer]) -> bool {
+        let mut dealt_in = players.iter().filter(|p| p.hole_cards.is_some()).peekable();
+        dealt_in.peek().is_some() && dealt_in.all(|p| p.wants_rabbit_hunt)
+    }
+
+    /// The rest of the board as it would have been dealt, burns included,
+    /// without touching the deck
+    pub fn rabbit_hunt_cards(deck: &[Card], community_cards: &[Card]) -> Vec<Card> {
+        let mut deck = deck.to_vec();
+        let mut burned = Vec::new();
+        let mut cards = Vec::new();
+        let mut board = community_cards.len();
+        while board < 5 {
+            let street = if board == 0 { 3 } else { 1 };
+            cards.extend(Self::burn_and_deal(&mut deck, &mut burned, street, true));
+            board += street;
+        }
+        cards
+    }
+
+    /// `SetAutoRebuy`: `deposit` is added to the player's reserve. Turning
+    /// auto-rebuy off returns the whole reserve, which the caller refunds.
+    pub async fn set_auto_rebuy(
+        &mut self,
+        player_name: &str,
+        threshold: Option<u64>,
+        top_up_to: Option<u64>,
+        deposit: u64,
+    ) -> Result<u64, PokerError> {
+        Self::validate_auto_rebuy(threshold, top_up_to, *self.buy_in_max.get())?;
+        let mut player = self.load_player(player_name).await?;
+        player.auto_rebuy_threshold = threshold;
+        player.auto_rebuy_to = top_up_to;
+        let reserve = player.auto_rebuy_reserve.checked_add(deposit);
+        player.auto_rebuy_reserve = reserve.ok_or_else(|| {
+            PokerError::InvalidOperation("Auto-rebuy reserve is too large".to_string())
+        })?;
+        let refund = if threshold.is_none() {
+            std::mem::take(&mut player.auto_rebuy_reserve)
+        } else {
+            0
+        };
+        self.save_players(&[player]);
+        Ok(refund)
+    }
+
+    pub fn validate_auto_rebuy(
+        threshold: Option<u64>,
+        top_up_to: Option<u64>,
+        buy_in_max: u64,
+    ) -> Result<(), PokerError> {
+        match (threshold, top_up_to) {
+            (None, None) => Ok(()),
+            (Some(threshold), Some(top_up_to)) => {
+                if top_up_to > buy_in_max {
+                    return Err(PokerError::InvalidConfig(format!(
+                        "Auto-rebuy cannot top up above the maximum buy-in of {}",
+                        buy_in_max
+                    )));
+                }
+                if threshold >= top_up_to {
+                    return Err(PokerError::InvalidConfig(
+                        "Auto-rebuy threshold must be below the top-up amount".to_string(),
+                    ));
+                }
+                Ok(())
+            }
+            _ => Err(PokerError::InvalidConfig(
+                "Auto-rebuy needs both a threshold and a top-up amount".to_string(),
+            )),
+        }
+    }
+
+    /// Call after each hand. Every top-up goes through `add_chips` and is
+    /// paid from the player's reserve; a player whose reserve cannot cover
+    /// it, or whose top-up `add_chips` refuses, is skipped. Returns the
+    /// players topped up and by how much.
+    pub async fn process_auto_rebuys(&mut self) -> Vec<(String, u64)> {
+        let mut rebuys = Vec::new();
+        for player in self.seated_players().await {
+            let Some(amount) = Self::auto_rebuy_due(&player) else {
+                continue;
+            };
+            if self.add_chips(&player.name, amount).await.is_err() {
+                continue;
+            }
+            let Ok(mut player) = self.load_player(&player.name).await else {
+                continue;
+            };
+            player.auto_rebuy_reserve -= amount;
+            rebuys.push((player.name.clone(), amount));
+            self.save_players(&[player]);
+        }
+        rebuys
+    }
+
+    /// The top-up owed to a player below their threshold, if their reserve
+    /// can pay for it
+    pub fn auto_rebuy_due(player: &PokerPlayer) -> Option<u64> {
+        let (Some(threshold), Some(top_up_to)) =
+            (player.auto_rebuy_threshold, player.auto_rebuy_to)
+        else {
+            return None;
+        };
+        if player.chips >= threshold {
+            return None;
+        }
+        let amount = top_up_to.saturating_sub(player.chips);
+        (amount > 0 && amount <= player.auto_rebuy_reserve).then_some(amount)
+    }
+
+    /// Called from the `PlayerAction` handler for every accepted action
+    pub async fn track_action_frequency(&mut self, player_name: &str, action: &PlayerAction) {
+        let mut frequency = self.action_frequency(player_name).await;
+        frequency.record(action);
+        self.action_frequencies
+            .insert(&player_name.to_string(), frequency)
+            .expect("Failed to save action frequency");
+    }
+
+    /// All zeroes for a player who has not acted yet
+    pub async fn action_frequency(&self, player_name: &str) -> ActionFrequency {
+        self.action_frequencies
+            .get(&player_name.to_string())
+            .await
+            .expect("Failed to load action frequency")
+            .unwrap_or_default()
+    }
+
+    /// Called for every accepted action, after `current_bet` has moved on
+    /// from `previous_bet`. Bets, raises and all-ins that put in more than
+    /// `previous_bet` make the player the last aggressor.
+    pub fn note_aggression(&mut self, player_name: &str, action: &PlayerAction, previous_bet: u64) {
+        let acted = self.acted_this_street.get_mut();
+        if !acted.iter().any(|name| name == player_name) {
+            acted.push(player_name.to_string());
+        }
+        let aggressive = match action {
+            PlayerAction::Bet(_) | PlayerAction::Raise(_) => true,
+            PlayerAction::AllIn => *self.current_bet.get() > previous_bet,
+            _ => false,
+        };
+        if aggressive {
+            self.last_aggressor.set(Some(player_name.to_string()));
+        }
+        let raised = match action {
+            PlayerAction::Raise(_) => true,
+            PlayerAction::AllIn => aggressive && previous_bet > 0,
+            _ => false,
+        };
+        if raised {
+            let raises = self.raises_this_street.get().saturating_add(1);
+            self.raises_this_street.set(raises);
+        }
+    }
+
+    /// Who has to show (or muck) next during `GamePhase::Showdown`
+    pub async fn next_showdown_player(&self) -> Option<String> {
+        if *self.phase.get() != GamePhase::Showdown {
+            return None;
+        }
+        let players = self.seated_players().await;
+        let first_to_act = Self::postflop_first_to_act(self.dealer_index(), players.len());
+        let in_showdown: Vec<String> = players
+            .iter()
+            .cycle()
+            .skip(first_to_act)
+            .take(players.len())
+            .filter(|p| !p.has_folded && p.status != PlayerStatus::SittingOut)
+            .map(|p| p.name.clone())
+            .collect();
+        let order = Self::showdown_order(
+            self.last_aggressor.get().as_deref(),
+            self.big_blind_player.get().as_deref(),
+            self.blind_seat_order.get(),
+            &in_showdown,
+        );
+        Self::next_to_show(&order, self.shown_at_showdown.get())
+    }
+
+    /// Enforce the reveal sequence; mucking is a fold and simply drops the
+    /// player from the order. Starts the next player's clock.
+    pub async fn record_showdown_reveal(
+        &mut self,
+        player_name: &str,
+        now_ms: u64,
+    ) -> Result<(), PokerError> {
+        if self.next_showdown_player().await.as_deref() != Some(player_name) {
+            return Err(PokerError::NotPlayersTurn(player_name.to_string()));
+        }
+        self.shown_at_showdown
+            .get_mut()
+            .push(player_name.to_string());
+        self.start_showdown_turn(now_ms);
+        Ok(())
+    }
+
+    /// Call when the hand reaches showdown and after every show or muck
+    pub fn start_showdown_turn(&mut self, now_ms: u64) {
+        self.showdown_turn_started_ms.set(now_ms);
+    }
+
+    /// `MuckHand`: the player folds out of the showdown in their turn. The
+    /// last player left wins without showing, so cannot muck.
+    pub async fn muck_at_showdown(
+        &mut self,
+        player_name: &str,
+        now_ms: u64,
+    ) -> Result<(), PokerError> {
+        if self.next_showdown_player().await.as_deref() != Some(player_name) {
+            return Err(PokerError::NotPlayersTurn(player_name.to_string()));
+        }
+        let players = self.seated_players().await;
+        let contenders = players
+            .iter()
+            .filter(|p| !p.has_folded && p.status != PlayerStatus::SittingOut)
+            .count();
+        if contenders <= 1 {
+            return Err(PokerError::InvalidOperation(
+                "The last player in the showdown cannot muck".to_string(),
+            ));
+        }
+        let mut player = self.load_player(player_name).await?;
+        player.has_folded = true;
+        player.status = PlayerStatus::Folded;
+        self.save_players(&[player]);
+        self.start_showdown_turn(now_ms);
+        Ok(())
+    }
+
+    /// A player who lets their showdown clock run out has their hand mucked.
+    /// Returns who was mucked.
+    pub async fn expire_showdown_turn(&mut self, now_ms: u64) -> Option<String> {
+        let next = self.next_showdown_player().await?;
+        if !Self::showdown_turn_expired(
+            *self.showdown_turn_started_ms.get(),
+            now_ms,
+            *self.showdown_reveal_timeout_ms.get(),
+        ) {
+            return None;
+        }
+        self.muck_at_showdown(&next, now_ms).await.ok()?;
+        Some(next)
+    }
+
+    pub fn showdown_turn_expired(turn_started_ms: u64, now_ms: u64, timeout_ms: u64) -> bool {
+        now_ms.saturating_sub(turn_started_ms) > timeout_ms
+    }
+
+    /// `players_in_showdown` runs clockwise from the first seat left of the
+    /// button. The last aggressor shows first, then the rest clockwise. With
+    /// no aggressor the big blind starts, or the first player still in after
+    /// the big blind in `blind_seat_order`.
+    pub fn showdown_order(
+        last_aggressor: Option<&str>,
+        big_blind: Option<&str>,
+        blind_seat_order: &[String],
+        players_in_showdown: &[String],
+    ) -> Vec<String> {
+        let position = |name: &str| players_in_showdown.iter().position(|p| p == name);
+        let after_big_blind = || {
+            let big_blind = blind_seat_order
+                .iter()
+                .position(|p| Some(p.as_str()) == big_blind)?;
+            blind_seat_order
+                .iter()
+                .cycle()
+                .skip(big_blind)
+                .take(blind_seat_order.len())
+                .find_map(|p| position(p))
+        };
+        let start = last_aggressor
+            .and_then(position)
+            .or_else(after_big_blind)
+            .unwrap_or(0);
+        let mut order = players_in_showdown.to_vec();
+        order.rotate_left(start);
+        order
+    }
+
+    pub fn next_to_show(order: &[String], shown: &[String]) -> Option<String> {
+        order.iter().find(|player| !shown.contains(player)).cloned()
+    }
+
+    /// Guard for `StartHand`
+    pub async fn check_enough_players(&self) -> Result<(), PokerError> {
+        let have = self.ready_player_count().await;
+        let need = *self.min_players_to_start.get();
+        if !Self::has_enough_players_to_start(have, need) {
+            return Err(PokerError::NotEnoughPlayers {
+                have,
+                need: need as usize,
+            });
+        }
+        Ok(())
+    }
+
+    /// Seated players who would be dealt in
+    pub async fn ready_player_count(&self) -> usize {
+        self.seated_players()
+            .await
+            .iter()
+            .filter(|p| p.status != PlayerStatus::SittingOut && p.chips > 0)
+            .count()
+    }
+
+    pub fn has_enough_players_to_start(active_players: usize, min_players: u8) -> bool {
+        active_players >= min_players as usize
+    }
+
+    /// The only way chips leave a stack for the pot: all of `amount` moves
+    /// or nothing does. Returns the amount moved.
+    pub fn move_chips_to_pot(player: &mut PokerPlayer, amount: u64) -> Result<u64, PokerError> {
+        Self::require_chips(player, amount)?;
+        player.chips -= amount;
+        Ok(amount)
+    }
+
+    pub fn move_all_chips_to_pot(player: &mut PokerPlayer) -> u64 {
+        std::mem::take(&mut player.chips)
+    }
+
+    /// Invariant check: the chips at the table, in stacks, pots, rake and
+    /// insurance, still add up to `initial_total_chips`. See `ChipLedger`.
+    pub async fn verify_chip_conservation(&self, initial_total_chips: u64) -> bool {
+        ChipLedger::of(self)
+            .await
+            .check(initial_total_chips)
+            .is_ok()
+    }
+
+    pub fn total_chips(players: &[PokerPlayer], pot: u64) -> u64 {
+        players.iter().map(|p| p.chips).sum::<u64>() + pot
+    }
+
+    /// Moves on to the next hand number, the key for `past_hands` and
+    /// `rabbit_hunt_log`
+    pub fn increment_hand_number(&mut self) -> Result<u64, PokerError> {
+        let next = Self::next_hand_number(*self.hand_number.get())?;
+        self.hand_number.set(next);
+        Ok(next)
+    }
+
+    pub fn next_hand_number(hand_number: u64) -> Result<u64, PokerError> {
+        hand_number
+            .checked_add(1)
+            .ok_or(PokerError::HandNumberOverflow)
+    }
+
+    /// Files the current hand's events under its hand number once it is over
+    pub fn archive_hand_history(&mut self) {
+        let events = std::mem::take(self.hand_history.get_mut());
+        if events.is_empty() {
+            return;
+        }
+        self.past_hands
+            .insert(self.hand_number.get(), events)
+            .expect("Failed to save hand history");
+    }
+
+    /// Hand numbers in a log should run without gaps or repeats
+    pub fn hand_number_sequence_is_contiguous(log: &[u64]) -> bool {
+        log.windows(2)
+            .all(|pair| pair[0].checked_add(1) == Some(pair[1]))
+    }
+
+    /// What the service may show: only the board dealt for the current phase
+    pub fn visible_community_cards(&self) -> &[Card] {
+        Self::community_cards_for_phase(self.community_cards.get(), self.phase.get())
+    }
+
+    pub fn community_cards_for_phase<'a>(
+        all_community_cards: &'a [Card],
+        phase: &GamePhase,
+    ) -> &'a [Card] {
+        let shown = match phase {
+            GamePhase::WaitingForPlayers
+            | GamePhase::PreFlop
+            | GamePhase::ThirdStreet
+            | GamePhase::FourthStreet
+            | GamePhase::FifthStreet
+            | GamePhase::SixthStreet
+            | GamePhase::SeventhStreet
+            | GamePhase::Draw => 0,
+            GamePhase::Flop => 3,
+            GamePhase::Turn => 4,
+            GamePhase::River | GamePhase::Showdown | GamePhase::HandComplete => 5,
+        };
+        &all_community_cards[..shown.min(all_community_cards.len())]
+    }
+
+    /// Street that follows `phase` in board games; the river is followed by
+    /// showdown
+    pub fn next_board_street(phase: &GamePhase) -> Option<GamePhase> {
+        match phase {
+            GamePhase::PreFlop => Some(GamePhase::Flop),
+            GamePhase::Flop => Some(GamePhase::Turn),
+            GamePhase::Turn => Some(GamePhase::River),
+            GamePhase::River => Some(GamePhase::Showdown),
+            _ => None,
+        }
+    }
+
+    /// Board cards dealt when `phase` moves to the next street
+    pub fn next_community_cards_count(phase: &GamePhase) -> usize {
+        match phase {
+            GamePhase::PreFlop => 3,
+            GamePhase::Flop | GamePhase::Turn => 1,
+            _ => 0,
+        }
+    }
+
+    /// Largest total bet `player_bet` may raise to under pot-limit: calling
+    /// first, then raising by the pot including that call
+    pub fn pot_limit_max_bet(pot: u64, current_bet: u64, player_bet: u64) -> u64 {
+        let to_call = current_bet.saturating_sub(player_bet);
+        current_bet + pot + to_call
+    }
+
+    /// Stud street that follows `phase`; the seventh is followed by showdown
+    pub fn next_stud_street(phase: &GamePhase) -> Option<GamePhase> {
+        match phase {
+            GamePhase::ThirdStreet => Some(GamePhase::FourthStreet),
+            GamePhase::FourthStreet => Some(GamePhase::FifthStreet),
+            GamePhase::FifthStreet => Some(GamePhase::SixthStreet),
+            GamePhase::SixthStreet => Some(GamePhase::SeventhStreet),
+            GamePhase::SeventhStreet => Some(GamePhase::Showdown),
+            _ => None,
+        }
+    }
+
+    /// Fourth to sixth street are dealt face up; seventh goes down like the
+    /// first two cards of third street
+    pub fn stud_card_is_face_up(phase: &GamePhase) -> bool {
+        matches!(
+            phase,
+            GamePhase::FourthStreet | GamePhase::FifthStreet | GamePhase::SixthStreet
+        )
+    }
+
+    /// Move a Stud hand on to its next street once the betting is settled:
+    /// deal the street from the deck and hand the action to the best showing
+    /// hand. Returns the new phase, `Showdown` after seventh street.
+    pub async fn deal_next_stud_street(&mut self) -> Option<GamePhase> {
+        let next = Self::next_stud_street(self.phase.get())?;
+        self.phase.set(next.clone());
+        // Only aggression on the final street decides who shows first
+        self.last_aggressor.set(None);
+        if next == GamePhase::Showdown {
+            return Some(next);
+        }
+        let mut deck = self.deck.get().clone();
+        let mut players = self.seated_players().await;
+        Self::deal_stud_street(&mut deck, &mut players, &next);
+        if let Some(first) = Self::stud_first_to_act_for_variant(self.current_variant(), &players) {
+            let seat = self.occupied_seats.get()[first];
+            self.current_player_index.set(seat);
+        }
+        self.save_players(&players);
+        self.deck.set(deck);
+        Some(next)
+    }
+
+    /// Deal one card to each player still in, onto `up_cards` or `hole_cards`
+    /// as the street requires
+    pub fn deal_stud_street(deck: &mut Vec<Card>, players: &mut [PokerPlayer], phase: &GamePhase) {
+        let face_up = Self::stud_card_is_face_up(phase);
+        for player in players.iter_mut().filter(|p| !p.has_folded) {
+            let Some(card) = deck.pop() else {
+                return;
+            };
+            if face_up {
+                player.up_cards.push(card);
+            } else {
+                player.hole_cards.get_or_insert_with(Vec::new).push(card);
+            }
+        }
+    }
+
+    /// Third street: the lowest door card pays the bring-in, with suit
+    /// breaking ties from clubs (lowest) to spades
+    pub fn stud_bring_in_player(players: &[PokerPlayer]) -> Option<usize> {
+        players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.has_folded)
+            .filter_map(|(i, p)| p.up_cards.first().map(|card| (i, card)))
+            .min_by_key(|(_, card)| (card.rank, Self::bring_in_suit_order(card.suit)))
+            .map(|(i, _)| i)
+    }
+
+    fn bring_in_suit_order(suit: Suit) -> u8 {
+        match suit {
+            Suit::Clubs => 0,
+            Suit::Diamonds => 1,
+            Suit::Hearts => 2,
+            Suit::Spades => 3,
+        }
+    }
+
+    /// From fourth street on, the best showing hand acts first; ties go to
+    /// the earliest seat
+    pub fn stud_first_to_act(players: &[PokerPlayer]) -> Option<usize> {
+        let mut best: Option<(usize, (u8, Vec<Rank>))> = None;
+        for (i, player) in players.iter().enumerate() {
+            if player.has_folded || player.up_cards.is_empty() {
+                continue;
+            }
+            let strength = Self::up_card_strength(&player.up_cards);
+            if best.as_ref().is_none_or(|(_, top)| strength > *top) {
+                best = Some((i, strength));
+            }
+        }
+        best.map(|(i, _)| i)
+    }
+
+    /// Ranks a partial board of up to four cards: quads, trips, two pair,
+    /// pair, then high card, with the grouped ranks as kickers
+    pub fn up_card_strength(up_cards: &[Card]) -> (u8, Vec<Rank>) {
+        let mut groups: Vec<(usize, Rank)> = Vec::new();
+        for card in up_cards {
+            match groups.iter_mut().find(|(_, rank)| *rank == card.rank) {
+                Some((count, _)) => *count += 1,
+                None => groups.push((1, card.rank)),
+            }
+        }
+        groups.sort_by(|a, b| b.cmp(a));
+        let category = match groups.first().map(|(count, _)| *count).unwrap_or(0) {
+            4 => 4,
+            3 => 3,
+            2 if groups.get(1).is_some_and(|(count, _)| *count == 2) => 2,
+            2 => 1,
+            _ => 0,
+        };
+        (category, groups.into_iter().map(|(_, rank)| rank).collect())
+    }
+
+    /// `DiscardCards`: swap the named cards for fresh ones from the deck.
+    /// Returns the replacements dealt.
+    pub async fn discard_cards(
+        &mut self,
+        player_name: &str,
+        cards: &[Card],
+    ) -> Result<Vec<Card>, PokerError> {
+        if *self.phase.get() != GamePhase::Draw {
+            return Err(PokerError::InvalidPhase {
+                expected: GamePhase::Draw,
+                actual: self.phase.get().clone(),
+            });
+        }
+        self.require_turn(player_name)?;
+        let mut player = self.load_player(player_name).await?;
+        let mut deck = self.deck.get().clone();
+        let replacements = Self::apply_draw(&mut player, &mut deck, cards)?;
+        self.deck.set(deck);
+        self.discard_pile.get_mut().extend_from_slice(cards);
+        self.save_players(&[player]);
+        Ok(replacements)
+    }
+
+    /// Each player draws once; discards must come from their own hand and
+    /// are replaced from the top of the deck
+    pub fn apply_draw(
+        player: &mut PokerPlayer,
+        deck: &mut Vec<Card>,
+        discards: &[Card],
+    ) -> Result<Vec<Card>, PokerError> {
+        if player.has_folded || player.has_drawn {
+            return Err(PokerError::AlreadyDone(format!(
+                "{} cannot draw again this hand",
+                player.name
+            )));
+        }
+        let hand = player
+            .hole_cards
+            .as_mut()
+            .ok_or_else(|| PokerError::PlayerNotFound(player.name.clone()))?;
+        let mut unique = discards.to_vec();
+        unique.sort_by_key(|card| (card.rank, card.suit as u8));
+        unique.dedup();
+        if unique.len() != discards.len() || discards.iter().any(|card| !hand.contains(card)) {
+            return Err(PokerError::InvalidOperation(
+                "Can only discard cards from your own hand".to_string(),
+            ));
+        }
+        if discards.len() > deck.len() {
+            return Err(PokerError::InvalidOperation(format!(
+                "Only {} cards left to draw",
+                deck.len()
+            )));
+        }
+
+        hand.retain(|card| !discards.contains(card));
+        let replacements: Vec<Card> = (0..discards.len()).filter_map(|_| deck.pop()).collect();
+        hand.extend_from_slice(&replacements);
+        player.has_drawn = true;
+        Ok(replacements)
+    }
+
+    /// The draw is over once every player still in has drawn or stood pat
+    pub fn draw_complete(players: &[PokerPlayer]) -> bool {
+        players
+            .iter()
+            .filter(|p| !p.has_folded && p.hole_cards.is_some())
+            .all(|p| p.has_drawn)
+    }
+
+    /// `DiscardHoleCard`: keep two of the three Pineapple hole cards
+    pub async fn discard_hole_card(
+        &mut self,
+        player_name: &str,
+        card: Card,
+    ) -> Result<(), PokerError> {
+        let variant = self.current_variant();
+        let Some(discard_phase) = variant.discard_phase() else {
+            return Err(PokerError::NotEnabled(format!(
+                "{:?} has no discard",
+                variant
+            )));
+        };
+        if *self.phase.get() != discard_phase {
+            return Err(PokerError::InvalidPhase {
+                expected: discard_phase,
+                actual: self.phase.get().clone(),
+            });
+        }
+        let mut player = self.load_player(player_name).await?;
+        Self::apply_hole_card_discard(&mut player, card)?;
+        self.discard_pile.get_mut().push(card);
+        self.save_players(&[player]);
+        Ok(())
+    }
+
+    pub fn apply_hole_card_discard(player: &mut PokerPlayer, card: Card) -> Result<(), PokerError> {
+        let hand = player
+            .hole_cards
+            .as_mut()
+            .filter(|_| !player.has_folded)
+            .ok_or_else(|| PokerError::PlayerNotFound(player.name.clone()))?;
+        if hand.len() <= 2 {
+            return Err(PokerError::AlreadyDone(format!(
+                "{} has already discarded",
+                player.name
+            )));
+        }
+        let Some(index) = hand.iter().position(|held| *held == card) else {
+            return Err(PokerError::InvalidOperation(
+                "Can only discard cards from your own hand".to_string(),
+            ));
+        };
+        hand.remove(index);
+        Ok(())
+    }
+
+    /// Guard for dealing the street after the discard: everyone still in
+    /// must be down to two hole cards
+    pub fn check_discards_complete(
+        variant: GameVariant,
+        phase: &GamePhase,
+        players: &[PokerPlayer],
+    ) -> Result<(), PokerError> {
+        if variant.discard_phase().as_ref() != Some(phase) {
+            return Ok(());
+        }
+        match players
+            .iter()
+            .filter(|p| !p.has_folded)
+            .find(|p| p.hole_cards.as_ref().is_some_and(|cards| cards.len() > 2))
+        {
+            Some(player) => Err(PokerError::InvalidOperation(format!(
+                "{} must discard before the next card is dealt",
+                player.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Called when everyone has drawn: opens the betting round after the
+    /// draw with no raises or actions carried over, and returns whether
+    /// another draw follows it, letting every player draw again if so
+    pub async fn finish_draw_round(&mut self) -> bool {
+        let completed = *self.draw_rounds_completed.get() + 1;
+        self.draw_rounds_completed.set(completed);
+        self.raises_this_street.set(0);
+        self.last_aggressor.set(None);
+        self.reset_raise_tracking();
+        if !Self::more_draws_remain(self.current_variant(), completed) {
+            return false;
+        }
+        let mut players = self.seated_players().await;
+        for player in players.iter_mut() {
+            player.has_drawn = false;
+        }
+        self.save_players(&players);
+        true
+    }
+
+    pub fn more_draws_remain(variant: GameVariant, completed: u8) -> bool {
+        completed < variant.draw_rounds()
+    }
+
+    /// Razz reverses the bring-in: the highest door card pays it, aces
+    /// counting low and spades breaking ties
+    pub fn razz_bring_in_player(players: &[PokerPlayer]) -> Option<usize> {
+        players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.has_folded)
+            .filter_map(|(i, p)| p.up_cards.first().map(|card| (i, card)))
+            .max_by_key(|(_, card)| {
+                (
+                    Self::ace_low_value(card.rank),
+                    Self::bring_in_suit_order(card.suit),
+                )
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// In Razz the best low showing acts first from fourth street on
+    pub fn razz_first_to_act(players: &[PokerPlayer]) -> Option<usize> {
+        let mut best: Option<(usize, (u8, Vec<u8>))> = None;
+        for (i, player) in players.iter().enumerate() {
+            if player.has_folded || player.up_cards.is_empty() {
+                continue;
+            }
+            let strength = Self::razz_up_card_strength(&player.up_cards);
+            if best.as_ref().is_none_or(|(_, top)| strength < *top) {
+                best = Some((i, strength));
+            }
+        }
+        best.map(|(i, _)| i)
+    }
+
+    /// As `up_card_strength` with aces low; lower is the better Razz hand
+    pub fn razz_up_card_strength(up_cards: &[Card]) -> (u8, Vec<u8>) {
+        let mut groups: Vec<(usize, u8)> = Vec::new();
+        for card in up_cards {
+            let value = Self::ace_low_value(card.rank);
+            match groups.iter_mut().find(|(_, held)| *held == value) {
+                Some((count, _)) => *count += 1,
+                None => groups.push((1, value)),
+            }
+        }
+        groups.sort_by(|a, b| b.cmp(a));
+        let category = match groups.first().map(|(count, _)| *count).unwrap_or(0) {
+            4 => 4,
+            3 => 3,
+            2 if groups.get(1).is_some_and(|(count, _)| *count == 2) => 2,
+            2 => 1,
+            _ => 0,
+        };
+        (
+            category,
+            groups.into_iter().map(|(_, value)| value).collect(),
+        )
+    }
+
+    fn ace_low_value(rank: Rank) -> u8 {
+        match rank {
+            Rank::Ace => 1,
+            rank => rank as u8,
+        }
+    }
+
+    /// The table's structure, except that pot-limit variants such as Omaha
+    /// are always played pot-limit
+    pub fn current_betting_structure(&self) -> BettingStructure {
+        if self.current_variant().is_pot_limit() {
+            return BettingStructure::PotLimit;
+        }
+        self.rules.get().betting_structure
+    }
+
+    /// Guard for `PlayerAction`: a bet or raise must fit the table's structure
+    pub fn require_bet_size(
+        &self,
+        player: &PokerPlayer,
+        action: &PlayerAction,
+    ) -> Result<(), PokerError> {
+        let situation = self.betting_situation();
+        if situation.structure == BettingStructure::FixedLimit {
+            return situation.check_fixed_limit(action);
+        }
+        situation.check_pot_limit(&Seat::from(player), action)
+    }
+
+    /// Guard for no-limit and pot-limit `PlayerAction`s: bets and raises
+    /// must be full-sized unless they are all-in
+    pub fn require_min_raise(
+        &self,
+        player: &PokerPlayer,
+        action: &PlayerAction,
+    ) -> Result<(), PokerError> {
+        let situation = self.betting_situation();
+        if situation.structure == BettingStructure::FixedLimit {
+            return Ok(());
+        }
+        let seat = Seat {
+            acted: self.acted_this_street.get().contains(&player.name),
+            ..Seat::from(player)
+        };
+        situation.check_min_raise(&seat, action)
+    }
+
+    /// The betting on the current street, for the `betting` checks
+    pub fn betting_situation(&self) -> BettingSituation {
+        BettingSituation {
+            structure: self.current_betting_structure(),
+            pot: *self.pot.get(),
+            current_bet: *self.current_bet.get(),
+            min_raise: self.min_raise(),
+            full_bet_level: *self.full_bet_level.get(),
+            bet_size: Self::fixed_limit_bet_size(self.phase.get(), *self.big_blind.get()),
+            raises_this_street: *self.raises_this_street.get(),
+            wager_cap: self.rules.get().wager_cap,
+        }
+    }
+
+    /// The current street as a `BettingRound`, picked up at the player
+    /// whose turn it is
+    pub async fn betting_round(&self) -> BettingRound {
+        let players = self.seated_players().await;
+        self.betting_round_for(&players)
+    }
+
+    fn betting_round_for(&self, players: &[PokerPlayer]) -> BettingRound {
+        let current_seat = *self.current_player_index.get();
+        let to_act = self
+            .occupied_seats
+            .get()
+            .iter()
+            .position(|&seat| seat == current_seat)
+            .unwrap_or_else(|| self.first_to_act());
+        BettingRound::resume(
+            players,
+            to_act,
+            self.acted_this_street.get(),
+            self.betting_situation(),
+        )
+    }
+
+    /// `PlayerAction`: run the action through the street's `BettingRound`,
+    /// move the chips and pass the turn on. Returns who acts next, or that
+    /// the round is closed.
+    pub async fn apply_betting_action(
+        &mut self,
+        player_name: &str,
+        action: &PlayerAction,
+    ) -> Result<RoundStatus, PokerError> {
+        if !Self::is_hand_in_progress(self.phase.get()) {
+            return Err(PokerError::NoHandInProgress);
+        }
+        let mut player = self.load_player(player_name).await?;
+        let players = self.seated_players().await;
+        let mut round = self.betting_round_for(&players);
+        let current_bet = *self.current_bet.get();
+        let min_raise = self.min_raise();
+        let status = round.act(player_name, action)?;
+
+        let seat = round
+            .seat(player_name)
+            .expect("The player who acted is seated");
+        // The round only checked the action; the stack pays for it here
+        let wagered = Self::move_chips_to_pot(&mut player, seat.bet - player.current_bet)?;
+        self.pot.set(*self.pot.get() + wagered);
+        player.current_bet = seat.bet;
+        player.total_bet_this_hand = seat.total_bet;
+        player.has_folded = seat.folded;
+        player.is_all_in = seat.all_in;
+        player.last_action = Some(action.clone());
+        if seat.folded {
+            player.status = PlayerStatus::Folded;
+        } else if seat.all_in {
+            player.status = PlayerStatus::AllIn;
+        }
+        Self::register_action(&mut player);
+        self.hand_history.get_mut().push(HandEvent::PlayerActed {
+            player: player_name.to_string(),
+            action: action.clone(),
+        });
+        if *action == PlayerAction::AllIn {
+            let reopens = betting::full_raise_size(current_bet, seat.bet, min_raise).is_some();
+            self.reopened_by_all_in.set(reopens);
+        }
+        self.save_players(&[player]);
+
+        self.record_bet_level(current_bet, round.current_bet());
+        self.current_bet.set(round.current_bet());
+        self.note_aggression(player_name, action, current_bet);
+        if let Some(index) = round.to_act_index() {
+            let seat = self.occupied_seats.get()[index];
+            self.current_player_index.set(seat);
+        }
+        invariants::check_chip_conservation(self).await?;
+        Ok(status)
+    }
+
+    /// A bet must be at least `min_raise`, and a raise must add at least
+    /// `min_raise` to `current_bet`; an all-in for less is allowed. A player
+    /// who already matched the last full bet can only call a short all-in.
+    pub fn check_min_raise(
+        action: &PlayerAction,
+        player: &PokerPlayer,
+        current_bet: u64,
+        min_raise: u64,
+        full_bet_level: u64,
+        has_acted: bool,
+    ) -> Result<(), PokerError> {
+        let situation = BettingSituation {
+            structure: BettingStructure::NoLimit,
+            pot: 0,
+            current_bet,
+            min_raise,
+            full_bet_level,
+            bet_size: min_raise,
+            raises_this_street: 0,
+            wager_cap: None,
+        };
+        let seat = Seat {
+            acted: has_acted,
+            ..Seat::from(player)
+        };
+        situation.check_min_raise(&seat, action)
+    }
+
+    /// Called when a bet or raise is accepted, before `current_bet` moves to
+    /// `new_bet`. Only a full raise resets the minimum and reopens the betting.
+    pub fn record_bet_level(&mut self, current_bet: u64, new_bet: u64) {
+        if let Some(size) = Self::full_raise_size(current_bet, new_bet, self.min_raise()) {
+            self.last_raise_size.set(size);
+            self.full_bet_level.set(new_bet);
+        }
+    }
+
+    pub fn full_raise_size(current_bet: u64, new_bet: u64, min_raise: u64) -> Option<u64> {
+        betting::full_raise_size(current_bet, new_bet, min_raise)
+    }
+
+    fn reset_raise_tracking(&mut self) {
+        self.last_raise_size.set(0);
+        self.full_bet_level.set(0);
+        self.acted_this_street.set(Vec::new());
+    }
+
+    pub const FIXED_LIMIT_RAISE_CAP: u8 = betting::FIXED_LIMIT_RAISE_CAP;
+
+    /// The small bet (one big blind) on the early streets, the big bet
+    /// (two big blinds) from the turn, or Stud's fifth street, on
+    pub fn fixed_limit_bet_size(phase: &GamePhase, big_blind: u64) -> u64 {
+        match phase {
+            GamePhase::Turn
+            | GamePhase::River
+            | GamePhase::FifthStreet
+            | GamePhase::SixthStreet
+            | GamePhase::SeventhStreet => big_blind * 2,
+            _ => big_blind,
+        }
+    }
+
+    /// Bets are exactly one `bet_size`, raises exactly one more on top of
+    /// `current_bet`, and a street allows `FIXED_LIMIT_RAISE_CAP` raises
+    pub fn check_fixed_limit(
+        action: &PlayerAction,
+        current_bet: u64,
+        bet_size: u64,
+        raises_this_street: u8,
+    ) -> Result<(), PokerError> {
+        let situation = BettingSituation {
+            structure: BettingStructure::FixedLimit,
+            pot: 0,
+            current_bet,
+            min_raise: bet_size,
+            full_bet_level: current_bet,
+            bet_size,
+            raises_this_street,
+            wager_cap: None,
+        };
+        situation.check_fixed_limit(action)
+    }
+
+    /// `Bet` and `Raise` amounts are the player's total bet for the street.
+    /// `pot` includes every bet already made on this street.
+    pub fn check_bet_size(
+        structure: BettingStructure,
+        action: &PlayerAction,
+        pot: u64,
+        current_bet: u64,
+        player_bet: u64,
+    ) -> Result<(), PokerError> {
+        let situation = BettingSituation {
+            structure,
+            pot,
+            current_bet,
+            min_raise: 0,
+            full_bet_level: current_bet,
+            bet_size: 0,
+            raises_this_street: 0,
+            wager_cap: None,
+        };
+        let seat = Seat {
+            name: String::new(),
+            chips: 0,
+            bet: player_bet,
+            total_bet: player_bet,
+            folded: false,
+            all_in: false,
+            sitting_out: false,
+            acted: false,
+        };
+        situation.check_pot_limit(&seat, action)
+    }
+
+    /// Heads-up the button posts the small blind; otherwise it is the seat
+    /// to the button's left
+    pub fn small_blind_seat(dealer_position: usize, player_count: usize) -> Option<usize> {
+        match player_count {
+            0 | 1 => None,
+            2 => Some(dealer_position % 2),
+            _ => Some((dealer_position + 1) % player_count),
+        }
+    }
+
+    /// Seat that posts the big blind; heads-up the button posts the small
+    /// blind, so the big blind is the other player
+    pub fn big_blind_seat(dealer_position: usize, player_count: usize) -> usize {
+        match player_count {
+            0 => 0,
+            2 => (dealer_position + 1) % 2,
+            _ => (dealer_position + 2) % player_count,
+        }
+    }
+
+    /// The big blind's ante is dead money taken before the blind. A short
+    /// stack posts the blind first and only antes what is left over.
+    pub fn collect_big_blind_ante(player: &mut PokerPlayer, ante: u64, big_blind: u64) -> u64 {
+        let amount = ante.min(player.chips.saturating_sub(big_blind));
+        Self::move_chips_to_pot(player, amount).expect("Ante is capped at the stack");
+        player.total_bet_this_hand += amount;
+        amount
+    }
+
+    /// `PostStraddle`: only the player who will be under the gun next hand,
+    /// at a table of three or more, may straddle. Returns the amount posted.
+    pub async fn post_straddle(&mut self, player_name: &str) -> Result<u64, PokerError> {
+        if !self.rules.get().straddle_allowed {
+            return Err(PokerError::NotEnabled(
+                "Straddling is not allowed at this table".to_string(),
+            ));
+        }
+        if Self::is_hand_in_progress(self.phase.get()) {
+            return Err(PokerError::InvalidOperation(
+                "Straddles are posted before the deal".to_string(),
+            ));
+        }
+        let player_names = self.player_order.get().clone();
+        let big_blind = self.next_blind_positions().big_blind;
+        let utg = Self::straddle_seat(big_blind as usize, player_names.len());
+        if utg
+            .and_then(|seat| player_names.get(seat))
+            .map(String::as_str)
+            != Some(player_name)
+        {
+            return Err(PokerError::InvalidOperation(
+                "Only the player under the gun can straddle".to_string(),
+            ));
+        }
+
+        let mut player = self.load_player(player_name).await?;
+        if player.straddle_posted > 0 {
+            return Err(PokerError::AlreadyDone(format!(
+                "{} has already straddled",
+                player_name
+            )));
+        }
+        let amount = *self.big_blind.get() * 2;
+        Self::require_chips(&player, amount)?;
+        player.straddle_posted = amount;
+        self.save_players(&[player]);
+        Ok(amount)
+    }
+
+    /// Under the gun, left of the big blind; there is no straddle heads-up
+    pub fn straddle_seat(big_blind_seat: usize, player_count: usize) -> Option<usize> {
+        if player_count < 3 {
+            return None;
+        }
+        Some((big_blind_seat + 1) % player_count)
+    }
+
+    /// Pre-flop action starts left of the big blind, or of the straddler.
+    /// Heads-up that is the button, who posted the small blind.
+    pub fn preflop_first_to_act(
+        big_blind_seat: usize,
+        player_count: usize,
+        straddled: bool,
+    ) -> usize {
+        match player_count {
+            0 => 0,
+            2 => (big_blind_seat + 1) % 2,
+            _ => (big_blind_seat + 1 + straddled as usize) % player_count,
+        }
+    }
+
+    /// After the flop the first seat left of the button acts first and the
+    /// button last; heads-up that puts the big blind first
+    pub fn postflop_first_to_act(dealer_position: usize, player_count: usize) -> usize {
+        (dealer_position + 1) % player_count.max(1)
+    }
+
+    /// Seat that opens the betting on the current street
+    pub fn first_to_act(&self) -> usize {
+        let player_count = self.player_order.get().len();
+        if *self.phase.get() == GamePhase::PreFlop {
+            let big_blind = self
+                .big_blind_player
+                .get()
+                .as_ref()
+                .and_then(|name| {
+                    self.player_order
+                        .get()
+                        .iter()
+                        .position(|seated| seated == name)
+                })
+                .unwrap_or_else(|| Self::big_blind_seat(self.dealer_index(), player_count));
+            Self::preflop_first_to_act(big_blind, player_count, *self.straddle_amount.get() > 0)
+        } else {
+            Self::postflop_first_to_act(self.dealer_index(), player_count)
+        }
+    }
+
+    /// Smallest raise increment: the last full raise this street, and never
+    /// less than a big blind, or the straddle pre-flop
+    pub fn min_raise(&self) -> u64 {
+        (*self.big_blind.get())
+            .max(*self.straddle_amount.get())
+            .max(*self.last_raise_size.get())
+    }
+
+    /// Called between hands with the block timestamp. Moves to the next blind
+    /// level once the current one has expired and returns it for
+    /// `BlindsIncreased`; the last level is played until the end.
+    pub fn advance_blind_level(&mut self, now_ms: u64) -> Option<(u32, BlindLevel)> {
+        let hand_number = *self.hand_number.get();
+        let next = Self::next_blind_level(
+            &self.blind_schedule.get().levels,
+            *self.blind_level.get(),
+            *self.blind_level_started_ms.get(),
+            *self.blind_level_started_hand.get(),
+            now_ms,
+            hand_number,
+        )?;
+        let level = self.blind_schedule.get().levels[next as usize].clone();
+        self.blind_level.set(next);
+        self.blind_level_started_ms.set(now_ms);
+        self.blind_level_started_hand.set(hand_number);
+        self.small_blind.set(level.small_blind);
+        self.big_blind.set(level.big_blind);
+        self.rules.get_mut().ante = level.ante;
+        Some((next, level))
+    }
+
+    pub fn next_blind_level(
+        schedule: &[BlindLevel],
+        current_level: u32,
+        started_ms: u64,
+        started_hand: u64,
+        now_ms: u64,
+        hand_number: u64,
+    ) -> Option<u32> {
+        let next = current_level.checked_add(1)?;
+        schedule.get(next as usize)?;
+        schedule
+            .get(current_level as usize)?
+            .is_expired(started_ms, started_hand, now_ms, hand_number)
+            .then_some(next)
+    }
+
+    /// Chips the player can still put in this hand under the table's cap
+    pub fn wager_left(player: &PokerPlayer, wager_cap: Option<u64>) -> u64 {
+        Seat::from(player).wager_left(wager_cap)
+    }
+
+    /// Call after every wager in a cap game. A player who has reached the cap
+    /// is all-in for the rest of the hand; returns true when that happens.
+    pub fn enforce_wager_cap(player: &mut PokerPlayer, wager_cap: Option<u64>) -> bool {
+        let capped = wager_cap.is_some_and(|cap| player.total_bet_this_hand >= cap);
+        if !capped || player.is_all_in {
+            return false;
+        }
+        player.is_all_in = true;
+        player.status = PlayerStatus::AllIn;
+        true
+    }
+
+    /// Stud Hi-Lo keeps the high-hand order of play; only Razz reverses it
+    pub fn bring_in_player_for_variant(
+        variant: GameVariant,
+        players: &[PokerPlayer],
+    ) -> Option<usize> {
+        match variant {
+            GameVariant::Razz => Self::razz_bring_in_player(players),
+            _ if variant.is_stud() => Self::stud_bring_in_player(players),
+            _ => None,
+        }
+    }
+
+    pub fn stud_first_to_act_for_variant(
+        variant: GameVariant,
+        players: &[PokerPlayer],
+    ) -> Option<usize> {
+        match variant {
+            GameVariant::Razz => Self::razz_first_to_act(players),
+            _ if variant.is_stud() => Self::stud_first_to_act(players),
+            _ => None,
+        }
+    }
+
+    /// All seven of a Stud player's cards, down cards first
+    pub fn stud_hand(player: &PokerPlayer) -> Vec<Card> {
+        let mut cards = player.hole_cards.clone().unwrap_or_default();
+        cards.extend_from_slice(&player.up_cards);
+        cards
+    }
+
+    /// Publish the priced offers for this hand's all-in favourites. Returns
+    /// the offers made, none when insurance is off.
+    pub fn open_insurance_offers(&mut self, offers: Vec<InsuranceOffer>) -> Vec<InsuranceOffer> {
+        if !*self.insurance_enabled.get() {
+            return Vec::new();
+        }
+        let hand_number = *self.hand_number.get();
+        let offers: Vec<InsuranceOffer> = offers
+            .into_iter()
+            .filter(|offer| offer.hand_number == hand_number && !offer.accepted)
+            .collect();
+        self.insurance_offers.set(offers.clone());
+        offers
+    }
+
+    /// `AcceptInsurance`: the pool holds back the claim less the premium so
+    /// it can always pay out
+    pub async fn accept_insurance(
+        &mut self,
+        player_name: &str,
+    ) -> Result<InsuranceOffer, PokerError> {
+        let pool = *self.insurance_pool.get();
+        let mut offers = self.insurance_offers.get().clone();
+        let offer = offers
+            .iter_mut()
+            .find(|offer| offer.player == player_name && !offer.accepted)
+            .ok_or_else(|| {
+                PokerError::NoOffer(format!("No insurance on offer for {}", player_name))
+            })?;
+        let reserved = offer.coverage - offer.premium;
+        if reserved > pool {
+            return Err(PokerError::InvalidOperation(
+                "The insurance pool cannot cover this hand".to_string(),
+            ));
+        }
+        offer.accepted = true;
+        let accepted = offer.clone();
+        self.insurance_pool.set(pool - reserved);
+        self.insurance_offers.set(offers);
+        invariants::check_chip_conservation(self).await?;
+        Ok(accepted)
+    }
+
+    /// After the pot is paid: a winner pays the premium out of their
+    /// winnings, a loser collects the coverage less the premium. Returns the
+    /// chips moved, or `None` without an accepted offer.
+    pub fn settle_insurance(&mut self, player: &mut PokerPlayer, won: bool) -> Option<u64> {
+        let mut offers = self.insurance_offers.get().clone();
+        let index = offers
+            .iter()
+            .position(|offer| offer.player == player.name && offer.accepted)?;
+        let offer = offers.remove(index);
+        self.insurance_offers.set(offers);
+        let (moved, to_pool) = Self::apply_insurance(&offer, player, won);
+        self.insurance_pool
+            .set(*self.insurance_pool.get() + to_pool);
+        Some(moved)
+    }
+
+    /// Returns (chips moved to or from the player, chips going back to the pool)
+    pub fn apply_insurance(
+        offer: &InsuranceOffer,
+        player: &mut PokerPlayer,
+        won: bool,
+    ) -> (u64, u64) {
+        let reserved = offer.coverage - offer.premium;
+        if won {
+            let premium = offer.premium.min(player.chips);
+            player.chips -= premium;
+            (premium, reserved + premium)
+        } else {
+            player.chips += reserved;
+            (reserved, 0)
+        }
+    }
+
+    /// Drop any offers and return held-back claims to the pool
+    fn release_insurance(&mut self) {
+        let held: u64 = self
+            .insurance_offers
+            .get()
+            .iter()
+            .filter(|offer| offer.accepted)
+            .map(|offer| offer.coverage - offer.premium)
+            .sum();
+        self.insurance_pool.set(*self.insurance_pool.get() + held);
+        self.insurance_offers.set(Vec::new());
+    }
+
+    /// Store the layered pots after a betting action. Money in the pot that
+    /// no player is credited with, such as rabbit hunt fees, belongs to the
+    /// main pot.
+    pub fn record_side_pots(&mut self, pots: Vec<SidePot>) {
+        self.side_pots
+            .set(Self::with_dead_money(pots, *self.pot.get()));
+    }
+
+    pub fn with_dead_money(mut pots: Vec<SidePot>, pot_total: u64) -> Vec<SidePot> {
+        let layered: u64 = pots.iter().map(|pot| pot.amount).sum();
+        let dead = pot_total.saturating_sub(layered);
+        match pots.first_mut() {
+            Some(main) => main.amount += dead,
+            None if dead > 0 => pots.push(SidePot {
+                amount: dead,
+                eligible_players: Vec::new(),
+            }),
+            None => {}
+        }
+        pots
+    }
+
+    /// Take the rake out of each pot before it is awarded. Returns the
+    /// amount taken, which is added to `rake_collected` and credited to the
+    /// players who paid it.
+    pub async fn collect_rake(&mut self) -> Result<u64, PokerError> {
+        let percent = *self.rake_percent.get();
+        let flop_seen = !self.rules.get().variant.uses_community_cards()
+            || !self.community_cards.get().is_empty();
+        if percent == 0 || (*self.no_flop_no_drop.get() && !flop_seen) {
+            return Ok(0);
+        }
+        let mut pots = Self::with_dead_money(self.side_pots.get().clone(), *self.pot.get());
+        let rake = Self::rake_pots(&mut pots, percent, *self.rake_cap.get());
+        self.side_pots.set(pots);
+        self.pot.set(self.pot.get() - rake);
+        self.rake_collected.set(*self.rake_collected.get() + rake);
+
+        let contributions: Vec<(String, u64)> = self
+            .seated_players()
+            .await
+            .iter()
+            .map(|p| (p.name.clone(), p.total_bet_this_hand))
+            .collect();
+        for (name, share) in Self::distribute_proportionally(rake, &contributions) {
+            let paid = self
+                .rake_paid
+                .get(&name)
+                .await
+                .expect("Failed to load rake paid")
+                .unwrap_or(0);
+            self.rake_paid
+                .insert(&name, paid + share)
+                .expect("Failed to save rake paid");
+        }
+        invariants::check_chip_conservation(self).await?;
+        Ok(rake)
+    }
+
+    /// Rake each pot in turn, main pot first, until the hand's cap is used up
+    pub fn rake_pots(pots: &mut [SidePot], percent: u8, cap: Option<u64>) -> u64 {
+        let mut taken = 0;
+        for pot in pots.iter_mut() {
+            let mut rake = pot.amount * percent as u64 / 100;
+            if let Some(cap) = cap {
+                rake = rake.min(cap - taken);
+            }
+            pot.amount -= rake;
+            taken += rake;
+        }
+        taken
+    }
+
+    /// `ClaimRakeback`: pay out the rakeback earned since the last claim,
+    /// from the house's collected rake. Only between hands.
+    pub async fn claim_rakeback(&mut self, player_name: &str) -> Result<u64, PokerError> {
+        self.enforce_table_stakes()?;
+        let mut player = self.load_player(player_name).await?;
+        let key = player_name.to_string();
+        let paid = self
+            .rake_paid
+            .get(&key)
+            .await
+            .expect("Failed to load rake paid")
+            .unwrap_or(0);
+        let claimed = self
+            .rakeback_claimed
+            .get(&key)
+            .await
+            .expect("Failed to load rakeback claimed")
+            .unwrap_or(0);
+        let owed = Self::rakeback_owed(paid, *self.rakeback_percent.get(), claimed)
+            .min(*self.rake_collected.get());
+        if owed == 0 {
+            return Err(PokerError::NoOffer(format!(
+                "{} has no rakeback to claim",
+                player_name
+            )));
+        }
+
+        player.chips += owed;
+        self.rake_collected.set(*self.rake_collected.get() - owed);
+        self.rakeback_claimed
+            .insert(&key, claimed + owed)
+            .expect("Failed to save rakeback claimed");
+        self.save_players(&[player]);
+        Ok(owed)
+    }
+
+    /// Rakeback earned on all rake paid so far, less what was already claimed
+    pub fn rakeback_owed(rake_paid: u64, percent: u8, claimed: u64) -> u64 {
+        (rake_paid * percent as u64 / 100).saturating_sub(claimed)
+    }
+
+    /// Legal actions for `player_name`; nothing is legal when it is not their
+    /// turn
+    pub async fn legal_actions(&self, player_name: &str) -> Result<LegalActions, PokerError> {
+        self.load_player(player_name).await?;
+        if !Self::is_hand_in_progress(self.phase.get()) {
+            return Ok(LegalActions::default());
+        }
+        Ok(self.betting_round().await.legal_actions(player_name))
+    }
+
+    pub fn compute_legal_actions(
+        player: &PokerPlayer,
+        situation: &BettingSituation,
+        has_acted: bool,
+    ) -> LegalActions {
+        let seat = Seat {
+            acted: has_acted,
+            ..Seat::from(player)
+        };
+        situation.legal_actions(&seat)
+    }
+
+    /// Call once a betting round is settled. When nobody is left to bet, deal
+    /// the rest of the board without stopping for action and go to showdown.
+    /// Returns each street dealt, for `CommunityCardsRevealed`.
+    pub async fn auto_run_out(&mut self) -> Vec<(GamePhase, Vec<Card>)> {
+        let players = self.seated_players().await;
+        let mut phase = self.phase.get().clone();
+        if !self.current_variant().uses_community_cards()
+            || Self::next_board_street(&phase).is_none()
+            || !Self::betting_is_over(&players)
+        {
+            return Vec::new();
+        }
+
+        let mut streets = Vec::new();
+        while let Some(next) = Self::next_board_street(&phase) {
+            let count = Self::next_community_cards_count(&phase);
+            phase = next;
+            if count > 0 {
+                let cards = self.deal_community_cards(count, true);
+                streets.push((phase.clone(), cards));
+            }
+        }
+        self.phase.set(GamePhase::Showdown);
+        streets
+    }
+
+    /// Two or more players are still in, but at most one of them has chips
+    /// left to bet with
+    pub fn betting_is_over(players: &[PokerPlayer]) -> bool {
+        let in_hand: Vec<&PokerPlayer> = players
+            .iter()
+            .filter(|p| !p.has_folded && p.status != PlayerStatus::SittingOut)
+            .collect();
+        let can_bet = in_hand
+            .iter()
+            .filter(|p| !p.is_all_in && p.chips > 0)
+            .count();
+        in_hand.len() >= 2 && can_bet <= 1
+    }
+
+    pub async fn set_auto_muck(
+        &mut self,
+        player_name: &str,
+        enabled: bool,
+    ) -> Result<(), PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        player.auto_muck = enabled;
+        self.save_players(&[player]);
+        Ok(())
+    }
+
+    /// `ShowHand`: returns the cards for `HandShown`
+    pub async fn show_hand(&mut self, player_name: &str) -> Result<Vec<Card>, PokerError> {
+        let phase = self.phase.get();
+        if !matches!(phase, GamePhase::Showdown | GamePhase::HandComplete) {
+            return Err(PokerError::InvalidPhase {
+                expected: GamePhase::Showdown,
+                actual: phase.clone(),
+            });
+        }
+        let player = self.load_player(player_name).await?;
+        let cards = player.hole_cards.ok_or_else(|| {
+            PokerError::InvalidOperation(format!("{} has no cards to show", player_name))
+        })?;
+        let shown = self.shown_voluntarily.get_mut();
+        if !shown.contains(&player.name) {
+            shown.push(player.name);
+        }
+        Ok(cards)
+    }
+
+    /// Hole cards `HandComplete` may reveal: every winner's, and each other
+    /// player's in the showdown unless they auto-muck and did not choose to
+    /// show
+    pub fn showdown_reveals(
+        in_showdown: &[PokerPlayer],
+        winners: &[String],
+        shown_voluntarily: &[String],
+    ) -> Vec<(String, Vec<Card>)> {
+        in_showdown
+            .iter()
+            .filter(|p| {
+                winners.contains(&p.name) || !p.auto_muck || shown_voluntarily.contains(&p.name)
+            })
+            .filter_map(|p| Some((p.name.clone(), p.hole_cards.clone()?)))
+            .collect()
+    }
+
+    /// `CancelHand`: refund every blind, ante and bet of a hand that cannot
+    /// be completed and go back to waiting for players. Returns the reason,
+    /// hand number and refunds for `HandCancelled`.
+    pub async fn cancel_hand(
+        &mut self,
+        player_name: &str,
+        current_time_ms: u64,
+    ) -> Result<(CancelReason, u64, Vec<(String, u64)>), PokerError> {
+        self.load_player(player_name).await?;
+        let phase = self.phase.get().clone();
+        if !Self::is_hand_in_progress(&phase) {
+            return Err(PokerError::NoHandInProgress);
+        }
+
+        let players = self.seated_players().await;
+        let first_street = phase == Self::first_betting_phase(*self.bomb_pot_this_hand.get())
+            || phase == GamePhase::ThirdStreet;
+        let voluntary_action = !first_street
+            || players.iter().any(|p| {
+                p.consecutive_timeouts == 0 && self.acted_this_street.get().contains(&p.name)
+            });
+        let waited_ms = current_time_ms.saturating_sub(*self.last_state_change_ms.get());
+        let reason = Self::hand_cancel_reason(
+            &players,
+            voluntary_action,
+            waited_ms,
+            *self.commitment_timeout_ms.get(),
+        )
+        .ok_or_else(|| {
+            PokerError::InvalidOperation("The hand can still be completed".to_string())
+        })?;
+
+        let refunds = self.void_current_hand(players).await?;
+        self.last_state_change_ms.set(current_time_ms);
+        Ok((reason, *self.hand_number.get(), refunds))
+    }
+
+    pub fn hand_cancel_reason(
+        players: &[PokerPlayer],
+        voluntary_action: bool,
+        waited_ms: u64,
+        commitment_timeout_ms: u64,
+    ) -> Option<CancelReason> {
+        let dealt_in = || {
+            players
+                .iter()
+                .filter(|p| p.status != PlayerStatus::SittingOut)
+        };
+        if waited_ms > commitment_timeout_ms
+            && dealt_in().any(|p| p.hole_cards_commitment.is_none())
+        {
+            return Some(CancelReason::CommitmentsMissing);
+        }
+        let timed_out = players.iter().any(|p| p.consecutive_timeouts > 0);
+        let still_responsive = dealt_in()
+            .filter(|p| !p.has_folded && p.consecutive_timeouts == 0)
+            .count();
+        if !voluntary_action && timed_out && still_responsive <= 1 {
+            return Some(CancelReason::EveryoneElseTimedOut);
+        }
+        None
+    }
+
+    /// `AddChips`: top up a seated player's stack between hands. Counts as a
+    /// rebuy. Returns the new stack for `ChipsAdded`.
+    pub async fn add_chips(&mut self, player_name: &str, amount: u64) -> Result<u64, PokerError> {
+        self.enforce_table_stakes()?;
+        if self.tournament_config.get().is_some() || !*self.rebuys_enabled.get() {
+            return Err(PokerError::NotEnabled(
+                "Chips cannot be added at this table".to_string(),
+            ));
+        }
+        let mut player = self.load_player(player_name).await?;
+        if self
+            .max_rebuys_per_session
+            .get()
+            .is_some_and(|max| player.rebuys_used >= max)
+        {
+            return Err(PokerError::LimitReached(format!(
+                "{} has used all their rebuys",
+                player_name
+            )));
+        }
+        Self::validate_top_up(player.chips, amount, *self.buy_in_max.get())?;
+        player.chips += amount;
+        player.rebuys_used += 1;
+        let stack = player.chips;
+        self.save_players(&[player]);
+        Ok(stack)
+    }
+
+    pub fn validate_top_up(chips: u64, amount: u64, buy_in_max: u64) -> Result<(), PokerError> {
+        if amount == 0 {
+            return Err(PokerError::InvalidOperation(
+                "Top-up amount must be positive".to_string(),
+            ));
+        }
+        if chips.saturating_add(amount) > buy_in_max {
+            return Err(PokerError::InvalidOperation(format!(
+                "Stack cannot go above the maximum buy-in of {}; at most {} more",
+                buy_in_max,
+                buy_in_max.saturating_sub(chips)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Call from `StartHand` after `assign_button`. Players sitting out in
+    /// the blinds miss them; one waiting for the big blind is dealt back in
+    /// when it reaches them. Returns the players dealt back in.
+    pub async fn record_missed_blinds(&mut self) -> Vec<String> {
+        let small_blind = self.small_blind_player.get().clone();
+        let big_blind = self.big_blind_player.get().clone();
+        let mut returned = Vec::new();
+        for (name, is_big_blind) in [(small_blind, false), (big_blind, true)] {
+            let Some(name) = name else {
+                continue;
+            };
+            let Ok(mut player) = self.load_player(&name).await else {
+                continue;
+            };
+            if Self::miss_blind(&mut player, is_big_blind) {
+                returned.push(name);
+            }
+            self.save_players(&[player]);
+        }
+        returned
+    }
+
+    /// Returns true if the player was waiting for this big blind and is back
+    pub fn miss_blind(player: &mut PokerPlayer, is_big_blind: bool) -> bool {
+        if player.status != PlayerStatus::SittingOut {
+            return false;
+        }
+        if is_big_blind && player.waiting_for_big_blind {
+            // Posting this big blind settles everything missed
+            player.waiting_for_big_blind = false;
+            player.missed_small_blind = false;
+            player.missed_big_blind = false;
+            player.status = PlayerStatus::Active;
+            return true;
+        }
+        if is_big_blind {
+            player.missed_big_blind = true;
+            player.big_blinds_missed += 1;
+        } else {
+            player.missed_small_blind = true;
+        }
+        false
+    }
+
+    /// Returning without waiting: the small blind is owed dead and the big
+    /// blind live, both collected with the next hand
+    pub fn post_missed_blinds(player: &mut PokerPlayer, small_blind: u64, big_blind: u64) {
+        if std::mem::take(&mut player.missed_small_blind) {
+            player.missed_blind_due += small_blind;
+        }
+        if std::mem::take(&mut player.missed_big_blind) {
+            player.live_blind_due = big_blind;
+        }
+        player.waiting_for_big_blind = false;
+    }
+
+    /// `JoinTable`: seat a new player, or put them on the waiting list when
+    /// the table is full. Waitlisted players take whichever seat frees up.
+    pub async fn join_table(
+        &mut self,
+        address: &str,
+        player_name: &str,
+        buy_in: u64,
+        wait_for_big_blind: bool,
+        seat: Option<u8>,
+    ) -> Result<JoinOutcome, PokerError> {
+        if let Some(config) = self.tournament_config.get().clone() {
+            return self
+                .register_for_tournament(address, player_name, buy_in, seat, &config)
+                .await;
+        }
+        Self::check_buy_in(buy_in, *self.buy_in_min.get(), *self.buy_in_max.get())?;
+        match self
+            .seat_player(address, player_name, buy_in, wait_for_big_blind, seat)
+            .await
+        {
+            Err(PokerError::TableFull) => {
+                let position = Self::add_to_waiting_list(
+                    self.waiting_list.get_mut(),
+                    WaitingPlayer {
+                        address: address.to_string(),
+                        player_name: player_name.to_string(),
+                        buy_in,
+                        wait_for_big_blind,
+                    },
+                )?;
+                Ok(JoinOutcome::Waitlisted { position })
+            }
+            result => result.map(|player| JoinOutcome::Seated(Box::new(player))),
+        }
+    }
+
+    /// Sit & Go registration: the buy-in goes into the prize pool and the
+    /// player gets the starting stack. There is no waiting list.
+    async fn register_for_tournament(
+        &mut self,
+        address: &str,
+        player_name: &str,
+        buy_in: u64,
+        seat: Option<u8>,
+        config: &TournamentConfig,
+    ) -> Result<JoinOutcome, PokerError> {
+        Self::check_tournament_entry(config, buy_in, *self.tournament_started.get())?;
+        let player = self
+            .seat_player(address, player_name, config.starting_stack, false, seat)
+            .await?;
+        *self.prize_pool.get_mut() += buy_in;
+        Ok(JoinOutcome::Seated(Box::new(player)))
+    }
+
+    pub fn check_tournament_entry(
+        config: &TournamentConfig,
+        buy_in: u64,
+        started: bool,
+    ) -> Result<(), PokerError> {
+        if started {
+            return Err(PokerError::RegistrationClosed);
+        }
+        if buy_in != config.buy_in {
+            return Err(PokerError::InvalidOperation(format!(
+                "The tournament buy-in is {}",
+                config.buy_in
+            )));
+        }
+        Ok(())
+    }
+
+    /// Call after each registration. Once the Sit & Go is full, closes
+    /// registration, locks the prize pool and starts the blind clock. Returns
+    /// the prize pool for `TournamentStarted`.
+    pub fn start_sit_and_go(&mut self, now_ms: u64) -> Option<u64> {
+        let config = self.tournament_config.get().as_ref()?;
+        if *self.tournament_started.get() || self.player_order.get().len() < config.players as usize
+        {
+            return None;
+        }
+        self.tournament_started.set(true);
+        self.blind_level_started_ms.set(now_ms);
+        self.blind_level_started_hand.set(*self.hand_number.get());
+        if let Some(level) = self.blind_schedule.get().levels.first().cloned() {
+            self.set_blind_level(0, &level);
+        }
+        Some(*self.prize_pool.get())
+    }
+
+    /// Blinds and ante from here on. Multi-table tournament tables get their
+    /// levels from the coordinator rather than their own schedule.
+    pub fn set_blind_level(&mut self, index: u32, level: &BlindLevel) {
+        self.blind_level.set(index);
+        self.small_blind.set(level.small_blind);
+        self.big_blind.set(level.big_blind);
+        self.rules.get_mut().ante = level.ante;
+    }
+
+    /// Seat a player sent by the tournament coordinator in the seat it chose,
+    /// with their tournament stack. No buy-in is taken at the table. Once
+    /// play has started they wait for the big blind to reach them, so a late
+    /// entrant or moved player never gets a hand without paying blinds.
+    pub async fn seat_tournament_player(
+        &mut self,
+        address: &str,
+        player_name: &str,
+        chips: u64,
+        seat: u8,
+    ) -> Result<PokerPlayer, PokerError> {
+        self.seat_player(address, player_name, chips, true, Some(seat))
+            .await
+    }
+
+    /// Add chips bought from the tournament coordinator. Table stakes apply,
+    /// so during a hand they wait for `credit_pending_tournament_chips`.
+    pub async fn add_tournament_chips(
+        &mut self,
+        player_name: &str,
+        chips: u64,
+    ) -> Result<(), PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        if Self::is_hand_in_progress(self.phase.get()) {
+            let pending = self
+                .pending_tournament_chips
+                .get(&player.name)
+                .await
+                .expect("Failed to load pending chips")
+                .unwrap_or(0);
+            self.pending_tournament_chips
+                .insert(&player.name, pending + chips)
+                .expect("Failed to save pending chips");
+            return Ok(());
+        }
+        player.chips += chips;
+        self.save_players(&[player]);
+        Ok(())
+    }
+
+    /// `TournamentMovePlayer`: the player leaves with their stack, to be
+    /// sent on to the new table in `TournamentStackMigration`. A player in
+    /// the middle of a hand moves once it is over.
+    pub async fn move_tournament_player(
+        &mut self,
+        player_name: &str,
+        to_table: &str,
+        seat: u8,
+    ) -> Result<Option<StackMigration>, PokerError> {
+        self.load_player(player_name).await?;
+        let pending = TournamentMove {
+            player_name: player_name.to_string(),
+            to_table: to_table.to_string(),
+            seat,
+        };
+        if Self::is_hand_in_progress(self.phase.get()) {
+            self.pending_tournament_moves.get_mut().push(pending);
+            return Ok(None);
+        }
+        Ok(self.migrate(pending).await)
+    }
+
+    /// Call when a hand ends, after busted players are eliminated. Returns
+    /// the stacks to send on.
+    pub async fn release_pending_tournament_moves(&mut self) -> Vec<StackMigration> {
+        let pending = std::mem::take(self.pending_tournament_moves.get_mut());
+        let mut migrations = Vec::new();
+        for tournament_move in pending {
+            migrations.extend(self.migrate(tournament_move).await);
+        }
+        migrations
+    }
+
+    /// `None` when the player has gone, e.g. busted out in the hand
+    async fn migrate(&mut self, tournament_move: TournamentMove) -> Option<StackMigration> {
+        let player = self.load_player(&tournament_move.player_name).await.ok()?;
+        self.players
+            .remove(&player.name)
+            .expect("Failed to remove player");
+        self.unseat(&player.name);
+        Some(StackMigration {
+            player_name: player.name,
+            address: player.address,
+            chips: player.chips,
+            to_table: tournament_move.to_table,
+            seat: tournament_move.seat,
+        })
+    }
+
+    /// Call when a hand ends, before busted players are eliminated
+    pub async fn credit_pending_tournament_chips(&mut self) {
+        let pending = self
+            .pending_tournament_chips
+            .index_values()
+            .await
+            .expect("Failed to load pending chips");
+        for (player_name, chips) in pending {
+            if let Ok(mut player) = self.load_player(&player_name).await {
+                player.chips += chips;
+                self.save_players(&[player]);
+            }
+        }
+        self.pending_tournament_chips.clear();
+    }
+
+    /// Call between tournament hands, before the hand history is archived:
+    /// players without chips leave the table in the order they busted.
+    /// Returns them with who knocked them out, for the coordinator.
+    pub async fn eliminate_busted_players(&mut self) -> Vec<Elimination> {
+        if self.tournament_config.get().is_none() {
+            return Vec::new();
+        }
+        let players = self.seated_players().await;
+        let eliminations: Vec<Elimination> = Self::bust_order(&players)
+            .into_iter()
+            .map(|player_name| Elimination {
+                eliminated_by: Self::eliminated_by(&players, &player_name, self.hand_history.get()),
+                player_name,
+            })
+            .collect();
+        for elimination in &eliminations {
+            let name = &elimination.player_name;
+            self.record_elimination(name);
+            self.players.remove(name).expect("Failed to remove player");
+            self.unseat(name);
+        }
+        eliminations
+    }
+
+    /// Who knocked a busted player out: the winners of the biggest pot they
+    /// were in, i.e. players who covered them, stayed in and took chips this
+    /// hand. At a showdown only the best hand among those counts.
+    pub fn eliminated_by(
+        players: &[PokerPlayer],
+        busted: &str,
+        events: &[HandEvent],
+    ) -> Vec<String> {
+        let Some(stake) = players
+            .iter()
+            .find(|player| player.name == busted)
+            .map(|player| player.total_bet_this_hand)
+        else {
+            return Vec::new();
+        };
+        let rank = |name: &str| {
+            events.iter().find_map(|event| match event {
+                HandEvent::ShowdownRevealed { player, hand_rank } if player == name => {
+                    Some(hand_rank)
+                }
+                _ => None,
+            })
+        };
+        let took_chips = |name: &str| {
+            events.iter().any(
+                |event| matches!(event, HandEvent::PotAwarded { player, .. } if player == name),
+            )
+        };
+        let contenders: Vec<&PokerPlayer> = players
+            .iter()
+            .filter(|player| {
+                player.name != busted
+                    && !player.has_folded
+                    && player.total_bet_this_hand >= stake
+                    && took_chips(&player.name)
+            })
+            .collect();
+        let best = contenders
+            .iter()
+            .filter_map(|player| rank(&player.name))
+            .max();
+        contenders
+            .into_iter()
+            .filter(|player| best.is_none() || rank(&player.name) == best)
+            .map(|player| player.name.clone())
+            .collect()
+    }
+
+    /// Players who busted in the same hand finish in order of the stacks
+    /// they started it with, so the smaller stack goes out first
+    pub fn bust_order(players: &[PokerPlayer]) -> Vec<String> {
+        let mut busted: Vec<&PokerPlayer> = players.iter().filter(|p| p.chips == 0).collect();
+        busted.sort_by_key(|p| p.total_bet_this_hand);
+        busted.into_iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Call whenever a seat frees up. Seats players from the front of the
+    /// waiting list; returns them for `SeatAvailable`.
+    pub async fn seat_from_waiting_list(&mut self) -> Vec<PokerPlayer> {
+        let mut seated = Vec::new();
+        while self.player_order.get().len() < *self.max_players.get() as usize {
+            let waiting = self.waiting_list.get_mut();
+            if waiting.is_empty() {
+                break;
+            }
+            let next = waiting.remove(0);
+            // Dropped if the name was taken or the buy-in limits changed meanwhile
+            if let Ok(player) = self
+                .seat_player(
+                    &next.address,
+                    &next.player_name,
+                    next.buy_in,
+                    next.wait_for_big_blind,
+                    None,
+                )
+                .await
+            {
+                seated.push(player);
+            }
+        }
+        seated
+    }
+
+    /// Returns how many players are ahead in the queue
+    pub fn add_to_waiting_list(
+        waiting_list: &mut Vec<WaitingPlayer>,
+        entry: WaitingPlayer,
+    ) -> Result<usize, PokerError> {
+        if waiting_list
+            .iter()
+            .any(|waiting| waiting.player_name == entry.player_name)
+        {
+            return Err(PokerError::DuplicatePlayer(entry.player_name));
+        }
+        waiting_list.push(entry);
+        Ok(waiting_list.len() - 1)
+    }
+
+    async fn seat_player(
+        &mut self,
+        address: &str,
+        player_name: &str,
+        buy_in: u64,
+        wait_for_big_blind: bool,
+        seat: Option<u8>,
+    ) -> Result<PokerPlayer, PokerError> {
+        Self::check_seat_available(
+            self.player_order.get(),
+            *self.max_players.get(),
+            player_name,
+        )?;
+        let taken: Vec<u8> = self
+            .seated_players()
+            .await
+            .iter()
+            .map(|player| player.position)
+            .collect();
+        let position = Self::choose_seat(&taken, *self.max_players.get(), seat)?;
+        let mut player = PokerPlayer::new(address, player_name, buy_in, position);
+        Self::seat_newcomer(
+            &mut player,
+            *self.hand_number.get() > 0,
+            wait_for_big_blind,
+            *self.big_blind.get(),
+        );
+        // Keep `player_order` in seat order so the button and blinds move
+        // round the table the way players see it
+        let index = taken.iter().filter(|&&other| other < position).count();
+        self.player_order
+            .get_mut()
+            .insert(index, player.name.clone());
+        self.occupied_seats.get_mut().insert(index, position);
+        self.save_players(&[player.clone()]);
+        Ok(player)
+    }
+
+    /// The requested seat if it is free, otherwise the lowest free one
+    pub fn choose_seat(
+        taken: &[u8],
+        max_players: u8,
+        preferred: Option<u8>,
+    ) -> Result<u8, PokerError> {
+        match preferred {
+            Some(seat) if seat >= max_players || taken.contains(&seat) => {
+                Err(PokerError::SeatUnavailable(seat))
+            }
+            Some(seat) => Ok(seat),
+            None => (0..max_players)
+                .find(|seat| !taken.contains(seat))
+                .ok_or(PokerError::TableFull),
+        }
+    }
+
+    pub fn check_buy_in(buy_in: u64, buy_in_min: u64, buy_in_max: u64) -> Result<(), PokerError> {
+        if buy_in < buy_in_min || buy_in > buy_in_max {
+            return Err(PokerError::BuyInOutOfRange {
+                min: buy_in_min,
+                max: buy_in_max,
+            });
+        }
+        Ok(())
+    }
+
+    /// Players at the first hand are dealt in for free. Later arrivals post a
+    /// live big blind with their first hand, or sit out until
+    /// `record_missed_blinds` deals them in on the big blind.
+    pub fn seat_newcomer(
+        player: &mut PokerPlayer,
+        game_running: bool,
+        wait_for_big_blind: bool,
+        big_blind: u64,
+    ) {
+        if !game_running {
+            return;
+        }
+        if wait_for_big_blind {
+            player.status = PlayerStatus::SittingOut;
+            player.missed_big_blind = true;
+            player.waiting_for_big_blind = true;
+        } else {
+            player.live_blind_due = big_blind;
+        }
+    }
+
+    /// `SitOut`: the player keeps their seat and chips but is not dealt in.
+    /// During a hand they have to fold first.
+    pub async fn sit_out(&mut self, player_name: &str) -> Result<(), PokerError> {
+        let mut player = self.load_player(player_name).await?;
+        let in_hand = Self::is_hand_in_progress(self.phase.get())
+            && !player.has_folded
+            && player.status != PlayerStatus::SittingOut;
+        if in_hand {
+            return Err(PokerError::InvalidOperation(
+                "Fold or finish the hand before sitting out".to_string(),
+            ));
+        }
+        player.status = PlayerStatus::SittingOut;
+        self.save_players(&[player]);
+        Ok(())
+    }
+
+    /// Call after `record_missed_blinds`: unseat players who have sat out
+    /// for `max_sit_out_orbits` big blinds. Returns each player removed with
+    /// the chips they leave with, for `PlayerLeft`.
+    pub async fn remove_idle_players(&mut self) -> Vec<(String, u64)> {
+        let Some(max_orbits) = *self.max_sit_out_orbits.get() else {
+            return Vec::new();
+        };
+        let idle: Vec<PokerPlayer> = self
+            .seated_players()
+            .await
+            .into_iter()
+            .filter(|p| Self::sat_out_too_long(p, max_orbits))
+            .collect();
+        for player in &idle {
+            self.players
+                .remove(&player.name)
+                .expect("Failed to remove player");
+        }
+        for player in &idle {
+            self.unseat(&player.name);
+        }
+        idle.into_iter().map(|p| (p.name, p.chips)).collect()
+    }
+
+    /// Drop `player_name` from `player_order` and free their seat
+    fn unseat(&mut self, player_name: &str) {
+        let Some(index) = self
+            .player_order
+            .get()
+            .iter()
+            .position(|name| name == player_name)
+        else {
+            return;
+        };
+        self.player_order.get_mut().remove(index);
+        self.occupied_seats.get_mut().remove(index);
+    }
+
+    pub fn sat_out_too_long(player: &PokerPlayer, max_orbits: u32) -> bool {
+        player.status == PlayerStatus::SittingOut && player.big_blinds_missed >= max_orbits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_sdk::{
+        util::BlockingWait,
+        views::{KeyValueStore, View},
+    };
+
+    /// A table created by "host" in an in-memory store, with `stacks` seated
+    /// in order
+    fn test_table(stacks: &[(&str, u64)]) -> PokerState {
+        table_on_chain(stacks, "test-chain")
+    }
+
+    fn table_on_chain(stacks: &[(&str, u64)], chain_id: &str) -> PokerState {
+        let context = ViewStorageContext::new_unchecked(KeyValueStore::mock(), Vec::new(), ());
+        let mut state = PokerState::load(context)
+            .blocking_wait()
+            .expect("Failed to load state");
+        state
+            .initialize(
+                TableConfig {
+                    creator: "host".to_string(),
+                    buy_in_min: 1,
+                    buy_in_max: 100_000,
+                    ..TableConfig::default()
+                },
+                chain_id,
+                0,
+            )
+            .blocking_wait();
+        for (name, chips) in stacks {
+            state
+                .join_table(name, name, *chips, false, None)
+                .blocking_wait()
+                .expect("Failed to seat player");
+        }
+        state
+    }
+
+    fn test_player(name: &str, chips: u64) -> PokerPlayer {
+        PokerPlayer {
+            address: name.to_string(),
+            name: name.to_string(),
+            chips,
+            position: 0,
+            status: PlayerStatus::Active,
+            current_bet: 0,
+            total_bet_this_hand: 0,
+            hole_cards_commitment: None,
+            hole_cards: None,
+            has_folded: false,
+            is_all_in: false,
+            last_action: None,
+            consecutive_timeouts: 0,
+            bounty_amount: 0,
+            bounties_won: 0,
+            disconnected_at_ms: None,
+            missed_blind_due: 0,
+            straddle_posted: 0,
+            wants_rabbit_hunt: false,
+            auto_rebuy_threshold: None,
+            auto_rebuy_to: None,
+            auto_rebuy_reserve: 0,
+            rebuys_used: 0,
+            up_cards: Vec::new(),
+            has_drawn: false,
+            auto_muck: false,
+            missed_small_blind: false,
+            missed_big_blind: false,
+            live_blind_due: 0,
+            waiting_for_big_blind: false,
+            big_blinds_missed: 0,
+        }
+    }
+
+    #[test]
+    fn test_bomb_pot_all_players_post_ante() {
+        let mut players = vec![
+            test_player("alice", 1000),
+            test_player("bob", 1000),
+            test_player("carol", 1000),
+        ];
+
+        let collected = PokerState::collect_bomb_pot_antes(&mut players, 50);
+
+        assert_eq!(collected, 150);
+        for player in &players {
+            assert_eq!(player.chips, 950);
+            assert_eq!(player.total_bet_this_hand, 50);
+            assert_eq!(player.current_bet, 0);
+            assert_eq!(player.status, PlayerStatus::Active);
+        }
+    }
+
+    #[test]
+    fn test_bomb_pot_short_stack_goes_all_in() {
+        let mut players = vec![test_player("alice", 1000), test_player("bob", 30)];
+
+        let collected = PokerState::collect_bomb_pot_antes(&mut players, 50);
+
+        assert_eq!(collected, 80);
+        assert_eq!(players[0].chips, 950);
+        assert_eq!(players[1].chips, 0);
+        assert!(players[1].is_all_in);
+        assert_eq!(players[1].status, PlayerStatus::AllIn);
+    }
+
+    #[test]
+    fn test_bomb_pot_skips_sitting_out_and_pre_flop() {
+        let mut sitting_out = test_player("carol", 1000);
+        sitting_out.status = PlayerStatus::SittingOut;
+        let mut players = vec![test_player("alice", 1000), sitting_out];
+
+        let collected = PokerState::collect_bomb_pot_antes(&mut players, 50);
+
+        assert_eq!(collected, 50);
+        assert_eq!(players[1].chips, 1000);
+        assert_eq!(PokerState::first_betting_phase(true), GamePhase::Flop);
+        assert_eq!(PokerState::first_betting_phase(false), GamePhase::PreFlop);
+    }
+
+    #[test]
+    fn test_initiate_bomb_pot_deals_to_the_flop() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 1000), ("carol", 30)]);
+        assert!(matches!(
+            state.initiate_bomb_pot("alice", 50).blocking_wait(),
+            Err(PokerError::Unauthorized(_))
+        ));
+
+        let total_pot = state.initiate_bomb_pot("host", 50).blocking_wait().unwrap();
+        assert_eq!(total_pot, 130);
+        assert_eq!(*state.phase.get(), GamePhase::Flop);
+        assert!(*state.bomb_pot_this_hand.get());
+        assert_eq!(*state.hand_number.get(), 1);
+        assert!(state.big_blind_player.get().is_some());
+
+        // Everyone is dealt in, and the flop comes after a burn card
+        let players = state.seated_players().blocking_wait();
+        let mut cards: Vec<Card> = players
+            .iter()
+            .flat_map(|p| p.hole_cards.clone().expect("Player was dealt in"))
+            .collect();
+        assert_eq!(cards.len(), 6);
+        assert_eq!(state.community_cards.get().len(), 3);
+        assert_eq!(state.burn_cards.get().len(), 1);
+        cards.extend(state.community_cards.get().iter().copied());
+        cards.extend(state.burn_cards.get().iter().copied());
+        cards.extend(state.deck.get().iter().copied());
+        cards.sort_by_key(|card| (card.rank, card.suit as u8));
+        cards.dedup();
+        assert_eq!(cards.len(), 52);
+
+        // Carol is all-in for her short ante and her starting stack is capped
+        assert!(players[2].is_all_in);
+        assert_eq!(
+            state.max_winnable_from("alice", "carol").blocking_wait(),
+            30
+        );
+        assert_eq!(*state.chips_at_hand_start.get(), 2030);
+        assert!(state.verify_chip_conservation(2030).blocking_wait());
+
+        // Post-flop betting opens with normal rules
+        let round = state.betting_round().blocking_wait();
+        let to_act = round
+            .to_act()
+            .expect("Someone acts on the flop")
+            .to_string();
+        assert_ne!(to_act, "carol");
+        let actions = state.legal_actions(&to_act).blocking_wait().unwrap();
+        assert!(actions.can_check);
+        assert_eq!(actions.bet_range, Some((20, 950)));
+    }
+
+    #[test]
+    fn test_single_timeout_keeps_player_active() {
+        let mut player = test_player("alice", 1000);
+
+        assert!(!PokerState::register_timeout(&mut player, 2));
+        assert_eq!(player.consecutive_timeouts, 1);
+        assert_eq!(player.status, PlayerStatus::Active);
+    }
+
+    #[test]
+    fn test_two_timeouts_sit_player_out() {
+        let mut player = test_player("alice", 1000);
+
+        PokerState::register_timeout(&mut player, 2);
+        assert!(PokerState::register_timeout(&mut player, 2));
+        assert_eq!(player.status, PlayerStatus::SittingOut);
+        // Already sitting out, so no second status change is reported
+        assert!(!PokerState::register_timeout(&mut player, 2));
+    }
+
+    #[test]
+    fn test_acting_resets_timeout_streak() {
+        let mut player = test_player("alice", 1000);
+
+        PokerState::register_timeout(&mut player, 2);
+        PokerState::register_action(&mut player);
+        assert!(!PokerState::register_timeout(&mut player, 2));
+        assert_eq!(player.consecutive_timeouts, 1);
+        assert_eq!(player.status, PlayerStatus::Active);
+    }
+
+    #[test]
+    fn test_custom_timeout_threshold() {
+        let config = TableConfig {
+            max_consecutive_timeouts: 3,
+            ..TableConfig::default()
+        };
+        let mut player = test_player("alice", 1000);
+
+        assert!(!PokerState::register_timeout(
+            &mut player,
+            config.max_consecutive_timeouts
+        ));
+        assert!(!PokerState::register_timeout(
+            &mut player,
+            config.max_consecutive_timeouts
+        ));
+        assert!(PokerState::register_timeout(
+            &mut player,
+            config.max_consecutive_timeouts
+        ));
+        assert_eq!(TableConfig::default().max_consecutive_timeouts, 2);
+    }
+
+    fn test_proposal() -> RuleProposal {
+        RuleProposal {
+            id: 0,
+            proposer: "alice".to_string(),
+            change: TableConfigPatch {
+                big_blind: Some(40),
+                ..TableConfigPatch::default()
+            },
+            votes_for: 0,
+            votes_against: 0,
+            expires_at_hand: 10,
+            voters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_rule_vote_majority_approves() {
+        let mut proposal = test_proposal();
+        proposal.record_vote("alice", true).unwrap();
+        proposal.record_vote("bob", true).unwrap();
+        assert_eq!(proposal.outcome(4, 8), ProposalOutcome::Pending);
+
+        proposal.record_vote("carol", true).unwrap();
+        assert_eq!(proposal.outcome(4, 8), ProposalOutcome::Approved);
+        assert!(proposal.record_vote("carol", false).is_err());
+    }
+
+    #[test]
+    fn test_rule_vote_majority_rejects() {
+        let mut proposal = test_proposal();
+        proposal.record_vote("alice", true).unwrap();
+        proposal.record_vote("bob", false).unwrap();
+        proposal.record_vote("carol", false).unwrap();
+
+        assert_eq!(proposal.outcome(3, 8), ProposalOutcome::Rejected);
+    }
+
+    #[test]
+    fn test_rule_vote_tie_is_rejected() {
+        let mut proposal = test_proposal();
+        proposal.record_vote("alice", true).unwrap();
+        proposal.record_vote("bob", true).unwrap();
+        proposal.record_vote("carol", false).unwrap();
+        proposal.record_vote("dave", false).unwrap();
+
+        assert_eq!(proposal.outcome(4, 8), ProposalOutcome::Rejected);
+    }
+
+    #[test]
+    fn test_rule_vote_expires() {
+        let mut proposal = test_proposal();
+        proposal.record_vote("alice", true).unwrap();
+
+        assert_eq!(proposal.outcome(4, 9), ProposalOutcome::Pending);
+        assert_eq!(proposal.outcome(4, 10), ProposalOutcome::Expired);
+    }
+
+    #[test]
+    fn test_standard_bounty_goes_to_eliminator() {
+        let mut winner = test_player("alice", 2000);
+        let mut loser = test_player("bob", 0);
+        loser.bounty_amount = 100;
+
+        let (paid, to_pool) = PokerState::split_bounty(&mut winner, &mut loser, false);
+
+        assert_eq!((paid, to_pool), (100, 0));
+        assert_eq!(winner.bounties_won, 100);
+        assert_eq!(loser.bounty_amount, 0);
+    }
+
+    #[test]
+    fn test_progressive_bounty_feeds_pool() {
+        let mut winner = test_player("alice", 2000);
+        let mut loser = test_player("bob", 0);
+        loser.bounty_amount = 101;
+
+        let (paid, to_pool) = PokerState::split_bounty(&mut winner, &mut loser, true);
+
+        assert_eq!(paid, 51);
+        assert_eq!(to_pool, 50);
+        assert_eq!(winner.bounties_won, 51);
+    }
+
+    #[test]
+    fn test_double_knockout_collects_both_bounties() {
+        let mut winner = test_player("alice", 3000);
+        let mut first = test_player("bob", 0);
+        let mut second = test_player("carol", 0);
+        first.bounty_amount = 100;
+        second.bounty_amount = 60;
+
+        let (first_paid, first_pool) = PokerState::split_bounty(&mut winner, &mut first, true);
+        let (second_paid, second_pool) = PokerState::split_bounty(&mut winner, &mut second, true);
+
+        assert_eq!(first_paid + second_paid, 80);
+        assert_eq!(first_pool + second_pool, 80);
+        assert_eq!(winner.bounties_won, 80);
+    }
+
+    #[test]
+    fn test_consecutive_wins_extend_streak() {
+        let mut entry = LeaderboardData::default();
+        assert_eq!(entry.current_streak_type, StreakType::Neutral);
+
+        entry.record_hand(true);
+        entry.record_hand(true);
+        entry.record_hand(true);
+
+        assert_eq!(entry.win_streak, 3);
+        assert_eq!(entry.current_streak_type, StreakType::Win);
+        assert_eq!(entry.current_streak_length, 3);
+        assert_eq!(entry.longest_win_streak, 3);
+    }
+
+    #[test]
+    fn test_loss_resets_win_streak() {
+        let mut entry = LeaderboardData::default();
+        entry.record_hand(true);
+        entry.record_hand(true);
+
+        entry.record_hand(false);
+
+        assert_eq!(entry.win_streak, 0);
+        assert_eq!(entry.loss_streak, 1);
+        assert_eq!(entry.current_streak_type, StreakType::Loss);
+        assert_eq!(entry.current_streak_length, 1);
+    }
+
+    #[test]
+    fn test_streak_records_never_decrease() {
+        let mut entry = LeaderboardData::default();
+        for won in [true, true, true, false, false, true, false] {
+            entry.record_hand(won);
+        }
+
+        assert_eq!(entry.longest_win_streak, 3);
+        assert_eq!(entry.longest_loss_streak, 2);
+        assert_eq!(entry.current_streak_length, 1);
+    }
+
+    #[test]
+    fn test_table_stakes_caps_winnings_by_starting_stack() {
+        // Alice started with 500 and has since won up to 900 in a side pot;
+        // against Bob's 200 starting stack she can still only win 200
+        let alice_start = 500;
+        let bob_start = 200;
+
+        assert_eq!(PokerState::table_stakes_cap(alice_start, bob_start), 200);
+        assert_eq!(PokerState::table_stakes_cap(bob_start, alice_start), 200);
+    }
+
+    #[test]
+    fn test_equal_stacks_contest_full_pot() {
+        assert_eq!(PokerState::table_stakes_cap(1000, 1000), 1000);
+    }
+
+    #[test]
+    fn test_chips_only_added_between_hands() {
+        assert!(!PokerState::is_hand_in_progress(
+            &GamePhase::WaitingForPlayers
+        ));
+        assert!(!PokerState::is_hand_in_progress(&GamePhase::HandComplete));
+        assert!(PokerState::is_hand_in_progress(&GamePhase::Turn));
+    }
+
+    fn participation(player: &str, folded_preflop: bool, bet_or_raised: bool) -> HandParticipation {
+        HandParticipation {
+            player: player.to_string(),
+            folded_preflop,
+            bet_or_raised,
+        }
+    }
+
+    #[test]
+    fn test_pair_that_always_checks_down_scores_high() {
+        let mut stats = PairStats::default();
+        for _ in 0..20 {
+            stats.record_hand(
+                &participation("alice", false, false),
+                &participation("bob", false, false),
+            );
+        }
+
+        assert_eq!(stats.hands_in_pot_together, 20);
+        assert_eq!(stats.times_neither_bet_raised, 20);
+        assert_eq!(stats.suspicion_score(), 1.0);
+    }
+
+    #[test]
+    fn test_aggressive_pair_scores_zero() {
+        let mut stats = PairStats::default();
+        for i in 0..20 {
+            let alice_bets = i % 2 == 0;
+            stats.record_hand(
+                &participation("alice", false, alice_bets),
+                &participation("bob", false, !alice_bets),
+            );
+        }
+
+        assert_eq!(stats.times_neither_bet_raised, 0);
+        assert_eq!(stats.suspicion_score(), 0.0);
+    }
+
+    #[test]
+    fn test_small_sample_is_discounted() {
+        let mut stats = PairStats::default();
+        for _ in 0..5 {
+            stats.record_hand(
+                &participation("alice", false, false),
+                &participation("bob", false, false),
+            );
+        }
+        stats.record_hand(
+            &participation("alice", true, false),
+            &participation("bob", false, true),
+        );
+
+        assert_eq!(stats.times_one_folded_preflop, 1);
+        assert_eq!(stats.suspicion_score(), 0.25);
+        assert_eq!(
+            PokerState::pair_key("bob", "alice"),
+            PokerState::pair_key("alice", "bob")
+        );
+    }
+
+    fn test_flag(hand_number: u64) -> FlaggedHand {
+        FlaggedHand {
+            hand_number,
+            reason: "suspected collusion".to_string(),
+            flagged_by: "bob".to_string(),
+            timestamp_ms: 1_000,
+            resolved: false,
+            resolution: None,
+        }
+    }
+
+    #[test]
+    fn test_flag_and_resolve_hand() {
+        let mut flags = Vec::new();
+        PokerState::push_flag(&mut flags, test_flag(7)).unwrap();
+
+        PokerState::resolve_flag(&mut flags, 7, "no issue found".to_string()).unwrap();
+
+        assert!(flags[0].resolved);
+        assert_eq!(flags[0].resolution.as_deref(), Some("no issue found"));
+        assert!(PokerState::resolve_flag(&mut flags, 7, "again".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_only_table_creator_resolves_flags() {
+        assert!(
+            PokerState::require_table_creator("alice", "alice", "resolve flagged hands").is_ok()
+        );
+        let err = PokerState::require_table_creator("alice", "bob", "resolve flagged hands");
+        assert_eq!(
+            err,
+            Err(PokerError::Unauthorized(
+                "Only the table creator can resolve flagged hands".to_string()
+            ))
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Only the table creator can resolve flagged hands"
+        );
+    }
+
+    #[test]
+    fn test_unresolved_flag_cap() {
+        let mut flags = Vec::new();
+        for hand in 1..=PokerState::MAX_UNRESOLVED_FLAGS as u64 {
+            PokerState::push_flag(&mut flags, test_flag(hand)).unwrap();
+        }
+        assert!(PokerState::push_flag(&mut flags, test_flag(51)).is_err());
+
+        // Resolving one frees a slot
+        PokerState::resolve_flag(&mut flags, 1, "resolved".to_string()).unwrap();
+        assert!(PokerState::push_flag(&mut flags, test_flag(51)).is_ok());
+    }
+
+    #[test]
+    fn test_stack_depth_category_boundaries() {
+        use StackDepthCategory::*;
+        let depth = |chips| PokerState::compute_stack_depth_category(chips, 20);
+
+        assert_eq!(depth(2000), DeepStack);
+        assert_eq!(depth(1999), MidStack);
+        assert_eq!(depth(800), MidStack);
+        assert_eq!(depth(799), ShortStack);
+        assert_eq!(depth(400), ShortStack);
+        assert_eq!(depth(399), CriticalStack);
+        assert_eq!(depth(200), CriticalStack);
+        assert_eq!(depth(199), PushFoldStack);
+        assert_eq!(test_player("alice", 4000).stack_depth(20), DeepStack);
+    }
+
+    #[test]
+    fn test_m_ratio_and_q_ratio() {
+        // 3000 chips, 50/100 blinds, 10 ante at 10 players: 3000 / 250 = 12
+        assert_eq!(PokerState::compute_m_ratio(3000, 50, 100, 10, 10), 12.0);
+        assert_eq!(PokerState::compute_m_ratio(3000, 50, 100, 0, 10), 20.0);
+        assert_eq!(test_player("alice", 1500).m_ratio(50, 100, 0, 6), 10.0);
+        assert_eq!(PokerState::compute_q_ratio(3000, 2000), 1.5);
+    }
+
+    fn top_three_tournament(buy_in: u64, rake_percentage: u8) -> TournamentConfig {
+        TournamentConfig {
+            buy_in,
+            rake_percentage,
+            payout_structure: vec![
+                TournamentPayout {
+                    rank: 1,
+                    percentage: 50,
+                },
+                TournamentPayout {
+                    rank: 2,
+                    percentage: 30,
+                },
+                TournamentPayout {
+                    rank: 3,
+                    percentage: 20,
+                },
+            ],
+            players: 4,
+            starting_stack: 1500,
+        }
+    }
+
+    fn pool(config: &TournamentConfig, finish: &[(String, u8)]) -> u64 {
+        config.buy_in * finish.len() as u64
+    }
+
+    fn rankings(entries: &[(&str, u8)]) -> Vec<(String, u8)> {
+        entries
+            .iter()
+            .map(|(name, rank)| (name.to_string(), *rank))
+            .collect()
+    }
+
+    #[test]
+    fn test_standard_top_three_payout() {
+        let config = top_three_tournament(100, 10);
+        let finish = rankings(&[("dave", 4), ("alice", 1), ("carol", 3), ("bob", 2)]);
+
+        // 400 pool, 40 rake
+        assert_eq!(
+            PokerState::compute_tournament_prizes(&config, pool(&config, &finish), &finish),
+            vec![
+                ("alice".to_string(), 180),
+                ("bob".to_string(), 108),
+                ("carol".to_string(), 72),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chopped_deal_splits_remaining_payouts() {
+        let config = top_three_tournament(100, 0);
+        let finish = rankings(&[("alice", 1), ("bob", 1), ("carol", 1), ("dave", 4)]);
+
+        assert_eq!(
+            PokerState::compute_tournament_prizes(&config, pool(&config, &finish), &finish),
+            vec![
+                ("alice".to_string(), 134),
+                ("bob".to_string(), 133),
+                ("carol".to_string(), 133),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_odd_chip_goes_to_higher_finisher() {
+        let config = TournamentConfig {
+            buy_in: 33,
+            rake_percentage: 0,
+            payout_structure: vec![
+                TournamentPayout {
+                    rank: 1,
+                    percentage: 50,
+                },
+                TournamentPayout {
+                    rank: 2,
+                    percentage: 50,
+                },
+            ],
+            players: 3,
+            starting_stack: 1500,
+        };
+        let finish = rankings(&[("bob", 2), ("alice", 1), ("carol", 3)]);
+
+        assert_eq!(
+            PokerState::compute_tournament_prizes(&config, pool(&config, &finish), &finish),
+            vec![("alice".to_string(), 50), ("bob".to_string(), 49)]
+        );
+    }
+
+    #[test]
+    fn test_final_rankings_from_elimination_order() {
+        let eliminated = vec!["carol".to_string(), "bob".to_string()];
+        assert_eq!(
+            PokerState::final_rankings("alice", &eliminated),
+            rankings(&[("alice", 1), ("bob", 2), ("carol", 3)])
+        );
+    }
+
+    fn horse_schedule() -> MixedGameSchedule {
+        MixedGameSchedule {
+            variants: vec![
+                GameVariant::TexasHoldem,
+                GameVariant::Omaha,
+                GameVariant::Razz,
+                GameVariant::SevenCardStud,
+                GameVariant::SevenCardStudHiLo,
+            ],
+            hands_per_rotation: 8,
+            rotate_by_orbit: false,
+        }
+    }
+
+    #[test]
+    fn test_mixed_game_rotates_in_order() {
+        let schedule = horse_schedule();
+        let mut index = 0;
+        let mut played = Vec::new();
+        for hand_number in 1..=48 {
+            if let Some(next) = PokerState::next_variant_index(&schedule, index, hand_number, 6) {
+                index = next;
+            }
+            played.push(schedule.variants[index as usize]);
+        }
+
+        assert_eq!(played[6], GameVariant::TexasHoldem);
+        assert_eq!(played[7], GameVariant::Omaha);
+        assert_eq!(played[15], GameVariant::Razz);
+        assert_eq!(played[23], GameVariant::SevenCardStud);
+        assert_eq!(played[31], GameVariant::SevenCardStudHiLo);
+        // Wraps back to Hold'em
+        assert_eq!(played[39], GameVariant::TexasHoldem);
+    }
+
+    #[test]
+    fn test_next_variant_countdown() {
+        let schedule = horse_schedule();
+        assert_eq!(PokerState::hands_until_variant_change(&schedule, 8, 6), 8);
+        assert_eq!(PokerState::hands_until_variant_change(&schedule, 13, 6), 3);
+        assert_eq!(PokerState::hands_until_variant_change(&schedule, 15, 6), 1);
+    }
+
+    #[test]
+    fn test_mixed_game_rotates_by_orbit() {
+        let schedule = MixedGameSchedule {
+            hands_per_rotation: 2,
+            rotate_by_orbit: true,
+            ..horse_schedule()
+        };
+        // Two orbits of a six-handed table
+        assert_eq!(schedule.hands_per_change(6), 12);
+        assert_eq!(PokerState::next_variant_index(&schedule, 0, 8, 6), None);
+        assert_eq!(PokerState::next_variant_index(&schedule, 0, 12, 6), Some(1));
+        assert_eq!(PokerState::hands_until_variant_change(&schedule, 13, 6), 11);
+
+        let empty = MixedGameSchedule {
+            variants: Vec::new(),
+            ..schedule
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_dealing_follows_variant() {
+        let mut deck = PokerState::shuffle_deck([7u8; 32]);
+        let omaha = PokerState::deal_hole_cards(&mut deck, 3, GameVariant::Omaha);
+        assert!(omaha.iter().all(|hand| hand.len() == 4));
+        assert_eq!(deck.len(), 40);
+
+        let stud = PokerState::deal_hole_cards(&mut deck, 3, GameVariant::SevenCardStud);
+        assert!(stud.iter().all(|hand| hand.len() == 3));
+        assert!(!GameVariant::Razz.uses_community_cards());
+        assert_eq!(
+            PokerState::deal_hole_cards(&mut deck, 2, GameVariant::TexasHoldem)[0].len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_sub_minimum_all_in_does_not_reopen() {
+        // Facing 100 with a 100 minimum raise: 150 is a raise, but short
+        let mut player = test_player("alice", 150);
+        let (amount, reopens) = PokerState::apply_all_in(&mut player, 100, 100).unwrap();
+        assert_eq!(amount, 150);
+        assert!(!reopens);
+
+        // Less than the bet is a partial call
+        let mut short = test_player("bob", 60);
+        assert_eq!(
+            PokerState::apply_all_in(&mut short, 100, 100),
+            Ok((60, false))
+        );
+        assert_eq!(short.status, PlayerStatus::AllIn);
+    }
+
+    #[test]
+    fn test_full_raise_all_in_reopens_betting() {
+        let mut player = test_player("alice", 300);
+        assert_eq!(
+            PokerState::apply_all_in(&mut player, 100, 100),
+            Ok((300, true))
+        );
+        assert_eq!(player.current_bet, 300);
+        assert_eq!(player.chips, 0);
+    }
+
+    #[test]
+    fn test_double_all_in_is_rejected() {
+        let mut players = vec![test_player("alice", 300), test_player("bob", 500)];
+        PokerState::apply_all_in(&mut players[0], 0, 20).unwrap();
+
+        assert!(!PokerState::can_go_all_in(&players[0]));
+        assert!(matches!(
+            PokerState::apply_all_in(&mut players[0], 300, 20),
+            Err(PokerError::InvalidAction {
+                action: PlayerAction::AllIn,
+                ..
+            })
+        ));
+        assert_eq!(PokerState::all_in_count(&players), 1);
+    }
+
+    #[test]
+    fn test_biggest_pot_tracks_across_hands() {
+        let mut biggest = 0;
+        for prize in [120, 800, 300, 801, 50] {
+            biggest = PokerState::updated_biggest_pot(biggest, prize);
+        }
+        assert_eq!(biggest, 801);
+    }
+
+    #[test]
+    fn test_no_orphaned_chips() {
+        let mut departed = vec![test_player("alice", 0)];
+        departed[0].status = PlayerStatus::SittingOut;
+        assert_eq!(PokerState::collect_orphaned_chips(&mut departed), 0);
+        assert_eq!(PokerState::collect_orphaned_chips(&mut []), 0);
+    }
+
+    #[test]
+    fn test_orphan_with_pending_chips_is_recovered() {
+        let mut departed = vec![test_player("alice", 750), test_player("bob", 300)];
+        departed[0].status = PlayerStatus::Folded;
+
+        // Bob left while still marked active, so his stack is not touched
+        assert_eq!(PokerState::collect_orphaned_chips(&mut departed), 750);
+        assert_eq!(departed[0].chips, 0);
+        assert_eq!(departed[1].chips, 300);
+    }
+
+    #[test]
+    fn test_orphaned_chips_split_by_stack_size() {
+        let stacks = vec![
+            ("alice".to_string(), 1000),
+            ("bob".to_string(), 500),
+            ("carol".to_string(), 500),
+        ];
+        assert_eq!(
+            PokerState::distribute_proportionally(401, &stacks),
+            vec![
+                ("alice".to_string(), 201),
+                ("bob".to_string(), 100),
+                ("carol".to_string(), 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reconnect_within_grace_period_keeps_hand() {
+        let mut player = test_player("alice", 1000);
+        player.disconnected_at_ms = Some(10_000);
+
+        assert_eq!(
+            PokerState::disconnect_auto_action(&mut player, 35_000, 30_000, 100),
+            None
+        );
+        assert!(PokerState::reconnect(&mut player));
+        assert_eq!(
+            PokerState::disconnect_auto_action(&mut player, 90_000, 30_000, 100),
+            None
+        );
+        assert!(!player.has_folded);
+    }
+
+    #[test]
+    fn test_reconnect_after_grace_period_is_already_folded() {
+        let mut player = test_player("alice", 1000);
+        player.disconnected_at_ms = Some(10_000);
+
+        assert_eq!(
+            PokerState::disconnect_auto_action(&mut player, 40_001, 30_000, 100),
+            Some(PlayerAction::Fold)
+        );
+        assert!(PokerState::reconnect(&mut player));
+        assert!(player.has_folded);
+        assert_eq!(player.status, PlayerStatus::Folded);
+    }
+
+    #[test]
+    fn test_disconnected_player_checks_when_free() {
+        let mut player = test_player("alice", 1000);
+        player.disconnected_at_ms = Some(0);
+
+        assert_eq!(
+            PokerState::disconnect_auto_action(&mut player, 60_000, 30_000, 0),
+            Some(PlayerAction::Check)
+        );
+        assert!(!player.has_folded);
+    }
+
+    #[test]
+    fn test_no_dead_money_without_antes() {
+        let mut players = vec![test_player("alice", 1000), test_player("bob", 1000)];
+        assert_eq!(PokerState::compute_dead_money(&mut players, 0), 0);
+        assert_eq!(players[0].chips, 1000);
+    }
+
+    #[test]
+    fn test_antes_collected_from_every_player() {
+        let mut players = vec![
+            test_player("alice", 1000),
+            test_player("bob", 1000),
+            test_player("carol", 5),
+        ];
+        assert_eq!(PokerState::compute_dead_money(&mut players, 10), 25);
+        assert_eq!(players[0].chips, 990);
+        assert_eq!(players[0].current_bet, 0);
+        assert!(players[2].is_all_in);
+    }
+
+    #[test]
+    fn test_straddle_and_antes() {
+        let mut players = vec![test_player("alice", 1000), test_player("bob", 1000)];
+        players[1].straddle_posted = 40;
+
+        assert_eq!(PokerState::compute_dead_money(&mut players, 5), 50);
+        assert_eq!(players[1].chips, 955);
+        assert_eq!(players[1].current_bet, 40);
+        assert_eq!(players[1].total_bet_this_hand, 45);
+    }
+
+    #[test]
+    fn test_missed_blind_and_straddle() {
+        let mut players = vec![test_player("alice", 1000), test_player("bob", 1000)];
+        players[0].missed_blind_due = 10;
+        players[1].straddle_posted = 40;
+
+        assert_eq!(PokerState::compute_dead_money(&mut players, 0), 50);
+        assert_eq!(players[0].chips, 990);
+        // The dead blind does not count toward alice's bet
+        assert_eq!(players[0].current_bet, 0);
+        assert_eq!(players[0].missed_blind_due, 0);
+    }
+
+    fn deal_from(deck: &mut Vec<Card>, count: usize) -> Vec<Card> {
+        deck.split_off(deck.len() - count)
+    }
+
+    #[test]
+    fn test_intact_deck_mid_hand_is_valid() {
+        let mut deck = PokerState::create_deck();
+        let hole_cards = vec![deal_from(&mut deck, 2), deal_from(&mut deck, 2)];
+        let board = deal_from(&mut deck, 3);
+
+        let result = PokerState::validate_deck_integrity(&deck, &board, &hole_cards);
+        assert!(result.valid);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_single_duplicate_card_detected() {
+        let mut deck = PokerState::create_deck();
+        let replaced = deck[0];
+        deck[0] = deck[1];
+
+        let result = PokerState::validate_deck_integrity(&deck, &[], &[]);
+        assert!(!result.valid);
+        assert_eq!(
+            result.errors,
+            vec![
+                DeckError::DuplicateCard(deck[1]),
+                DeckError::MissingCard(replaced)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_suit_detected() {
+        let deck: Vec<Card> = PokerState::create_deck()
+            .into_iter()
+            .filter(|card| card.suit != Suit::Spades)
+            .collect();
+
+        let result = PokerState::validate_deck_integrity(&deck, &[], &[]);
+        assert!(!result.valid);
+        assert_eq!(result.errors[0], DeckError::WrongDeckSize(39));
+        let missing = result
+            .errors
+            .iter()
+            .filter(
+                |error| matches!(error, DeckError::MissingCard(card) if card.suit == Suit::Spades),
+            )
+            .count();
+        assert_eq!(missing, 13);
+    }
+
+    #[test]
+    fn test_corruption_after_partial_deal_voids_hand() {
+        let mut deck = PokerState::create_deck();
+        let hole_cards = vec![deal_from(&mut deck, 2), deal_from(&mut deck, 2)];
+        // A bad shuffle put a dealt card back on top of the deck
+        let mut board = deal_from(&mut deck, 3);
+        board[0] = hole_cards[0][0];
+
+        let result = PokerState::validate_deck_integrity(&deck, &board, &hole_cards);
+        assert!(!result.valid);
+        assert!(result
+            .errors
+            .contains(&DeckError::DuplicateCard(hole_cards[0][0])));
+
+        let mut players = vec![test_player("alice", 900), test_player("bob", 0)];
+        players[0].total_bet_this_hand = 100;
+        players[0].status = PlayerStatus::Folded;
+        players[0].has_folded = true;
+        players[1].total_bet_this_hand = 1000;
+        players[1].status = PlayerStatus::AllIn;
+        players[1].is_all_in = true;
+
+        let refunds = PokerState::refund_hand_bets(&mut players);
+        assert_eq!(
+            refunds,
+            vec![("alice".to_string(), 100), ("bob".to_string(), 1000)]
+        );
+        assert_eq!(players[0].chips, 1000);
+        assert_eq!(players[1].chips, 1000);
+        assert!(players
+            .iter()
+            .all(|p| p.status == PlayerStatus::Active && p.total_bet_this_hand == 0));
+    }
+
+    fn leaderboard(chips: &[(&str, u64)]) -> Vec<(String, LeaderboardData)> {
+        chips
+            .iter()
+            .map(|(name, chips)| {
+                let entry = LeaderboardData {
+                    chips: *chips,
+                    ..Default::default()
+                };
+                (name.to_string(), entry)
+            })
+            .collect()
+    }
+
+    fn names(entries: &[(String, LeaderboardData)]) -> Vec<&str> {
+        entries.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_leaderboard_rank_with_ties() {
+        let entries = leaderboard(&[("alice", 500), ("bob", 900), ("carol", 500), ("dave", 100)]);
+        assert_eq!(PokerState::rank_by_chips(&entries, "bob"), Some(1));
+        assert_eq!(PokerState::rank_by_chips(&entries, "alice"), Some(2));
+        assert_eq!(PokerState::rank_by_chips(&entries, "carol"), Some(2));
+        assert_eq!(PokerState::rank_by_chips(&entries, "dave"), Some(4));
+        assert_eq!(PokerState::rank_by_chips(&entries, "erin"), None);
+    }
+
+    #[test]
+    fn test_leaderboard_top_n_ordering() {
+        let entries = leaderboard(&[("carol", 500), ("dave", 100), ("bob", 900), ("alice", 500)]);
+        let top = PokerState::leaderboard_page_of(entries.clone(), 0, 3);
+        assert_eq!(names(&top), vec!["bob", "alice", "carol"]);
+        assert_eq!(PokerState::leaderboard_page_of(entries, 0, 10).len(), 4);
+    }
+
+    #[test]
+    fn test_leaderboard_pagination_boundaries() {
+        let entries = leaderboard(&[("a", 5), ("b", 4), ("c", 3), ("d", 2), ("e", 1)]);
+        assert_eq!(
+            names(&PokerState::leaderboard_page_of(entries.clone(), 0, 2)),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            names(&PokerState::leaderboard_page_of(entries.clone(), 2, 2)),
+            vec!["e"]
+        );
+        assert!(PokerState::leaderboard_page_of(entries.clone(), 3, 2).is_empty());
+        assert!(PokerState::leaderboard_page_of(entries.clone(), 0, 0).is_empty());
+        assert!(PokerState::leaderboard_page_of(entries, usize::MAX, 2).is_empty());
+    }
+
+    #[test]
+    fn test_stale_detection_threshold() {
+        let phase = GamePhase::PreFlop;
+        assert!(!PokerState::is_stale(&phase, 1_000, 61_000, 60_000));
+        assert!(PokerState::is_stale(&phase, 1_000, 61_001, 60_000));
+        // Clock skew never makes a game stale
+        assert!(!PokerState::is_stale(&phase, 5_000, 1_000, 0));
+    }
+
+    #[test]
+    fn test_waiting_table_is_never_stale() {
+        for phase in [GamePhase::WaitingForPlayers, GamePhase::HandComplete] {
+            assert!(!PokerState::is_stale(&phase, 0, u64::MAX, 60_000));
+        }
+    }
+
+    #[test]
+    fn test_stale_reset_refunds_every_contributor() {
+        let mut players = vec![
+            test_player("alice", 800),
+            test_player("bob", 950),
+            test_player("carol", 1000),
+        ];
+        players[0].current_bet = 200;
+        players[0].total_bet_this_hand = 200;
+        players[1].current_bet = 50;
+        players[1].total_bet_this_hand = 50;
+
+        let refunds = PokerState::refund_hand_bets(&mut players);
+        assert_eq!(
+            refunds,
+            vec![("alice".to_string(), 200), ("bob".to_string(), 50)]
+        );
+        assert!(players
+            .iter()
+            .all(|p| p.chips == 1000 && p.current_bet == 0));
+    }
+
+    #[test]
+    fn test_seating_errors() {
+        let order = vec!["alice".to_string(), "bob".to_string()];
+        assert!(PokerState::check_seat_available(&order, 3, "carol").is_ok());
+        assert_eq!(
+            PokerState::check_seat_available(&order, 3, "bob"),
+            Err(PokerError::DuplicatePlayer("bob".to_string()))
+        );
+        assert_eq!(
+            PokerState::check_seat_available(&order, 2, "carol"),
+            Err(PokerError::TableFull)
+        );
+    }
+
+    #[test]
+    fn test_turn_and_table_errors() {
+        let order = vec!["alice".to_string(), "bob".to_string()];
+        let seats = [0, 4];
+        assert!(PokerState::check_turn(&order, &seats, 4, "bob").is_ok());
+        assert_eq!(
+            PokerState::check_turn(&order, &seats, 0, "bob"),
+            Err(PokerError::NotPlayersTurn("bob".to_string()))
+        );
+        assert_eq!(
+            PokerState::check_turn(&order, &seats, 1, "bob"),
+            Err(PokerError::NotPlayersTurn("bob".to_string()))
+        );
+
+        assert!(PokerState::check_table_id("table-1", "table-1").is_ok());
+        assert_eq!(
+            PokerState::check_table_id("table-1", "table-2"),
+            Err(PokerError::TableNotFound("table-2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_chip_and_deck_errors() {
+        let player = test_player("alice", 100);
+        assert!(PokerState::require_chips(&player, 100).is_ok());
+        assert_eq!(
+            PokerState::require_chips(&player, 150),
+            Err(PokerError::InsufficientChips {
+                needed: 150,
+                available: 100
+            })
+        );
+
+        let deck = PokerState::create_deck();
+        assert!(PokerState::validate_deck_integrity(&deck, &[], &[])
+            .ensure_valid()
+            .is_ok());
+        assert_eq!(
+            PokerState::validate_deck_integrity(&deck[1..], &[], &[]).ensure_valid(),
+            Err(PokerError::DeckCorrupted)
+        );
+    }
+
+    #[test]
+    fn test_poker_error_messages() {
+        assert_eq!(
+            PokerError::PlayerNotFound("alice".to_string()).to_string(),
+            "Player alice not found"
+        );
+        assert_eq!(
+            PokerError::InvalidPhase {
+                expected: GamePhase::WaitingForPlayers,
+                actual: GamePhase::Flop,
+            }
+            .to_string(),
+            "Expected phase WaitingForPlayers, but the table is in Flop"
+        );
+        assert_eq!(PokerError::GameNotStale.to_string(), "Game is not stale");
+        assert_eq!(
+            PokerError::BuyInOutOfRange { min: 40, max: 200 }.to_string(),
+            "Buy-in must be between 40 and 200"
+        );
+    }
+
+    #[test]
+    fn test_player_data_export_fields() {
+        let mut player = test_player("alice", 1500);
+        player.bounties_won = 200;
+        let leaderboard = LeaderboardData {
+            chips: 1500,
+            chain_id: "chain-a".to_string(),
+            longest_win_streak: 4,
+            ..Default::default()
+        };
+        let flag = |hand_number, flagged_by: &str| FlaggedHand {
+            hand_number,
+            reason: "chip dumping".to_string(),
+            flagged_by: flagged_by.to_string(),
+            timestamp_ms: 0,
+            resolved: false,
+            resolution: None,
+        };
+        let flags = vec![
+            flag(3, "alice"),
+            flag(5, "bob"),
+            flag(7, "bob"),
+            flag(9, "alice"),
+            flag(9, "bob"),
+        ];
+        let transfer = |from: &str, to: &str| ChipTransfer {
+            hand_number: 2,
+            from: from.to_string(),
+            to: to.to_string(),
+            amount: 100,
+            timestamp_ms: 0,
+        };
+        let transfers = vec![
+            transfer("alice", "bob"),
+            transfer("bob", "carol"),
+            transfer("carol", "alice"),
+        ];
+        let chat = |sender: &str, message: &str| ChatEntry {
+            sender: sender.to_string(),
+            message: message.to_string(),
+            timestamp_ms: 0,
+        };
+        let chat_log = vec![chat("alice", "gl"), chat("bob", "ty"), chat("alice", "nh")];
+
+        let export = PokerState::build_player_export(
+            player,
+            leaderboard,
+            &transfers,
+            &chat_log,
+            &flags,
+            &[5, 6],
+        );
+        assert_eq!(export.player.name, "alice");
+        assert_eq!(export.player.chips, 1500);
+        assert_eq!(export.player.bounties_won, 200);
+        assert_eq!(export.leaderboard.chain_id, "chain-a");
+        assert_eq!(export.leaderboard.longest_win_streak, 4);
+        assert_eq!(
+            export.transfers,
+            vec![transfer("alice", "bob"), transfer("carol", "alice")]
+        );
+        assert_eq!(
+            export.chat_messages,
+            vec![chat("alice", "gl"), chat("alice", "nh")]
+        );
+        // Her own flags, plus Bob's flag on a hand she played in
+        assert_eq!(export.flagged_hand_numbers, vec![3, 5, 9]);
+    }
+
+    #[test]
+    fn test_cannot_export_another_players_data() {
+        assert!(PokerState::check_export_access("alice", "alice").is_ok());
+        assert!(matches!(
+            PokerState::check_export_access("bob", "alice"),
+            Err(PokerError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_collects_transfers_chat_and_flags() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 1000)]);
+        state.chip_transfer_log.get_mut().push(ChipTransfer {
+            hand_number: 0,
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            amount: 300,
+            timestamp_ms: 10,
+        });
+        state.chat_log.get_mut().extend([
+            ChatEntry {
+                sender: "alice".to_string(),
+                message: "gl".to_string(),
+                timestamp_ms: 11,
+            },
+            ChatEntry {
+                sender: "bob".to_string(),
+                message: "thanks".to_string(),
+                timestamp_ms: 12,
+            },
+        ]);
+
+        // Bob checks in the flagged hand without flagging it himself
+        state.initiate_bomb_pot("host", 10).blocking_wait().unwrap();
+        for _ in 0..2 {
+            let round = state.betting_round().blocking_wait();
+            let to_act = round.to_act().expect("Someone acts").to_string();
+            state
+                .apply_betting_action(&to_act, &PlayerAction::Check)
+                .blocking_wait()
+                .unwrap();
+        }
+        state
+            .flag_hand_for_review(1, "collusion".to_string(), "host".to_string(), 14)
+            .unwrap();
+
+        assert!(matches!(
+            state.export_player_data("alice", "bob").blocking_wait(),
+            Err(PokerError::Unauthorized(_))
+        ));
+        let export = state
+            .export_player_data("bob", "bob")
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(export.player.chips, 990);
+        assert_eq!(export.transfers.len(), 1);
+        assert_eq!(export.transfers[0].amount, 300);
+        assert_eq!(export.chat_messages.len(), 1);
+        assert_eq!(export.chat_messages[0].message, "thanks");
+        assert_eq!(export.flagged_hand_numbers, vec![1]);
+        assert!(state
+            .pending_exports
+            .get(&"bob".to_string())
+            .blocking_wait()
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_operations_report_typed_errors() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 1000)]);
+        assert!(matches!(
+            state
+                .join_table("carol", "carol", 0, false, None)
+                .blocking_wait(),
+            Err(PokerError::BuyInOutOfRange {
+                min: 1,
+                max: 100_000
+            })
+        ));
+        assert!(matches!(
+            state
+                .join_table("carol", "carol", 500, false, Some(0))
+                .blocking_wait(),
+            Err(PokerError::SeatUnavailable(0))
+        ));
+        assert_eq!(
+            state
+                .apply_betting_action("alice", &PlayerAction::Check)
+                .blocking_wait(),
+            Err(PokerError::NoHandInProgress)
+        );
+        assert!(matches!(
+            state.vote_on_rule("alice", 7, true).blocking_wait(),
+            Err(PokerError::NoOffer(_))
+        ));
+        assert!(matches!(
+            state.accept_insurance("alice").blocking_wait(),
+            Err(PokerError::NoOffer(_))
+        ));
+        state.rules.get_mut().straddle_allowed = false;
+        assert!(matches!(
+            state.post_straddle("alice").blocking_wait(),
+            Err(PokerError::NotEnabled(_))
+        ));
+        assert_eq!(
+            state.reset_stale_game("host", 0).blocking_wait(),
+            Err(PokerError::GameNotStale)
+        );
+        state.finalize_session("host").blocking_wait().unwrap();
+        assert_eq!(
+            state.finalize_session("host").blocking_wait(),
+            Err(PokerError::SessionComplete)
+        );
+
+        let mut sit_and_go = test_table(&[]);
+        sit_and_go
+            .tournament_config
+            .set(Some(top_three_tournament(100, 0)));
+        sit_and_go.tournament_started.set(true);
+        assert!(matches!(
+            sit_and_go
+                .join_table("dave", "dave", 100, false, None)
+                .blocking_wait(),
+            Err(PokerError::RegistrationClosed)
+        ));
+    }
+
+    #[test]
+    fn test_session_stops_at_exactly_max_hands() {
+        let mut hands_played = 0;
+        while !PokerState::session_limit_reached(hands_played, Some(25)) {
+            hands_played += 1;
+            assert!(hands_played <= 100, "session never ended");
+        }
+        assert_eq!(hands_played, 25);
+        assert!(!PokerState::session_limit_reached(1_000, None));
+    }
+
+    #[test]
+    fn test_early_finalize_returns_chip_balances() {
+        assert!(matches!(
+            PokerState::require_table_creator("alice", "bob", "finalize the session"),
+            Err(PokerError::Unauthorized(_))
+        ));
+
+        let stacks = vec![
+            ("alice".to_string(), 800),
+            ("bob".to_string(), 1700),
+            ("carol".to_string(), 500),
+        ];
+        assert_eq!(
+            PokerState::session_standings(&stacks, None, 0),
+            vec![
+                ("bob".to_string(), 1700),
+                ("alice".to_string(), 800),
+                ("carol".to_string(), 500),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_session_standings_use_payout_structure() {
+        let stacks = vec![
+            ("alice".to_string(), 800),
+            ("bob".to_string(), 1700),
+            ("carol".to_string(), 500),
+            ("dave".to_string(), 0),
+        ];
+        let config = top_three_tournament(100, 0);
+        assert_eq!(
+            PokerState::session_standings(&stacks, Some(&config), 400),
+            vec![
+                ("bob".to_string(), 200),
+                ("alice".to_string(), 120),
+                ("carol".to_string(), 80),
+            ]
+        );
+        // The pool actually collected is paid out, not buy-ins times seats
+        assert_eq!(
+            PokerState::session_standings(&stacks, Some(&config), 600),
+            vec![
+                ("bob".to_string(), 300),
+                ("alice".to_string(), 180),
+                ("carol".to_string(), 120),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_burn_advances_deck_past_burned_card() {
+        let mut deck = PokerState::create_deck();
+        let mut burned = Vec::new();
+        PokerState::deal_hole_cards(&mut deck, 2, GameVariant::TexasHoldem);
+
+        let flop = PokerState::burn_and_deal(&mut deck, &mut burned, 3, true);
+        assert_eq!(deck.len(), 52 - 4 - 4);
+        let turn = PokerState::burn_and_deal(&mut deck, &mut burned, 1, true);
+        let river = PokerState::burn_and_deal(&mut deck, &mut burned, 1, true);
+        assert_eq!(deck.len(), 52 - 4 - 8);
+        assert_eq!(flop.len() + turn.len() + river.len(), 5);
+        assert_eq!(burned.len(), 3);
+
+        let mut unburned = PokerState::create_deck();
+        PokerState::burn_and_deal(&mut unburned, &mut Vec::new(), 3, false);
+        assert_eq!(unburned.len(), 49);
+    }
+
+    #[test]
+    fn test_burn_cards_come_from_expected_deck_positions() {
+        let deck = PokerState::shuffle_deck([7; 32]);
+        let top = |position: usize| deck[deck.len() - 1 - position];
+
+        let mut remaining = deck.clone();
+        let mut burned = Vec::new();
+        PokerState::deal_hole_cards(&mut remaining, 2, GameVariant::TexasHoldem);
+        let flop = PokerState::burn_and_deal(&mut remaining, &mut burned, 3, true);
+        let turn = PokerState::burn_and_deal(&mut remaining, &mut burned, 1, true);
+        let river = PokerState::burn_and_deal(&mut remaining, &mut burned, 1, true);
+
+        // Four hole cards, burn, flop, burn, turn, burn, river
+        assert_eq!(burned, vec![top(4), top(8), top(10)]);
+        assert_eq!(flop, vec![top(5), top(6), top(7)]);
+        assert_eq!(turn, vec![top(9)]);
+        assert_eq!(river, vec![top(11)]);
+    }
+
+    fn seat_names(count: usize) -> Vec<String> {
+        (0..count).map(|seat| format!("player{}", seat)).collect()
+    }
+
+    #[test]
+    fn test_initial_button_is_deterministic() {
+        let names = seat_names(6);
+        let seed = [42; 32];
+        assert_eq!(
+            PokerState::assign_initial_button(&names, seed),
+            PokerState::assign_initial_button(&names, seed)
+        );
+        assert!(PokerState::assign_initial_button(&names, seed) < 6);
+        assert_eq!(PokerState::assign_initial_button(&[], seed), 0);
+    }
+
+    #[test]
+    fn test_initial_button_positions_equally_likely() {
+        let names = seat_names(6);
+        let mut counts = [0u32; 6];
+        for draw in 0..1000u64 {
+            let mut seed = [0u8; 32];
+            seed[..8].copy_from_slice(&draw.wrapping_mul(0x9E37_79B9_7F4A_7C15).to_le_bytes());
+            counts[PokerState::assign_initial_button(&names, seed) as usize] += 1;
+        }
+        // 1000 / 6 is about 167 per seat
+        for count in counts {
+            assert!((130..=205).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_initial_button_uses_the_whole_seed() {
+        let names = seat_names(6);
+        let buttons: std::collections::HashSet<u8> = (0..32u8)
+            .map(|last| {
+                let mut seed = [0u8; 32];
+                seed[31] = last;
+                PokerState::assign_initial_button(&names, seed)
+            })
+            .collect();
+        assert!(buttons.len() > 1);
+    }
+
+    #[test]
+    fn test_tables_with_different_seeds_deal_different_decks() {
+        let stacks = [("alice", 1000), ("bob", 1000)];
+        let mut first = table_on_chain(&stacks, "chain-a");
+        let mut second = table_on_chain(&stacks, "chain-b");
+        assert_ne!(first.random_seed.get(), &[0; 32]);
+        assert_ne!(first.random_seed.get(), second.random_seed.get());
+        for state in [&mut first, &mut second] {
+            state.begin_hand().blocking_wait().unwrap();
+        }
+        assert_ne!(first.deck.get(), second.deck.get());
+
+        // The same table deals a fresh shuffle every hand
+        let first_hand = first.deck.get().clone();
+        first.begin_hand().blocking_wait().unwrap();
+        assert_ne!(first.deck.get(), &first_hand);
+    }
+
+    #[test]
+    fn test_later_hands_rotate_the_button() {
+        let names = seat_names(4);
+        let seats = [0, 1, 2, 3];
+        let seed = [9; 32];
+        assert_eq!(PokerState::button_for_hand(1, &seats, &names, 5, seed), 2);
+        assert_eq!(PokerState::button_for_hand(3, &seats, &names, 6, seed), 0);
+        assert_eq!(
+            PokerState::button_for_hand(3, &seats, &names, 0, seed),
+            PokerState::assign_initial_button(&names, seed)
+        );
+    }
+
+    #[test]
+    fn test_button_follows_seats_when_players_leave() {
+        let seed = [9; 32];
+        // Seat 0 leaves while the button is on seat 2: it still moves to seat 3
+        let names = seat_names(3);
+        let seats = [1, 2, 3];
+        assert_eq!(PokerState::button_for_hand(2, &seats, &names, 5, seed), 2);
+        // The button's own seat is vacated: the next seat along gets it
+        let seats = [0, 2, 3];
+        assert_eq!(PokerState::button_for_hand(1, &seats, &names, 5, seed), 1);
+        assert_eq!(PokerState::index_at_or_before_seat(&[2, 5], 1), 1);
+    }
+
+    fn with_bet(name: &str, chips: u64, bet: u64) -> PokerPlayer {
+        let mut player = test_player(name, chips);
+        player.current_bet = bet;
+        player.total_bet_this_hand = bet;
+        player
+    }
+
+    #[test]
+    fn test_uncalled_raise_returned_after_fold() {
+        let mut players = vec![with_bet("alice", 700, 300), with_bet("bob", 900, 100)];
+        players[1].has_folded = true;
+
+        let returned = PokerState::apply_uncalled_bet_return(&mut players, 300);
+        assert_eq!(returned, Some(("alice".to_string(), 200)));
+        assert_eq!(players[0].chips, 900);
+        assert_eq!(players[0].current_bet, 100);
+        assert_eq!(players[0].total_bet_this_hand, 100);
+    }
+
+    #[test]
+    fn test_called_raise_returns_nothing() {
+        let mut players = vec![with_bet("alice", 700, 300), with_bet("bob", 700, 300)];
+        assert_eq!(
+            PokerState::apply_uncalled_bet_return(&mut players, 300),
+            None
+        );
+        assert_eq!(players[0].chips, 700);
+    }
+
+    #[test]
+    fn test_uncalled_all_in_fully_returned() {
+        let mut players = vec![with_bet("alice", 0, 500), with_bet("bob", 1000, 0)];
+        players[0].is_all_in = true;
+        players[0].status = PlayerStatus::AllIn;
+
+        let returned = PokerState::apply_uncalled_bet_return(&mut players, 500);
+        assert_eq!(returned, Some(("alice".to_string(), 500)));
+        assert_eq!(players[0].chips, 500);
+        assert!(!players[0].is_all_in);
+        assert_eq!(players[0].status, PlayerStatus::Active);
+    }
+
+    #[test]
+    fn test_uncalled_amount_after_several_folds() {
+        let mut players = vec![
+            with_bet("alice", 500, 40),
+            with_bet("bob", 200, 120),
+            with_bet("carol", 0, 800),
+            with_bet("dave", 900, 20),
+        ];
+        assert_eq!(
+            PokerState::compute_uncalled_bet_return(&players[2], 800, 120),
+            680
+        );
+        let returned = PokerState::apply_uncalled_bet_return(&mut players, 800);
+        assert_eq!(returned, Some(("carol".to_string(), 680)));
+        assert_eq!(players[2].current_bet, 120);
+    }
+
+    fn dealt_in(name: &str, wants_rabbit_hunt: bool) -> PokerPlayer {
+        let mut player = test_player(name, 1000);
+        player.hole_cards = Some(vec![
+            Card {
+                rank: Rank::Two,
+                suit: Suit::Clubs,
+            },
+            Card {
+                rank: Rank::Seven,
+                suit: Suit::Hearts,
+            },
+        ]);
+        player.wants_rabbit_hunt = wants_rabbit_hunt;
+        player
+    }
+
+    #[test]
+    fn test_rabbit_hunt_shown_when_both_consent() {
+        let players = vec![dealt_in("alice", true), dealt_in("bob", true)];
+        assert!(PokerState::rabbit_hunt_consented(&players));
+
+        let deck = PokerState::shuffle_deck([5; 32]);
+        let top = |position: usize| deck[deck.len() - 1 - position];
+        let flop = vec![top(1), top(2), top(3)];
+        // Folded on the flop: burn, turn, burn, river still to come
+        assert_eq!(
+            PokerState::rabbit_hunt_cards(&deck[..deck.len() - 4], &flop),
+            vec![top(5), top(7)]
+        );
+        assert_eq!(
+            PokerState::rabbit_hunt_cards(&deck, &[]),
+            vec![top(1), top(2), top(3), top(5), top(7)]
+        );
+        assert!(PokerState::rabbit_hunt_cards(&deck, &deck[..5]).is_empty());
+    }
+
+    #[test]
+    fn test_rabbit_hunt_needs_every_player() {
+        let mut players = vec![dealt_in("alice", true), dealt_in("bob", false)];
+        assert!(!PokerState::rabbit_hunt_consented(&players));
+
+        // Players who were not dealt in have no say
+        players[1].hole_cards = None;
+        assert!(PokerState::rabbit_hunt_consented(&players));
+        assert!(!PokerState::rabbit_hunt_consented(&[]));
+    }
+
+    #[test]
+    fn test_rabbit_hunt_rejected_when_disabled() {
+        assert!(matches!(
+            PokerState::check_rabbit_hunt_allowed(false, &GamePhase::HandComplete),
+            Err(PokerError::NotEnabled(_))
+        ));
+        assert!(matches!(
+            PokerState::check_rabbit_hunt_allowed(true, &GamePhase::Turn),
+            Err(PokerError::InvalidPhase { .. })
+        ));
+        assert!(PokerState::check_rabbit_hunt_allowed(true, &GamePhase::HandComplete).is_ok());
+    }
+
+    fn auto_rebuyer(chips: u64) -> PokerPlayer {
+        let mut player = test_player("alice", chips);
+        player.auto_rebuy_threshold = Some(500);
+        player.auto_rebuy_to = Some(2000);
+        player.auto_rebuy_reserve = 5000;
+        player
+    }
+
+    #[test]
+    fn test_auto_rebuy_below_threshold() {
+        assert_eq!(PokerState::auto_rebuy_due(&auto_rebuyer(320)), Some(1680));
+        assert_eq!(PokerState::auto_rebuy_due(&auto_rebuyer(0)), Some(2000));
+        assert_eq!(PokerState::auto_rebuy_due(&auto_rebuyer(500)), None);
+    }
+
+    #[test]
+    fn test_auto_rebuy_skipped_when_reserve_cannot_pay() {
+        let mut player = auto_rebuyer(320);
+        player.auto_rebuy_reserve = 1679;
+        assert_eq!(PokerState::auto_rebuy_due(&player), None);
+        player.auto_rebuy_reserve = 1680;
+        assert_eq!(PokerState::auto_rebuy_due(&player), Some(1680));
+    }
+
+    #[test]
+    fn test_auto_rebuy_is_charged_like_add_chips() {
+        let mut state = test_table(&[("alice", 300), ("bob", 300), ("carol", 300)]);
+        state.buy_in_max.set(2000);
+        state.rebuys_enabled.set(true);
+        state.max_rebuys_per_session.set(Some(1));
+        assert_eq!(
+            state
+                .set_auto_rebuy("alice", Some(500), Some(2000), 2500)
+                .blocking_wait(),
+            Ok(0)
+        );
+        // bob's reserve cannot cover the top-up
+        state
+            .set_auto_rebuy("bob", Some(500), Some(2000), 1000)
+            .blocking_wait()
+            .unwrap();
+
+        assert_eq!(
+            state.process_auto_rebuys().blocking_wait(),
+            vec![("alice".to_string(), 1700)]
+        );
+        let alice = state.load_player("alice").blocking_wait().unwrap();
+        assert_eq!((alice.chips, alice.auto_rebuy_reserve), (2000, 800));
+        let bob = state.load_player("bob").blocking_wait().unwrap();
+        assert_eq!((bob.chips, bob.auto_rebuy_reserve), (300, 1000));
+
+        // add_chips' limits apply: alice has used her one rebuy
+        let mut alice = alice;
+        alice.chips = 100;
+        state.save_players(&[alice]);
+        state
+            .set_auto_rebuy("alice", Some(500), Some(2000), 2000)
+            .blocking_wait()
+            .unwrap();
+        assert!(state.process_auto_rebuys().blocking_wait().is_empty());
+
+        // ...and so does table stakes: nothing is added during a hand
+        state.max_rebuys_per_session.set(None);
+        state.phase.set(GamePhase::Flop);
+        assert!(state.process_auto_rebuys().blocking_wait().is_empty());
+        state.phase.set(GamePhase::HandComplete);
+        assert_eq!(
+            state.process_auto_rebuys().blocking_wait(),
+            vec![("alice".to_string(), 1900)]
+        );
+
+        // Turning it off hands back what is left of the reserve
+        assert_eq!(
+            state.set_auto_rebuy("alice", None, None, 0).blocking_wait(),
+            Ok(900)
+        );
+    }
+
+    #[test]
+    fn test_auto_rebuy_settings_validated() {
+        assert!(PokerState::validate_auto_rebuy(Some(500), Some(2000), 2000).is_ok());
+        assert!(PokerState::validate_auto_rebuy(None, None, 2000).is_ok());
+        assert!(PokerState::validate_auto_rebuy(Some(500), Some(2500), 2000).is_err());
+        assert!(PokerState::validate_auto_rebuy(Some(2000), Some(2000), 2000).is_err());
+        assert!(PokerState::validate_auto_rebuy(Some(500), None, 2000).is_err());
+    }
+
+    #[test]
+    fn test_each_action_increments_its_counter() {
+        let mut frequency = ActionFrequency::default();
+        frequency.record(&PlayerAction::Fold);
+        frequency.record(&PlayerAction::Check);
+        frequency.record(&PlayerAction::Check);
+        frequency.record(&PlayerAction::Call);
+        frequency.record(&PlayerAction::Bet(100));
+        frequency.record(&PlayerAction::Raise(300));
+        frequency.record(&PlayerAction::AllIn);
+        assert_eq!(
+            frequency,
+            ActionFrequency {
+                fold: 1,
+                check: 2,
+                call: 1,
+                bet: 1,
+                raise: 1,
+                all_in: 1,
+            }
+        );
+        assert_eq!(frequency.total(), 7);
+    }
+
+    #[test]
+    fn test_last_aggressor_shows_first() {
+        let players = seat_names(4);
+        assert_eq!(
+            PokerState::showdown_order(Some("player2"), Some("player1"), &players, &players),
+            vec!["player2", "player3", "player0", "player1"]
+        );
+        // An aggressor who has since folded no longer leads
+        assert_eq!(
+            PokerState::showdown_order(Some("player9"), None, &players, &players),
+            players
+        );
+    }
+
+    #[test]
+    fn test_shove_is_last_aggression() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 1000)]);
+        state.initiate_bomb_pot("host", 10).blocking_wait().unwrap();
+        let to_act = |state: &PokerState| {
+            let round = state.betting_round().blocking_wait();
+            round.to_act().expect("The round is open").to_string()
+        };
+
+        let bettor = to_act(&state);
+        state
+            .apply_betting_action(&bettor, &PlayerAction::Bet(100))
+            .blocking_wait()
+            .unwrap();
+        let shover = to_act(&state);
+        state
+            .apply_betting_action(&shover, &PlayerAction::AllIn)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(state.last_aggressor.get().as_deref(), Some(shover.as_str()));
+        assert_eq!(*state.raises_this_street.get(), 1);
+
+        // Calling all-in for no more than the bet is not aggression
+        state
+            .apply_betting_action(&bettor, &PlayerAction::AllIn)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(state.last_aggressor.get().as_deref(), Some(shover.as_str()));
+        assert_eq!(*state.raises_this_street.get(), 1);
+        let players = vec![bettor, shover.clone()];
+        assert_eq!(
+            PokerState::showdown_order(
+                state.last_aggressor.get().as_deref(),
+                None,
+                &players,
+                &players
+            )[0],
+            shover
+        );
+    }
+
+    #[test]
+    fn test_no_aggressor_big_blind_shows_first() {
+        // Heads-up the big blind is first to act after the flop
+        let players = vec!["big_blind".to_string(), "button".to_string()];
+        assert_eq!(
+            PokerState::showdown_order(None, Some("big_blind"), &players, &players),
+            vec!["big_blind", "button"]
+        );
+        assert_eq!(
+            PokerState::next_to_show(&players, &[]),
+            Some("big_blind".to_string())
+        );
+
+        // Four-handed the big blind is not the first seat after the button
+        let seats = seat_names(4);
+        assert_eq!(
+            PokerState::showdown_order(None, Some("player2"), &seats, &seats),
+            vec!["player2", "player3", "player0", "player1"]
+        );
+        // A folded big blind passes the lead to the next player still in
+        let in_showdown = vec!["player0".to_string(), "player1".to_string()];
+        assert_eq!(
+            PokerState::showdown_order(None, Some("player2"), &seats, &in_showdown),
+            vec!["player0", "player1"]
+        );
+        let in_showdown = vec!["player1".to_string(), "player3".to_string()];
+        assert_eq!(
+            PokerState::showdown_order(None, Some("player2"), &seats, &in_showdown),
+            vec!["player3", "player1"]
+        );
+    }
+
+    #[test]
+    fn test_muck_mid_showdown_sequence() {
+        let mut in_showdown = seat_names(3);
+        let seats = in_showdown.clone();
+        let order = PokerState::showdown_order(Some("player1"), None, &seats, &in_showdown);
+        let mut shown = vec!["player1".to_string()];
+        assert_eq!(
+            PokerState::next_to_show(&order, &shown),
+            Some("player2".to_string())
+        );
+
+        // player2 mucks: they fold and drop out of the showdown
+        in_showdown.retain(|p| p != "player2");
+        let order = PokerState::showdown_order(Some("player1"), None, &seats, &in_showdown);
+        assert_eq!(
+            PokerState::next_to_show(&order, &shown),
+            Some("player0".to_string())
+        );
+        shown.push("player0".to_string());
+        assert_eq!(PokerState::next_to_show(&order, &shown), None);
+    }
+
+    #[test]
+    fn test_showdown_fold_mid_sequence() {
+        let mut state = test_table(&[
+            ("alice", 1000),
+            ("bob", 1000),
+            ("carol", 1000),
+            ("dave", 1000),
+        ]);
+        state.initiate_bomb_pot("host", 50).blocking_wait().unwrap();
+        state.phase.set(GamePhase::Showdown);
+        state.last_aggressor.set(None);
+
+        // Nobody bet, so the big blind shows first and the rest follow
+        // clockwise in the order the blinds were posted
+        let big_blind = state
+            .big_blind_player
+            .get()
+            .clone()
+            .expect("Blinds were recorded");
+        let seats = state.blind_seat_order.get().clone();
+        let bb = seats.iter().position(|p| *p == big_blind).unwrap();
+        let clockwise: Vec<String> = (1..=3).map(|i| seats[(bb + i) % 4].clone()).collect();
+        assert_eq!(
+            state.next_showdown_player().blocking_wait(),
+            Some(big_blind.clone())
+        );
+        state
+            .record_showdown_reveal(&big_blind, 0)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(
+            state.next_showdown_player().blocking_wait().as_ref(),
+            Some(&clockwise[0])
+        );
+
+        // The next player mucks; their turn passes on without them showing
+        assert!(matches!(
+            state.muck_at_showdown(&clockwise[1], 1).blocking_wait(),
+            Err(PokerError::NotPlayersTurn(_))
+        ));
+        state
+            .muck_at_showdown(&clockwise[0], 1)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(
+            state.next_showdown_player().blocking_wait().as_ref(),
+            Some(&clockwise[1])
+        );
+        state
+            .record_showdown_reveal(&clockwise[1], 2)
+            .blocking_wait()
+            .unwrap();
+        state
+            .record_showdown_reveal(&clockwise[2], 3)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(state.next_showdown_player().blocking_wait(), None);
+        assert_eq!(
+            *state.shown_at_showdown.get(),
+            vec![big_blind, clockwise[1].clone(), clockwise[2].clone()]
+        );
+    }
+
+    #[test]
+    fn test_single_player_cannot_start() {
+        let config = TableConfig::default();
+        assert!(!PokerState::has_enough_players_to_start(
+            1,
+            config.min_players_to_start
+        ));
+        assert!(PokerState::has_enough_players_to_start(
+            2,
+            config.min_players_to_start
+        ));
+    }
+
+    #[test]
+    fn test_player_leaving_drops_table_below_minimum() {
+        let mut seated = 3;
+        assert!(PokerState::has_enough_players_to_start(seated, 3));
+        seated -= 1;
+        assert!(!PokerState::has_enough_players_to_start(seated, 3));
+        assert_eq!(
+            PokerError::NotEnoughPlayers { have: 2, need: 3 }.to_string(),
+            "Need 3 players to start, only 2 ready"
+        );
+    }
+
+    #[test]
+    fn test_full_ring_minimum() {
+        let config = TableConfig {
+            min_players_to_start: 6,
+            ..TableConfig::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(!PokerState::has_enough_players_to_start(5, 6));
+        assert!(PokerState::has_enough_players_to_start(6, 6));
+
+        let too_many = TableConfig {
+            max_players: 6,
+            min_players_to_start: 7,
+            ..TableConfig::default()
+        };
+        assert!(too_many.validate().is_err());
+        let too_few = TableConfig {
+            min_players_to_start: 1,
+            ..TableConfig::default()
+        };
+        assert!(too_few.validate().is_err());
+    }
+
+    #[test]
+    fn test_move_chips_to_pot_is_all_or_nothing() {
+        let mut player = test_player("alice", 300);
+        assert_eq!(PokerState::move_chips_to_pot(&mut player, 120), Ok(120));
+        assert_eq!(player.chips, 180);
+        assert_eq!(
+            PokerState::move_chips_to_pot(&mut player, 200),
+            Err(PokerError::InsufficientChips {
+                needed: 200,
+                available: 180
+            })
+        );
+        assert_eq!(player.chips, 180);
+        assert_eq!(PokerState::move_all_chips_to_pot(&mut player), 180);
+        assert_eq!(player.chips, 0);
+    }
+
+    #[test]
+    fn test_chips_conserved_through_a_hand() {
+        let mut players = vec![
+            test_player("alice", 1000),
+            test_player("bob", 600),
+            test_player("carol", 250),
+        ];
+        players[2].missed_blind_due = 20;
+        let initial = PokerState::total_chips(&players, 0);
+        let mut pot = 0;
+
+        pot += PokerState::compute_dead_money(&mut players, 10);
+        assert_eq!(PokerState::total_chips(&players, pot), initial);
+
+        let (all_in, _) = PokerState::apply_all_in(&mut players[2], 0, 20).unwrap();
+        pot += all_in;
+        assert_eq!(PokerState::total_chips(&players, pot), initial);
+
+        let (shove, _) = PokerState::apply_all_in(&mut players[0], 220, 20).unwrap();
+        pot += shove;
+        assert_eq!(PokerState::total_chips(&players, pot), initial);
+
+        players[1].has_folded = true;
+        let current_bet = players[0].current_bet;
+        let (_, returned) =
+            PokerState::apply_uncalled_bet_return(&mut players, current_bet).unwrap();
+        pot -= returned;
+        assert_eq!(PokerState::total_chips(&players, pot), initial);
+
+        let refunded: u64 = PokerState::refund_hand_bets(&mut players)
+            .iter()
+            .map(|(_, amount)| amount)
+            .sum();
+        pot -= refunded;
+        assert_eq!(pot, 0);
+        assert_eq!(PokerState::total_chips(&players, pot), initial);
+    }
+
+    #[test]
+    fn test_chips_conserved_after_every_operation() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 600), ("carol", 250)]);
+        state.rake_percent.set(5);
+        state.insurance_pool.set(500);
+        let total = 1850 + 500;
+        let conserved = |state: &PokerState| state.verify_chip_conservation(total).blocking_wait();
+        let to_act = |state: &PokerState| {
+            let round = state.betting_round().blocking_wait();
+            round.to_act().expect("The round is open").to_string()
+        };
+
+        state.initiate_bomb_pot("host", 20).blocking_wait().unwrap();
+        assert!(conserved(&state));
+        for action in [
+            PlayerAction::Bet(100),
+            PlayerAction::Call,
+            PlayerAction::Fold,
+        ] {
+            let player = to_act(&state);
+            state
+                .apply_betting_action(&player, &action)
+                .blocking_wait()
+                .unwrap();
+            assert!(conserved(&state));
+        }
+        state.settle_betting_round().blocking_wait().unwrap();
+        assert!(conserved(&state));
+        assert!(state.collect_rake().blocking_wait().unwrap() > 0);
+        assert!(conserved(&state));
+
+        // A chip appearing from nowhere is caught, and the next action fails
+        state.pot.set(*state.pot.get() + 1);
+        assert!(!conserved(&state));
+        let player = to_act(&state);
+        assert!(matches!(
+            state
+                .apply_betting_action(&player, &PlayerAction::Check)
+                .blocking_wait(),
+            Err(PokerError::ChipsNotConserved { .. })
+        ));
+    }
+
+    #[test]
+    fn test_hand_number_overflow() {
+        let last = PokerState::next_hand_number(u64::MAX - 1);
+        assert_eq!(last, Ok(u64::MAX));
+        assert_eq!(
+            PokerState::next_hand_number(u64::MAX),
+            Err(PokerError::HandNumberOverflow)
+        );
+    }
+
+    #[test]
+    fn test_hand_number_sequence_is_contiguous() {
+        assert!(PokerState::hand_number_sequence_is_contiguous(&[]));
+        assert!(PokerState::hand_number_sequence_is_contiguous(&[7]));
+        assert!(PokerState::hand_number_sequence_is_contiguous(&[3, 4, 5]));
+        assert!(!PokerState::hand_number_sequence_is_contiguous(&[3, 5, 6]));
+        assert!(!PokerState::hand_number_sequence_is_contiguous(&[3, 3, 4]));
+        assert!(!PokerState::hand_number_sequence_is_contiguous(&[
+            u64::MAX,
+            0
+        ]));
+    }
+
+    #[test]
+    fn test_community_cards_for_phase() {
+        let board: Vec<Card> = PokerState::create_deck().into_iter().take(5).collect();
+        let shown = |phase| PokerState::community_cards_for_phase(&board, &phase).len();
+        assert_eq!(shown(GamePhase::PreFlop), 0);
+        assert_eq!(shown(GamePhase::Flop), 3);
+        assert_eq!(shown(GamePhase::Turn), 4);
+        assert_eq!(shown(GamePhase::River), 5);
+        assert_eq!(shown(GamePhase::Showdown), 5);
+
+        // The turn is hidden until the flop betting is over
+        let flop = PokerState::community_cards_for_phase(&board, &GamePhase::Flop);
+        assert!(!flop.contains(&board[3]));
+        // A hand that ended on the flop has no more cards to show
+        assert_eq!(
+            PokerState::community_cards_for_phase(&board[..3], &GamePhase::HandComplete),
+            &board[..3]
+        );
+    }
+
+    #[test]
+    fn test_next_community_cards_count() {
+        let dealt: usize = [GamePhase::PreFlop, GamePhase::Flop, GamePhase::Turn]
+            .iter()
+            .map(PokerState::next_community_cards_count)
+            .sum();
+        assert_eq!(dealt, 5);
+        assert_eq!(PokerState::next_community_cards_count(&GamePhase::River), 0);
+        assert_eq!(
+            PokerState::next_community_cards_count(&GamePhase::WaitingForPlayers),
+            0
+        );
+    }
+
+    #[test]
+    fn test_pot_limit_max_bet() {
+        // First bet into a pot of 100
+        assert_eq!(PokerState::pot_limit_max_bet(100, 0, 0), 100);
+        // 100 in facing a raise to 300 with 500 out: call 200, raise 700
+        assert_eq!(PokerState::pot_limit_max_bet(500, 300, 100), 1000);
+        assert!(GameVariant::Omaha.is_pot_limit());
+        assert!(GameVariant::OmahaHiLo.is_pot_limit());
+        assert!(!GameVariant::TexasHoldem.is_pot_limit());
+    }
+
+    fn stud_player(name: &str, up_cards: &[(Rank, Suit)]) -> PokerPlayer {
+        let mut player = test_player(name, 1000);
+        player.up_cards = up_cards
+            .iter()
+            .map(|&(rank, suit)| Card { rank, suit })
+            .collect();
+        player
+    }
+
+    #[test]
+    fn test_stud_bring_in_is_lowest_door_card() {
+        let players = vec![
+            stud_player("alice", &[(Rank::King, Suit::Hearts)]),
+            stud_player("bob", &[(Rank::Two, Suit::Spades)]),
+            stud_player("carol", &[(Rank::Two, Suit::Clubs)]),
+        ];
+        assert_eq!(PokerState::stud_bring_in_player(&players), Some(2));
+    }
+
+    #[test]
+    fn test_stud_best_showing_hand_acts_first() {
+        let mut players = vec![
+            stud_player(
+                "alice",
+                &[(Rank::Ace, Suit::Hearts), (Rank::King, Suit::Clubs)],
+            ),
+            stud_player(
+                "bob",
+                &[(Rank::Four, Suit::Spades), (Rank::Four, Suit::Clubs)],
+            ),
+            stud_player(
+                "carol",
+                &[(Rank::Ace, Suit::Clubs), (Rank::Queen, Suit::Clubs)],
+            ),
+        ];
+        assert_eq!(PokerState::stud_first_to_act(&players), Some(1));
+        players[1].has_folded = true;
+        assert_eq!(PokerState::stud_first_to_act(&players), Some(0));
+    }
+
+    #[test]
+    fn test_stud_streets_and_dealing() {
+        let mut phase = GamePhase::ThirdStreet;
+        let mut face_up = Vec::new();
+        while let Some(next) = PokerState::next_stud_street(&phase) {
+            face_up.push(PokerState::stud_card_is_face_up(&next));
+            phase = next;
+        }
+        assert_eq!(phase, GamePhase::Showdown);
+        assert_eq!(face_up, vec![true, true, true, false, false]);
+
+        let mut deck = PokerState::create_deck();
+        let mut players = vec![test_player("alice", 1000), test_player("bob", 1000)];
+        players[1].has_folded = true;
+        PokerState::deal_stud_street(&mut deck, &mut players, &GamePhase::FourthStreet);
+        PokerState::deal_stud_street(&mut deck, &mut players, &GamePhase::SeventhStreet);
+        assert_eq!(players[0].up_cards.len(), 1);
+        assert_eq!(players[0].hole_cards.as_ref().map(Vec::len), Some(1));
+        assert!(players[1].up_cards.is_empty());
+        assert_eq!(deck.len(), 50);
+    }
+
+    #[test]
+    fn test_stud_betting_reopens_on_fourth_street() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 1000), ("carol", 1000)]);
+        state.rules.get_mut().variant = GameVariant::SevenCardStud;
+        state.begin_hand().blocking_wait().unwrap();
+        state.phase.set(GamePhase::ThirdStreet);
+        let bet = *state.big_blind.get() * 2;
+        let to_act = |state: &PokerState| {
+            state
+                .betting_round()
+                .blocking_wait()
+                .to_act()
+                .map(str::to_string)
+        };
+
+        let opener = to_act(&state).expect("Third street is open");
+        let mut status = state
+            .apply_betting_action(&opener, &PlayerAction::Bet(bet))
+            .blocking_wait()
+            .unwrap();
+        while let RoundStatus::ToAct(player) = status {
+            status = state
+                .apply_betting_action(&player, &PlayerAction::Call)
+                .blocking_wait()
+                .unwrap();
+        }
+        state.settle_betting_round().blocking_wait().unwrap();
+        assert_eq!(
+            state.deal_next_stud_street().blocking_wait(),
+            Some(GamePhase::FourthStreet)
+        );
+        assert!(state.acted_this_street.get().is_empty());
+        assert_eq!(*state.full_bet_level.get(), 0);
+
+        // Everyone acted on third street, but fourth street still has to be bet
+        let players = state.seated_players().blocking_wait();
+        let best_showing = PokerState::stud_first_to_act(&players).unwrap();
+        let opener = to_act(&state).expect("Fourth street is open");
+        assert_eq!(opener, players[best_showing].name);
+        let mut status = state
+            .apply_betting_action(&opener, &PlayerAction::Bet(bet))
+            .blocking_wait()
+            .unwrap();
+        let mut callers = 0;
+        while let RoundStatus::ToAct(player) = status {
+            assert_ne!(player, opener);
+            status = state
+                .apply_betting_action(&player, &PlayerAction::Call)
+                .blocking_wait()
+                .unwrap();
+            callers += 1;
+        }
+        assert_eq!(callers, 2);
+        assert_eq!(*state.pot.get(), bet * 6);
+        assert_eq!(state.last_aggressor.get().as_deref(), Some(opener.as_str()));
+    }
+
+    #[test]
+    fn test_short_deck_has_sixes_through_aces() {
+        let deck = PokerState::create_deck_for_variant(GameVariant::ShortDeckHoldem);
+        assert_eq!(deck.len(), 36);
+        assert!(deck.iter().all(|card| card.rank >= Rank::Six));
+        assert!(PokerState::validate_deck_against(&deck, &deck, &[], &[]).valid);
+        assert!(!PokerState::validate_deck_integrity(&deck, &[], &[]).valid);
+        assert_eq!(
+            PokerState::shuffle_deck_for_variant([9u8; 32], GameVariant::ShortDeckHoldem).len(),
+            36
+        );
+    }
+
+    #[test]
+    fn test_draw_replaces_discards_from_deck() {
+        let mut deck = PokerState::create_deck();
+        let mut player = test_player("alice", 1000);
+        player.hole_cards =
+            PokerState::deal_hole_cards(&mut deck, 1, GameVariant::FiveCardDraw).pop();
+        let hand = player.hole_cards.clone().unwrap();
+        assert_eq!(hand.len(), 5);
+
+        let top = *deck.last().unwrap();
+        let replacements = PokerState::apply_draw(&mut player, &mut deck, &hand[..2]).unwrap();
+        assert_eq!(replacements.len(), 2);
+        assert_eq!(replacements[0], top);
+        let new_hand = player.hole_cards.clone().unwrap();
+        assert_eq!(new_hand.len(), 5);
+        assert!(!new_hand.contains(&hand[0]) && new_hand.contains(&hand[2]));
+        assert!(PokerState::draw_complete(&[player.clone()]));
+
+        // One draw per hand
+        assert!(PokerState::apply_draw(&mut player, &mut deck, &[]).is_err());
+    }
+
+    #[test]
+    fn test_draw_rejects_foreign_or_repeated_cards() {
+        let mut deck = PokerState::create_deck();
+        let mut player = test_player("alice", 1000);
+        player.hole_cards =
+            PokerState::deal_hole_cards(&mut deck, 1, GameVariant::FiveCardDraw).pop();
+        let hand = player.hole_cards.clone().unwrap();
+        let foreign = deck[0];
+
+        assert!(PokerState::apply_draw(&mut player, &mut deck, &[foreign]).is_err());
+        assert!(PokerState::apply_draw(&mut player, &mut deck, &[hand[0], hand[0]]).is_err());
+        assert!(!PokerState::draw_complete(&[player.clone()]));
+
+        // Standing pat is a draw of nothing
+        assert_eq!(
+            PokerState::apply_draw(&mut player, &mut deck, &[]),
+            Ok(Vec::new())
+        );
+        assert_eq!(player.hole_cards, Some(hand));
+    }
+
+    #[test]
+    fn test_pineapple_discard_before_next_street() {
+        let mut deck = PokerState::create_deck();
+        let mut players = vec![test_player("alice", 1000), test_player("bob", 1000)];
+        for (player, cards) in players.iter_mut().zip(PokerState::deal_hole_cards(
+            &mut deck,
+            2,
+            GameVariant::CrazyPineapple,
+        )) {
+            player.hole_cards = Some(cards);
+        }
+        let variant = GameVariant::CrazyPineapple;
+        assert!(
+            PokerState::check_discards_complete(variant, &GamePhase::PreFlop, &players).is_ok()
+        );
+        assert!(PokerState::check_discards_complete(variant, &GamePhase::Flop, &players).is_err());
+
+        let card = players[0].hole_cards.as_ref().unwrap()[1];
+        PokerState::apply_hole_card_discard(&mut players[0], card).unwrap();
+        assert_eq!(players[0].hole_cards.as_ref().map(Vec::len), Some(2));
+        assert!(PokerState::apply_hole_card_discard(&mut players[0], card).is_err());
+
+        // A folded player's third card no longer holds up the turn
+        players[1].has_folded = true;
+        assert!(PokerState::check_discards_complete(variant, &GamePhase::Flop, &players).is_ok());
+        assert_eq!(
+            GameVariant::Pineapple.discard_phase(),
+            Some(GamePhase::PreFlop)
+        );
+    }
+
+    #[test]
+    fn test_triple_draw_has_three_draws() {
+        let variant = GameVariant::DeuceToSevenTripleDraw;
+        assert_eq!(variant.starting_cards(), 5);
+        let draws = (1..=5)
+            .take_while(|&completed| PokerState::more_draws_remain(variant, completed - 1))
+            .count();
+        assert_eq!(draws, 3);
+        assert!(!PokerState::more_draws_remain(GameVariant::FiveCardDraw, 1));
+    }
+
+    #[test]
+    fn test_betting_after_the_draw_starts_fresh() {
+        let mut state = test_table(&[("alice", 1000), ("bob", 1000)]);
+        state.rules.get_mut().variant = GameVariant::DeuceToSevenTripleDraw;
+        // The round before the draw was capped and everyone acted
+        state
+            .raises_this_street
+            .set(PokerState::FIXED_LIMIT_RAISE_CAP);
+        state.full_bet_level.set(400);
+        state.last_raise_size.set(100);
+        state.last_aggressor.set(Some("alice".to_string()));
+        state
+            .acted_this_street
+            .set(vec!["alice".to_string(), "bob".to_string()]);
+
+        assert!(state.finish_draw_round().blocking_wait());
+        assert_eq!(*state.raises_this_street.get(), 0);
+        assert_eq!(*state.full_bet_level.get(), 0);
+        assert_eq!(*state.last_raise_size.get(), 0);
+        assert_eq!(*state.last_aggressor.get(), None);
+        assert!(state.acted_this_street.get().is_empty());
+    }
+
+    #[test]
+    fn test_razz_bring_in_is_highest_door_card() {
+        let players = vec![
+            stud_player("alice", &[(Rank::King, Suit::Hearts)]),
+            stud_player("bob", &[(Rank::King, Suit::Spades)]),
+            stud_player("carol", &[(Rank::Ace, Suit::Spades)]),
+        ];
+        assert_eq!(PokerState::razz_bring_in_player(&players), Some(1));
+        assert_eq!(PokerState::stud_bring_in_player(&players), Some(0));
+    }
+
+    #[test]
+    fn test_razz_best_low_showing_acts_first() {
+        let players = vec![
+            stud_player(
+                "alice",
+                &[(Rank::Ace, Suit::Hearts), (Rank::Ace, Suit::Clubs)],
+            ),
+            stud_player(
+                "bob",
+                &[(Rank::Seven, Suit::Spades), (Rank::Two, Suit::Clubs)],
+            ),
+            stud_player(
+                "carol",
+                &[(Rank::Ace, Suit::Clubs), (Rank::Six, Suit::Clubs)],
+            ),
+        ];
+        assert_eq!(PokerState::razz_first_to_act(&players), Some(2));
+        assert_eq!(
+            PokerState::razz_up_card_strength(&[
+                Card {
+                    rank: Rank::Two,
+                    suit: Suit::Clubs
+                },
+                Card {
+                    rank: Rank::Ace,
+                    suit: Suit::Clubs
+                },
+                Card {
+                    rank: Rank::Two,
+                    suit: Suit::Hearts
+                },
+                Card {
+                    rank: Rank::Ace,
+                    suit: Suit::Spades
+                },
+            ]),
+            (2, vec![2, 1])
+        );
+        assert_eq!(PokerState::stud_first_to_act(&players), Some(0));
+    }
+
+    #[test]
+    fn test_scheduled_bomb_pot_every_nth_hand() {
+        let bomb_pots: Vec<u64> = (1..=20)
+            .filter(|&hand| PokerState::is_scheduled_bomb_pot(hand, Some(8)))
+            .collect();
+        assert_eq!(bomb_pots, vec![8, 16]);
+        assert!(!PokerState::is_scheduled_bomb_pot(8, None));
+        assert!(!PokerState::is_scheduled_bomb_pot(8, Some(0)));
+
+        let config = TableConfig {
+            bomb_pot_frequency: Some(8),
+            ..TableConfig::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(TableConfig {
+            bomb_pot_ante: 40,
+            ..config
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn test_pot_limit_caps_bets_and_raises() {
+        let check =
+            |structure, action| PokerState::check_bet_size(structure, &action, 500, 300, 100);
+        // Call 200 into 500, then raise by 700: raise to 1000
+        assert!(check(BettingStructure::PotLimit, PlayerAction::Raise(1000)).is_ok());
+        assert!(matches!(
+            check(BettingStructure::PotLimit, PlayerAction::Raise(1001)),
+            Err(PokerError::InvalidAction { .. })
+        ));
+        assert!(check(BettingStructure::NoLimit, PlayerAction::Raise(5000)).is_ok());
+        assert!(check(BettingStructure::PotLimit, PlayerAction::AllIn).is_ok());
+        assert!(PokerState::check_bet_size(
+            BettingStructure::PotLimit,
+            &PlayerAction::Bet(150),
+            100,
+            0,
+            0
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_fixed_limit_bet_sizes_and_cap() {
+        assert_eq!(PokerState::fixed_limit_bet_size(&GamePhase::Flop, 20), 20);
+        assert_eq!(PokerState::fixed_limit_bet_size(&GamePhase::Turn, 20), 40);
+
+        let check = |action, raises| PokerState::check_fixed_limit(&action, 40, 20, raises);
+        assert!(check(PlayerAction::Bet(20), 0).is_ok());
+        assert!(check(PlayerAction::Bet(30), 0).is_err());
+        assert!(check(PlayerAction::Raise(60), 3).is_ok());
+        assert!(matches!(
+            check(PlayerAction::Raise(80), 3),
+            Err(PokerError::InvalidAction { .. })
+        ));
+        assert_eq!(
+            check(PlayerAction::Raise(60), 4),
+            Err(PokerError::RaiseCapReached { cap: 4 })
+        );
+        // Calling a capped pot is still allowed
+        assert!(check(PlayerAction::Call, 4).is_ok());
+    }
+
+    #[test]
+    fn test_big_blind_ante() {
+        assert_eq!(PokerState::big_blind_seat(0, 6), 2);
+        assert_eq!(PokerState::big_blind_seat(5, 6), 1);
+        assert_eq!(PokerState::big_blind_seat(1, 2), 0);
+
+        let mut big_blind = test_player("bob", 1000);
+        assert_eq!(
+            PokerState::collect_big_blind_ante(&mut big_blind, 20, 20),
+            20
+        );
+        assert_eq!(big_blind.chips, 980);
+        assert_eq!(big_blind.current_bet, 0);
+        assert_eq!(big_blind.total_bet_this_hand, 20);
+
+        // 30 chips: the 20 blind comes first, leaving 10 for the ante
+        let mut short = test_player("carol", 30);
+        assert_eq!(PokerState::collect_big_blind_ante(&mut short, 20, 20), 10);
+        assert_eq!(short.chips, 20);
+        let mut shorter = test_player("dave", 15);
+        assert_eq!(PokerState::collect_big_blind_ante(&mut shorter, 20, 20), 0);
+        assert_eq!(shorter.chips, 15);
+    }
+
+    #[test]
+    fn test_straddle_moves_preflop_action() {
+        assert_eq!(PokerState::straddle_seat(2, 6), Some(3));
+        assert_eq!(PokerState::straddle_seat(5, 6), Some(0));
+        assert_eq!(PokerState::straddle_seat(1, 2), None);
+
+        assert_eq!(PokerState::preflop_first_to_act(2, 6, false), 3);
+        assert_eq!(PokerState::preflop_first_to_act(2, 6, true), 4);
+        // Three-handed the straddle is on the button, which acts after it
+        assert_eq!(PokerState::straddle_seat(2, 3), Some(0));
+        assert_eq!(PokerState::preflop_first_to_act(2, 3, true), 1);
+    }
+
+    fn blind_level(big_blind: u64, duration: LevelDuration) -> BlindLevel {
+        BlindLevel {
+            small_blind: big_blind / 2,
+            big_blind,
+            ante: 0,
+            duration,
+        }
+    }
+
+    #[test]
+    fn test_blind_levels_advance_by_time_or_hands() {
+        let schedule = vec![
+            blind_level(20, LevelDuration::Millis(600_000)),
+            blind_level(40, LevelDuration::Hands(10)),
+            blind_level(80, LevelDuration::Hands(10)),
+        ];
+        assert_eq!(
+            PokerState::next_blind_level(&schedule, 0, 1_000, 0, 500_000, 7),
+            None
+        );
+        assert_eq!(
+            PokerState::next_blind_level(&schedule, 0, 1_000, 0, 601_000, 7),
+            Some(1)
+        );
+
+        assert_eq!(
+            PokerState::next_blind_level(&schedule, 1, 0, 7, u64::MAX, 16),
+            None
+        );
+        assert_eq!(
+            PokerState::next_blind_level(&schedule, 1, 0, 7, 0, 17),
+            Some(2)
+        );
+
+        // The last level never expires into nothing
+        assert_eq!(
+            PokerState::next_blind_level(&schedule, 2, 0, 0, u64::MAX, 1_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_blind_schedule_validation() {
+        let rising = vec![
+            blind_level(20, LevelDuration::Hands(10)),
+            blind_level(40, LevelDuration::Hands(10)),
+        ];
+        let falling = vec![rising[1].clone(), rising[0].clone()];
+        assert!(BlindSchedule { levels: rising }.validate().is_ok());
+        assert!(BlindSchedule { levels: falling }.validate().is_err());
+
+        let endless = vec![blind_level(20, LevelDuration::Millis(0))];
+        assert!(BlindSchedule { levels: endless }.validate().is_err());
+    }
+
+    #[test]
+    fn test_blind_schedule_presets() {
+        let standard = BlindSchedule::standard(20);
+        assert!(standard.validate().is_ok());
+        assert_eq!(
+            standard.levels[0],
+            blind_level(20, LevelDuration::Millis(900_000))
+        );
+        assert_eq!(standard.levels[1].big_blind, 30);
+        assert_eq!(standard.levels[1].small_blind, 15);
+        assert_eq!(standard.levels[2].ante, 0);
+        assert_eq!(standard.levels[3].ante, 6);
+
+        let hyper = BlindSchedule::hyper(20);
+        assert_eq!(hyper.levels.len(), standard.levels.len());
+        assert_eq!(hyper.levels[0].duration, LevelDuration::Millis(180_000));
+        assert_eq!(
+            BlindSchedule::turbo(20).levels[5].duration,
+            LevelDuration::Millis(480_000)
+        );
+        let by_hands = BlindSchedule::preset(100, LevelDuration::Hands(12));
+        assert_eq!(by_hands.levels[19].big_blind, 12_000);
+    }
+
+    fn seated(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dead_button_when_small_blind_leaves() {
+        // Last hand: a button, b small blind, c big blind. b busts.
+        let previous = seated(&["a", "b", "c", "d", "e"]);
+        let now = seated(&["a", "c", "d", "e"]);
+        let positions = PokerState::dead_button_positions(&previous, Some("b"), Some("c"), &now, 0);
+        assert_eq!(
+            positions,
+            BlindPositions {
+                dealer: 0,
+                dead_button: true,
+                small_blind: Some(1),
+                big_blind: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_dead_small_blind_when_big_blind_leaves() {
+        // c posted the big blind and left, so d still owes the big blind
+        let previous = seated(&["a", "b", "c", "d", "e"]);
+        let now = seated(&["a", "b", "d", "e"]);
+        let positions = PokerState::dead_button_positions(&previous, Some("b"), Some("c"), &now, 0);
+        assert_eq!(positions.big_blind, 2);
+        assert_eq!(positions.small_blind, None);
+        assert_eq!(positions.dealer, 1);
+        assert!(!positions.dead_button);
+    }
+
+    #[test]
+    fn test_big_blind_never_skips_a_player() {
+        let previous = seated(&["a", "b", "c", "d"]);
+        let now = seated(&["a", "b", "c", "d"]);
+        let positions = PokerState::dead_button_positions(&previous, Some("b"), Some("c"), &now, 0);
+        assert_eq!(
+            positions,
+            PokerState::live_button_positions(1, 4),
+            "with nobody leaving the button just moves on"
+        );
+        assert_eq!(positions.big_blind, 3);
+    }
+
+    #[test]
+    fn test_dead_button_going_heads_up() {
+        // c had the big blind; b busts, leaving c and d
+        let previous = seated(&["b", "c", "d"]);
+        let now = seated(&["c", "d"]);
+        let positions = PokerState::dead_button_positions(&previous, Some("b"), Some("c"), &now, 0);
+        assert_eq!(positions.big_blind, 1);
+        assert_eq!(positions.small_blind, Some(0));
+        assert_eq!(positions.dealer, 0);
+
+        // The first hand falls back to the drawn button
+        let first = PokerState::dead_button_positions(&[], None, None, &now, 1);
+        assert_eq!(first, PokerState::live_button_positions(1, 2));
+    }
+
+    #[test]
+    fn test_heads_up_button_posts_small_blind_and_acts_first_preflop() {
+        for dealer in 0..2 {
+            let big_blind = PokerState::big_blind_seat(dealer, 2);
+            assert_eq!(PokerState::small_blind_seat(dealer, 2), Some(dealer));
+            assert_ne!(big_blind, dealer);
+            assert_eq!(
+                PokerState::preflop_first_to_act(big_blind, 2, false),
+                dealer
+            );
+            // The big blind opens every later street, so the button acts last
+            assert_eq!(PokerState::postflop_first_to_act(dealer, 2), big_blind);
+        }
+        // No straddle heads-up, so the flag cannot move the action
+        assert_eq!(PokerState::preflop_first_to_act(1, 2, true), 0);
+
+        // Three-handed the button is neither blind
+        assert_eq!(PokerState::small_blind_seat(0, 3), Some(1));
+        assert_eq!(PokerState::big_blind_seat(0, 3), 2);
+        assert_eq!(PokerState::preflop_first_to_act(2, 3, false), 0);
+        assert_eq!(PokerState::postflop_first_to_act(0, 3), 1);
+    }
+
+    #[test]
+    fn test_capped_all_in_keeps_the_rest_of_the_stack() {
+        let mut player = test_player("capped", 1_000);
+        player.total_bet_this_hand = 50;
+        let (amount, _) = PokerState::apply_capped_all_in(&mut player, 100, 20, Some(300)).unwrap();
+        assert_eq!(amount, 250);
+        assert_eq!(player.total_bet_this_hand, 300);
+        assert_eq!(player.chips, 750);
+        assert!(player.is_all_in);
+
+        // Without a cap the whole stack goes in
+        let mut player = test_player("uncapped", 1_000);
+        let (amount, _) = PokerState::apply_capped_all_in(&mut player, 100, 20, None).unwrap();
+        assert_eq!(amount, 1_000);
+    }
+
+    #[test]
+    fn test_reaching_the_cap_counts_as_all_in() {
+        let mut player = test_player("caller", 1_000);
+        player.total_bet_this_hand = 200;
+        assert_eq!(PokerState::wager_left(&player, Some(300)), 100);
+        assert!(!PokerState::enforce_wager_cap(&mut player, Some(300)));
+        assert!(!PokerState::enforce_wager_cap(&mut player, None));
+
+        player.total_bet_this_hand = 300;
+        assert!(PokerState::enforce_wager_cap(&mut player, Some(300)));
+        assert_eq!(player.status, PlayerStatus::AllIn);
+        assert_eq!(PokerState::wager_left(&player, Some(300)), 0);
+        // Already all-in, so nothing changes the second time
+        assert!(!PokerState::enforce_wager_cap(&mut player, Some(300)));
+    }
+
+    #[test]
+    fn test_stud_hi_lo_plays_in_high_order() {
+        let players = vec![
+            stud_player("alice", &[(Rank::King, Suit::Hearts)]),
+            stud_player("bob", &[(Rank::Two, Suit::Spades)]),
+        ];
+        let hi_lo = GameVariant::SevenCardStudHiLo;
+        assert!(hi_lo.is_stud() && hi_lo.is_hi_lo());
+        assert_eq!(
+            PokerState::bring_in_player_for_variant(hi_lo, &players),
+            Some(1)
+        );
+        assert_eq!(
+            PokerState::stud_first_to_act_for_variant(hi_lo, &players),
+            Some(0)
+        );
+        assert_eq!(
+            PokerState::bring_in_player_for_variant(GameVariant::Razz, &players),
+            Some(0)
+        );
+        assert_eq!(
+            PokerState::bring_in_player_for_variant(GameVariant::TexasHoldem, &players),
+            None
+        );
+
+        let mut alice = players[0].clone();
+        alice.hole_cards = Some(vec![Card {
+            rank: Rank::Ace,
+            suit: Suit::Clubs,
+        }]);
+        assert_eq!(PokerState::stud_hand(&alice).len(), 2);
+    }
+
+    #[test]
+    fn test_insurance_settles_both_ways() {
+        let offer = InsuranceOffer {
+            player: "alice".to_string(),
+            hand_number: 0,
+            equity: 0.8,
+            coverage: 800,
+            premium: 200,
+            accepted: true,
+        };
+        // Won: the premium comes out of the pot won, and the pool gets back
+        // what it held for the claim plus the premium
+        let mut winner = test_player("alice", 1_600);
+        assert_eq!(
+            PokerState::apply_insurance(&offer, &mut winner, true),
+            (200, 800)
+        );
+        assert_eq!(winner.chips, 1_400);
+
+        // Lost: the claim net of the premium is paid from what was held back
+        let mut loser = test_player("alice", 0);
+        assert_eq!(
+            PokerState::apply_insurance(&offer, &mut loser, false),
+            (600, 0)
+        );
+        assert_eq!(loser.chips, 600);
+    }
+
+    #[test]
+    fn test_rabbit_hunt_fee_from_everyone_dealt_in() {
+        let mut players = vec![
+            dealt_in("alice", true),
+            dealt_in("bob", true),
+            test_player("carol", 500),
+        ];
+        players[1].chips = 3;
+        assert_eq!(PokerState::collect_rabbit_hunt_fees(&mut players, 5), 8);
+        assert_eq!(players[1].chips, 0);
+        // Not dealt in, so not charged
+        assert_eq!(players[2].chips, 500);
+        assert_eq!(PokerState::collect_rabbit_hunt_fees(&mut players, 0), 0);
+    }
+
+    #[test]
+    fn test_unattributed_chips_join_the_main_pot() {
+        let pots = vec![
+            SidePot {
+                amount: 300,
+                eligible_players: vec!["alice".to_string(), "bob".to_string()],
+            },
+            SidePot {
+                amount: 200,
+                eligible_players: vec!["bob".to_string()],
+            },
+        ];
+        let with_fees = PokerState::with_dead_money(pots.clone(), 510);
+        assert_eq!(with_fees[0].amount, 310);
+        assert_eq!(with_fees[1].amount, 200);
+        assert_eq!(PokerState::with_dead_money(pots.clone(), 500), pots);
+    }
+
+    #[test]
+    fn test_short_all_in_call_returns_the_rest_at_settlement() {
+        // Alice bets 500; Bob can only call all-in for 300
+        let mut players = vec![
+            with_bet("alice", 500, 500),
+            with_bet("bob", 0, 300),
+            with_bet("carol", 900, 0),
+        ];
+        players[1].is_all_in = true;
+        players[2].has_folded = true;
+
+        let returned = PokerState::close_betting_round(&mut players, 500);
+        assert_eq!(returned, Some(("alice".to_string(), 200)));
+        assert_eq!(players[0].chips, 700);
+        assert_eq!(players[0].total_bet_this_hand, 300);
+        assert!(players.iter().all(|p| p.current_bet == 0));
+        // Bob stays all-in for the 300 he matched
+        assert!(players[1].is_all_in);
+    }
+
+    #[test]
+    fn test_two_short_all_ins_return_only_above_the_larger() {
+        let mut players = vec![
+            with_bet("alice", 0, 100),
+            with_bet("bob", 0, 250),
+            with_bet("carol", 200, 600),
+        ];
+        players[0].is_all_in = true;
+        players[1].is_all_in = true;
+        let returned = PokerState::close_betting_round(&mut players, 600);
+        assert_eq!(returned, Some(("carol".to_string(), 350)));
+        assert_eq!(players[2].total_bet_this_hand, 250);
+
+        // A fully called street returns nothing but still clears the bets
+        let mut called = vec![with_bet("alice", 100, 200), with_bet("bob", 100, 200)];
+        assert_eq!(PokerState::close_betting_round(&mut called, 200), None);
+        assert!(called.iter().all(|p| p.current_bet == 0));
+    }
+
+    fn pot_of(amount: u64) -> SidePot {
+        SidePot {
+            amount,
+            eligible_players: vec!["alice".to_string(), "bob".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_rake_taken_from_each_pot() {
+        let mut pots = vec![pot_of(1000), pot_of(300)];
+        assert_eq!(PokerState::rake_pots(&mut pots, 5, None), 65);
+        assert_eq!(pots[0].amount, 950);
+        assert_eq!(pots[1].amount, 285);
+    }
+
+    #[test]
+    fn test_rake_cap_reached_in_main_pot_spares_side_pots() {
+        let mut pots = vec![pot_of(1000), pot_of(300)];
+        assert_eq!(PokerState::rake_pots(&mut pots, 5, Some(60)), 60);
+        assert_eq!(pots[0].amount, 950);
+        assert_eq!(pots[1].amount, 290);
+
+        let mut capped = vec![pot_of(5000), pot_of(5000)];
+        assert_eq!(PokerState::rake_pots(&mut capped, 10, Some(300)), 300);
+        assert_eq!(capped[1].amount, 5000);
+    }
+
+    #[test]
+    fn test_rake_rounds_down_on_small_pots() {
+        let mut pots = vec![pot_of(19)];
+        assert_eq!(PokerState::rake_pots(&mut pots, 5, None), 0);
+        assert_eq!(pots[0].amount, 19);
+    }
+
+    #[test]
+    fn test_rake_percent_over_100_rejected() {
+        let config = TableConfig {
+            rake_percent: 101,
+            ..TableConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_rakeback_owed_is_percent_of_rake_paid() {
+        assert_eq!(PokerState::rakeback_owed(200, 30, 0), 60);
+        assert_eq!(PokerState::rakeback_owed(200, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_rakeback_owed_excludes_earlier_claims() {
+        assert_eq!(PokerState::rakeback_owed(200, 30, 60), 0);
+        // Rounding that was short on the first claim catches up later
+        assert_eq!(PokerState::rakeback_owed(15, 30, 0), 4);
+        assert_eq!(PokerState::rakeback_owed(25, 30, 4), 3);
+    }
+
+    fn min_raise_check(
+        action: PlayerAction,
+        chips: u64,
+        current_bet: u64,
+        full_bet_level: u64,
+        has_acted: bool,
+    ) -> Result<(), PokerError> {
+        let player = test_player("alice", chips);
+        PokerState::check_min_raise(
+            &action,
+            &player,
+            current_bet,
+            100,
+            full_bet_level,
+            has_acted,
+        )
+    }
+
+    #[test]
+    fn test_raise_must_add_the_minimum() {
+        // Facing a bet of 300 with a minimum raise of 100
+        assert!(min_raise_check(PlayerAction::Raise(400), 1000, 300, 300, false).is_ok());
+        assert!(matches!(
+            min_raise_check(PlayerAction::Raise(350), 1000, 300, 300, false),
+            Err(PokerError::InvalidAction { .. })
+        ));
+        assert!(min_raise_check(PlayerAction::Raise(300), 1000, 300, 300, false).is_err());
+    }
+
+    #[test]
+    fn test_short_all_in_raise_allowed() {
+        assert!(min_raise_check(PlayerAction::Raise(350), 350, 300, 300, false).is_ok());
+        assert!(min_raise_check(PlayerAction::Bet(60), 60, 0, 0, false).is_ok());
+        assert!(min_raise_check(PlayerAction::Bet(60), 500, 0, 0, false).is_err());
+    }
+
+    #[test]
+    fn test_bet_and_raise_match_whether_there_is_a_bet() {
+        assert!(min_raise_check(PlayerAction::Bet(200), 1000, 100, 100, false).is_err());
+        assert!(min_raise_check(PlayerAction::Raise(200), 1000, 0, 0, false).is_err());
+    }
+
+    #[test]
+    fn test_short_all_in_does_not_reopen_for_players_who_acted() {
+        // Alice called 300, then a short all-in made it 350
+        let mut player = test_player("alice", 700);
+        player.current_bet = 300;
+        let raise = PlayerAction::Raise(500);
+        assert!(PokerState::check_min_raise(&raise, &player, 350, 100, 300, true).is_err());
+        // She has not acted yet, or a full raise to 400 came after her call
+        assert!(PokerState::check_min_raise(&raise, &player, 350, 100, 300, false).is_ok());
+        assert!(PokerState::check_min_raise(&raise, &player, 400, 100, 400, true).is_ok());
+    }
+
+    #[test]
+    fn test_only_full_raises_set_the_next_minimum() {
+        assert_eq!(PokerState::full_raise_size(100, 400, 100), Some(300));
+        assert_eq!(PokerState::full_raise_size(400, 450, 300), None);
+        assert_eq!(PokerState::full_raise_size(0, 100, 100), Some(100));
+    }
+
+    fn situation(structure: BettingStructure, pot: u64, current_bet: u64) -> BettingSituation {
+        BettingSituation {
+            structure,
+            pot,
+            current_bet,
+            min_raise: 100,
+            full_bet_level: current_bet,
+            bet_size: 100,
+            raises_this_street: 0,
+            wager_cap: None,
+        }
+    }
+
+    #[test]
+    fn test_legal_actions_with_no_bet() {
+        let player = test_player("alice", 1000);
+        let actions = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::NoLimit, 300, 0),
+            false,
+        );
+        assert!(actions.can_check);
+        assert_eq!(actions.call_amount, None);
+        assert_eq!(actions.bet_range, Some((100, 1000)));
+        assert_eq!(actions.raise_range, None);
+        assert!(actions.can_all_in);
+    }
+
+    #[test]
+    fn test_legal_actions_facing_a_bet() {
+        let mut player = test_player("alice", 1000);
+        player.current_bet = 50;
+        let actions = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::NoLimit, 450, 300),
+            false,
+        );
+        assert!(!actions.can_check);
+        assert_eq!(actions.call_amount, Some(250));
+        assert_eq!(actions.bet_range, None);
+        assert_eq!(actions.raise_range, Some((400, 1050)));
+    }
+
+    #[test]
+    fn test_legal_actions_short_stack_can_only_call_all_in() {
+        let player = test_player("alice", 200);
+        let actions = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::NoLimit, 450, 300),
+            false,
+        );
+        assert_eq!(actions.call_amount, Some(200));
+        assert_eq!(actions.raise_range, None);
+        assert!(actions.can_all_in);
+
+        // Enough to raise, but not by the full minimum
+        let player = test_player("alice", 350);
+        let actions = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::NoLimit, 450, 300),
+            false,
+        );
+        assert_eq!(actions.raise_range, Some((350, 350)));
+    }
+
+    #[test]
+    fn test_legal_actions_pot_limit_and_fixed_limit_maximums() {
+        let player = test_player("alice", 5000);
+        let pot_limit = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::PotLimit, 400, 100),
+            false,
+        );
+        // Calling 100 makes the pot 500, so the raise can total 100 + 500
+        assert_eq!(pot_limit.raise_range, Some((200, 600)));
+        assert!(!pot_limit.can_all_in);
+
+        let fixed = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::FixedLimit, 400, 100),
+            false,
+        );
+        assert_eq!(fixed.raise_range, Some((200, 200)));
+
+        let mut capped = situation(BettingStructure::FixedLimit, 400, 100);
+        capped.raises_this_street = PokerState::FIXED_LIMIT_RAISE_CAP;
+        let actions = PokerState::compute_legal_actions(&player, &capped, false);
+        assert_eq!(actions.raise_range, None);
+        assert_eq!(actions.call_amount, Some(100));
+    }
+
+    #[test]
+    fn test_legal_actions_after_short_all_in() {
+        let mut player = test_player("alice", 700);
+        player.current_bet = 300;
+        let mut short = situation(BettingStructure::NoLimit, 900, 350);
+        short.full_bet_level = 300;
+        let actions = PokerState::compute_legal_actions(&player, &short, true);
+        assert_eq!(actions.call_amount, Some(50));
+        assert_eq!(actions.raise_range, None);
+        assert!(!actions.can_all_in);
+    }
+
+    #[test]
+    fn test_folded_player_has_no_legal_actions() {
+        let mut player = test_player("alice", 700);
+        player.has_folded = true;
+        let actions = PokerState::compute_legal_actions(
+            &player,
+            &situation(BettingStructure::NoLimit, 300, 0),
+            false,
+        );
+        assert_eq!(actions, LegalActions::default());
+    }
+
+    #[test]
+    fn test_betting_over_when_everyone_left_is_all_in() {
+        let mut alice = test_player("alice", 0);
+        alice.is_all_in = true;
+        let mut bob = test_player("bob", 0);
+        bob.is_all_in = true;
+        let mut carol = test_player("carol", 500);
+        carol.has_folded = true;
+        assert!(PokerState::betting_is_over(&[
+            alice.clone(),
+            bob,
+            carol.clone()
+        ]));
+
+        // One covering stack has nobody to bet against either
+        carol.has_folded = false;
+        assert!(PokerState::betting_is_over(&[alice.clone(), carol.clone()]));
+
+        let dave = test_player("dave", 500);
+        assert!(!PokerState::betting_is_over(&[alice, carol, dave]));
+    }
+
+    #[test]
+    fn test_betting_not_over_once_hand_is_won() {
+        let alice = test_player("alice", 500);
+        let mut bob = test_player("bob", 500);
+        bob.has_folded = true;
+        assert!(!PokerState::betting_is_over(&[alice, bob]));
+    }
+
+    #[test]
+    fn test_board_streets_run_to_showdown() {
+        let mut phase = GamePhase::PreFlop;
+        let mut dealt = Vec::new();
+        while let Some(next) = PokerState::next_board_street(&phase) {
+            dealt.push(PokerState::next_community_cards_count(&phase));
+            phase = next;
+        }
+        assert_eq!(phase, GamePhase::Showdown);
+        assert_eq!(dealt, vec![3, 1, 1, 0]);
+        assert_eq!(PokerState::next_board_street(&GamePhase::ThirdStreet), None);
+    }
+
+    fn holding(name: &str, auto_muck: bool) -> PokerPlayer {
+        let mut player = test_player(name, 1000);
+        player.auto_muck = auto_muck;
+        player.hole_cards = Some(vec![
+            Card {
+                rank: Rank::Two,
+                suit: Suit::Clubs,
+            },
+            Card {
+                rank: Rank::Seven,
+                suit: Suit::Diamonds,
+            },
+        ]);
+        player
+    }
+
+    fn revealed(players: &[PokerPlayer], shown: &[&str]) -> Vec<String> {
+        let winners = vec!["alice".to_string()];
+        let shown: Vec<String> = shown.iter().map(|name| name.to_string()).collect();
+        PokerState::showdown_reveals(players, &winners, &shown)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_muck_hides_losing_hands() {
+        let players = vec![
+            holding("alice", true),
+            holding("bob", true),
+            holding("carol", false),
+        ];
+        assert_eq!(revealed(&players, &[]), vec!["alice", "carol"]);
+    }
+
+    #[test]
+    fn test_shown_hand_revealed_despite_auto_muck() {
+        let players = vec![holding("alice", true), holding("bob", true)];
+        assert_eq!(revealed(&players, &["bob"]), vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_showdown_turn_expires_after_timeout() {
+        let timeout = TableConfig::default().showdown_reveal_timeout_ms;
+        assert!(!PokerState::showdown_turn_expired(
+            1_000,
+            1_000 + timeout,
+            timeout
+        ));
+        assert!(PokerState::showdown_turn_expired(
+            1_000,
+            1_001 + timeout,
+            timeout
+        ));
+        // A clock set after `now` never counts as expired
+        assert!(!PokerState::showdown_turn_expired(5_000, 1_000, timeout));
+    }
+
+    fn committed(name: &str) -> PokerPlayer {
+        let mut player = test_player(name, 1000);
+        player.hole_cards_commitment = Some(CardCommitment {
+            cards_hash: [0; 32],
+            salt: String::new(),
+            committed_at: 0,
+            player_address: name.to_string(),
+            hand_number: 1,
+        });
+        player
+    }
+
+    #[test]
+    fn test_hand_cancelled_when_commitments_never_arrive() {
+        let players = vec![committed("alice"), test_player("bob", 1000)];
+        assert_eq!(
+            PokerState::hand_cancel_reason(&players, false, 60_001, 60_000),
+            Some(CancelReason::CommitmentsMissing)
+        );
+        // Still within the timeout
+        assert_eq!(
+            PokerState::hand_cancel_reason(&players, false, 60_000, 60_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hand_cancelled_when_all_but_one_time_out() {
+        let mut players = vec![committed("alice"), committed("bob"), committed("carol")];
+        for player in players.iter_mut().skip(1) {
+            player.consecutive_timeouts = 1;
+            player.has_folded = true;
+        }
+        assert_eq!(
+            PokerState::hand_cancel_reason(&players, false, 0, 60_000),
+            Some(CancelReason::EveryoneElseTimedOut)
+        );
+        // Once someone has chosen to act the hand plays out normally
+        assert_eq!(
+            PokerState::hand_cancel_reason(&players, true, 0, 60_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_healthy_hand_cannot_be_cancelled() {
+        let players = vec![committed("alice"), committed("bob")];
+        assert_eq!(
+            PokerState::hand_cancel_reason(&players, false, 120_000, 60_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_top_up_allowed_to_max_buy_in() {
+        assert!(PokerState::validate_top_up(1500, 500, 2000).is_ok());
+        assert!(PokerState::validate_top_up(1500, 501, 2000).is_err());
+        assert!(PokerState::validate_top_up(2000, 1, 2000).is_err());
+    }
+
+    #[test]
+    fn test_top_up_needs_positive_amount() {
+        assert!(PokerState::validate_top_up(100, 0, 2000).is_err());
+    }
+
+    fn sitting_out(name: &str) -> PokerPlayer {
+        let mut player = test_player(name, 1000);
+        player.status = PlayerStatus::SittingOut;
+        player
+    }
+
+    #[test]
+    fn test_blinds_missed_while_sitting_out() {
+        let mut player = sitting_out("alice");
+        assert!(!PokerState::miss_blind(&mut player, true));
+        assert!(!PokerState::miss_blind(&mut player, false));
+        assert!(player.missed_big_blind && player.missed_small_blind);
+
+        let mut active = test_player("bob", 1000);
+        PokerState::miss_blind(&mut active, true);
+        assert!(!active.missed_big_blind);
+    }
+
+    #[test]
+    fn test_returning_player_posts_dead_small_and_live_big_blind() {
+        let mut player = sitting_out("alice");
+        PokerState::miss_blind(&mut player, true);
+        PokerState::miss_blind(&mut player, false);
+        PokerState::post_missed_blinds(&mut player, 10, 20);
+        player.status = PlayerStatus::Active;
+
+        let mut players = vec![player];
+        assert_eq!(PokerState::compute_dead_money(&mut players, 0), 30);
+        // Only the big blind is live
+        assert_eq!(players[0].current_bet, 20);
+        assert_eq!(players[0].total_bet_this_hand, 30);
+        assert!(!players[0].missed_big_blind);
+    }
+
+    #[test]
+    fn test_waiting_player_returns_on_big_blind() {
+        let mut player = sitting_out("alice");
+        PokerState::miss_blind(&mut player, false);
+        player.waiting_for_big_blind = true;
+        // The small blind passes them again
+        assert!(!PokerState::miss_blind(&mut player, false));
+        assert!(PokerState::miss_blind(&mut player, true));
+        assert_eq!(player.status, PlayerStatus::Active);
+        assert!(!player.missed_small_blind);
+        assert_eq!(player.missed_blind_due, 0);
+    }
+
+    #[test]
+    fn test_players_at_first_hand_join_free() {
+        let mut player = PokerPlayer::new("addr", "alice", 1000, 0);
+        PokerState::seat_newcomer(&mut player, false, true, 20);
+        assert_eq!(player.status, PlayerStatus::Active);
+        assert_eq!(player.live_blind_due, 0);
+    }
+
+    #[test]
+    fn test_newcomer_posts_big_blind_or_waits_for_it() {
+        let mut posting = PokerPlayer::new("addr", "alice", 1000, 3);
+        PokerState::seat_newcomer(&mut posting, true, false, 20);
+        assert_eq!(posting.status, PlayerStatus::Active);
+        assert_eq!(posting.live_blind_due, 20);
+
+        let mut waiting = PokerPlayer::new("addr", "bob", 1000, 4);
+        PokerState::seat_newcomer(&mut waiting, true, true, 20);
+        assert_eq!(waiting.status, PlayerStatus::SittingOut);
+        // Not dealt in as the small blind passes, then in on the big blind
+        assert!(!PokerState::miss_blind(&mut waiting, false));
+        assert!(PokerState::miss_blind(&mut waiting, true));
+        assert_eq!(waiting.status, PlayerStatus::Active);
+        assert_eq!(waiting.live_blind_due, 0);
+    }
+
+    #[test]
+    fn test_buy_in_limits() {
+        assert!(PokerState::check_buy_in(400, 400, 2000).is_ok());
+        assert_eq!(
+            PokerState::check_buy_in(399, 400, 2000),
+            Err(PokerError::BuyInOutOfRange {
+                min: 400,
+                max: 2000
+            })
+        );
+        assert!(PokerState::check_buy_in(2001, 400, 2000).is_err());
+    }
+
+    #[test]
+    fn test_each_missed_big_blind_counts_an_orbit() {
+        let mut player = sitting_out("alice");
+        for _ in 0..3 {
+            PokerState::miss_blind(&mut player, false);
+            PokerState::miss_blind(&mut player, true);
+        }
+        assert_eq!(player.big_blinds_missed, 3);
+        assert!(PokerState::sat_out_too_long(&player, 3));
+        assert!(!PokerState::sat_out_too_long(&player, 4));
+    }
+
+    #[test]
+    fn test_active_player_never_removed_as_idle() {
+        let mut player = test_player("alice", 1000);
+        player.big_blinds_missed = 10;
+        assert!(!PokerState::sat_out_too_long(&player, 3));
+    }
+
+    fn waiting(name: &str) -> WaitingPlayer {
+        WaitingPlayer {
+            address: name.to_string(),
+            player_name: name.to_string(),
+            buy_in: 1000,
+            wait_for_big_blind: false,
+        }
+    }
+
+    #[test]
+    fn test_waiting_list_is_first_come_first_served() {
+        let mut list = Vec::new();
+        assert_eq!(
+            PokerState::add_to_waiting_list(&mut list, waiting("alice")),
+            Ok(0)
+        );
+        assert_eq!(
+            PokerState::add_to_waiting_list(&mut list, waiting("bob")),
+            Ok(1)
+        );
+        assert_eq!(list[0].player_name, "alice");
+    }
+
+    #[test]
+    fn test_player_waits_in_line_once() {
+        let mut list = vec![waiting("alice")];
+        assert_eq!(
+            PokerState::add_to_waiting_list(&mut list, waiting("alice")),
+            Err(PokerError::DuplicatePlayer("alice".to_string()))
+        );
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_choose_seat() {
+        let taken = [0, 2];
+        assert_eq!(PokerState::choose_seat(&taken, 4, None), Ok(1));
+        assert_eq!(PokerState::choose_seat(&taken, 4, Some(3)), Ok(3));
+        assert_eq!(
+            PokerState::choose_seat(&taken, 4, Some(2)),
+            Err(PokerError::SeatUnavailable(2))
+        );
+        assert_eq!(
+            PokerState::choose_seat(&taken, 4, Some(4)),
+            Err(PokerError::SeatUnavailable(4))
+        );
+        assert_eq!(
+            PokerState::choose_seat(&[0, 1], 2, None),
+            Err(PokerError::TableFull)
+        );
+    }
+
+    #[test]
+    fn test_tournament_entry() {
+        let config = top_three_tournament(100, 0);
+        assert!(PokerState::check_tournament_entry(&config, 100, false).is_ok());
+        assert!(PokerState::check_tournament_entry(&config, 150, false).is_err());
+        assert_eq!(
+            PokerState::check_tournament_entry(&config, 100, true),
+            Err(PokerError::RegistrationClosed)
+        );
+    }
+
+    #[test]
+    fn test_smaller_stack_busts_first() {
+        let mut alice = test_player("alice", 0);
+        alice.total_bet_this_hand = 800;
+        let mut bob = test_player("bob", 0);
+        bob.total_bet_this_hand = 300;
+        let carol = test_player("carol", 2600);
+        assert_eq!(
+            PokerState::bust_order(&[alice, bob, carol]),
+            vec!["bob".to_string(), "alice".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_knockout_credited_to_winner_of_the_busted_players_pot() {
+        let mut players = vec![
+            with_bet("alice", 0, 300),
+            with_bet("bob", 0, 100),
+            with_bet("carol", 1200, 300),
+            with_bet("dave", 900, 300),
+        ];
+        players[3].has_folded = true;
+        let pot = |player: &str| HandEvent::PotAwarded {
+            player: player.to_string(),
+            amount: 400,
+        };
+        let shown = |player: &str, hand_rank| HandEvent::ShowdownRevealed {
+            player: player.to_string(),
+            hand_rank,
+        };
+        let events = vec![
+            shown("alice", HandRank::OnePair(Rank::Ace, vec![])),
+            shown("bob", HandRank::HighCard(vec![Rank::King])),
+            shown("carol", HandRank::Flush(vec![Rank::Ace])),
+            pot("carol"),
+        ];
+        assert_eq!(
+            PokerState::eliminated_by(&players, "alice", &events),
+            vec!["carol".to_string()]
+        );
+        assert_eq!(
+            PokerState::eliminated_by(&players, "bob", &events),
+            vec!["carol".to_string()]
+        );
+
+        // A chopped pot shares the knockout
+        players[0].chips = 600;
+        let chopped = vec![
+            shown("alice", HandRank::Flush(vec![Rank::Ace])),
+            shown("bob", HandRank::HighCard(vec![Rank::King])),
+            shown("carol", HandRank::Flush(vec![Rank::Ace])),
+            pot("alice"),
+            pot("carol"),
+        ];
+        assert_eq!(
+            PokerState::eliminated_by(&players, "bob", &chopped),
+            vec!["alice".to_string(), "carol".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_rotate_from() {
+        let names = seat_names(3);
+        assert_eq!(
+            PokerState::rotate_from(&names, 1),
+            vec!["player1", "player2", "player0"]
+        );
+        assert_eq!(PokerState::rotate_from(&names, 3), names);
+        assert!(PokerState::rotate_from(&[], 2).is_empty());
     }
 }